@@ -1,11 +1,55 @@
 //! ADB Client Abstraction Layer
 //!
-//! This module provides a high-level interface to ADB operations using the adb_client crate.
-//! It abstracts away the complexity of working with ADB and provides typed command execution.
+//! This module provides a high-level interface to ADB operations on top of a pluggable
+//! [`AdbBackend`]. It abstracts away the complexity of working with ADB and provides
+//! typed command execution.
 
-use adb_client::{ADBDeviceExt, ADBServer};
-use std::io;
-use std::net::{Ipv4Addr, SocketAddrV4};
+pub mod address;
+pub mod backend;
+pub mod binary;
+pub mod client;
+pub mod dumpsys;
+mod host;
+pub mod input;
+pub mod mock;
+pub mod packages;
+pub mod ps;
+pub mod screen;
+pub mod screencap;
+pub mod shell;
+pub mod stats;
+pub mod sync;
+pub mod timeout;
+
+pub use address::{ServerAddress, Transport};
+pub use backend::{AdbBackend, BackendKind};
+pub use binary::BinaryBackend;
+pub use client::ClientBackend;
+pub use dumpsys::{BatteryStatus, ConnectivityState, MemInfo};
+pub use host::CancelToken;
+pub use input::{DeviceInput, Keycode};
+pub use mock::MockBackend;
+pub use packages::PackageEntry;
+pub use ps::{ProcessEntry, ProcessSample, Signal};
+pub use screen::{RecordOptions, StreamConfig, MAX_DURATION};
+pub use shell::ShellOutput;
+pub use stats::{format_size, DeviceSample};
+pub use sync::RemoteEntry;
+pub use timeout::{CommandTimeouts, Watchdog};
+
+use screen::parse_screen_size;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Staging directory for files pushed before `pm install`
+const REMOTE_TMP_DIR: &str = "/data/local/tmp";
+
+/// Result text of a shell command that printed nothing
+const NO_OUTPUT: &str = "Command executed successfully (no output)";
 
 /// Result type for ADB operations
 pub type AdbResult<T> = Result<T, AdbError>;
@@ -19,6 +63,22 @@ pub enum AdbError {
     IoError(io::Error),
     ParseError(String),
     NoDeviceSelected,
+    /// The command was cancelled before it finished
+    Cancelled,
+    /// Package manager rejected an install, e.g. `Failure [INSTALL_FAILED_VERSION_DOWNGRADE]`
+    InstallFailed {
+        code: String,
+        message: Option<String>,
+    },
+    /// A destructive command was refused because the manager is read-only
+    ReadOnly,
+    /// A shell command exited with a non-zero status; `output` holds stdout and stderr
+    ExitStatus {
+        code: u8,
+        output: String,
+    },
+    /// The command did not finish within its timeout and was cancelled
+    Timeout(Duration),
 }
 
 impl std::fmt::Display for AdbError {
@@ -30,21 +90,49 @@ impl std::fmt::Display for AdbError {
             AdbError::IoError(e) => write!(f, "IO error: {}", e),
             AdbError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             AdbError::NoDeviceSelected => write!(f, "No device selected"),
+            AdbError::Cancelled => write!(f, "Cancelled"),
+            AdbError::InstallFailed { code, message } => match message {
+                Some(message) => write!(f, "Install failed: {} ({})", code, message),
+                None => write!(f, "Install failed: {}", code),
+            },
+            AdbError::ReadOnly => write!(f, "Refused in read-only mode"),
+            AdbError::ExitStatus { code, output } => match output.trim() {
+                "" => write!(f, "Exited with status {}", code),
+                output => write!(f, "Exited with status {}: {}", code, output),
+            },
+            AdbError::Timeout(timeout) => {
+                write!(f, "Timed out after {}s", timeout.as_secs_f32())
+            }
         }
     }
 }
 
 impl std::error::Error for AdbError {}
 
-impl From<io::Error> for AdbError {
-    fn from(e: io::Error) -> Self {
-        AdbError::IoError(e)
+impl AdbError {
+    /// Process exit code used by the command line interface
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AdbError::ConnectionError(_) => 3,
+            AdbError::DeviceNotFound => 4,
+            AdbError::NoDeviceSelected => 5,
+            AdbError::CommandFailed(_) | AdbError::ExitStatus { .. } => 6,
+            AdbError::InstallFailed { .. } => 7,
+            AdbError::ParseError(_) => 8,
+            AdbError::IoError(_) => 9,
+            AdbError::ReadOnly => 10,
+            AdbError::Timeout(_) => 11,
+            AdbError::Cancelled => 130,
+        }
     }
 }
 
-impl From<adb_client::RustADBError> for AdbError {
-    fn from(e: adb_client::RustADBError) -> Self {
-        AdbError::CommandFailed(e.to_string())
+impl From<io::Error> for AdbError {
+    fn from(e: io::Error) -> Self {
+        if e.kind() == io::ErrorKind::Interrupted {
+            return AdbError::Cancelled;
+        }
+        AdbError::IoError(e)
     }
 }
 
@@ -66,6 +154,12 @@ pub enum AdbCommand {
     },
     InstallPackage {
         apk_path: String,
+        options: InstallOptions,
+    },
+    /// Install a base APK together with its splits in a single session
+    InstallSplitPackage {
+        apk_paths: Vec<String>,
+        options: InstallOptions,
     },
     UninstallPackage {
         package_name: String,
@@ -73,8 +167,23 @@ pub enum AdbCommand {
     ClearPackageData {
         package_name: String,
     },
+    /// Enable a package, or disable it for the current user
+    SetPackageEnabled {
+        package_name: String,
+        enabled: bool,
+    },
+    /// Start the package's launcher activity
+    LaunchPackage {
+        package_name: String,
+    },
+    /// Browse packages interactively; the TUI opens the package manager, other callers get a table
+    ManagePackages,
 
     // System commands
+    /// Reboot through adbd's `reboot:` service, which works without root
+    Reboot {
+        target: RebootTarget,
+    },
     GetBatteryInfo,
     GetMemoryInfo,
     GetCpuInfo,
@@ -82,21 +191,62 @@ pub enum AdbCommand {
     GetSystemLog {
         lines: usize,
     },
+    /// Follow `logcat -v threadtime`; the TUI streams it, other callers get a snapshot
+    StreamLogcat,
+    /// Poll battery, CPU, memory and network; the TUI opens the live dashboard, other callers get one sample
+    Dashboard,
 
     // Network commands
     GetNetworkInfo,
     GetWifiStatus,
 
+    // Wireless commands
+    /// Restart adbd on the device listening on a TCP port
+    TcpIp {
+        port: u16,
+    },
+    /// Connect to a device over the network; an empty address prompts in the TUI
+    Connect {
+        address: String,
+    },
+    /// Disconnect one network device, or all of them
+    Disconnect {
+        address: Option<String>,
+    },
+    /// Pair with an Android 11+ device using its wireless debugging code
+    Pair {
+        address: String,
+        code: String,
+    },
+
     // Screen commands
     TakeScreenshot,
+    /// Record the screen to a local MP4; the TUI shows progress and a stop key
+    RecordScreen {
+        options: RecordOptions,
+    },
+    /// Mirror the screen inside the terminal
+    MirrorScreen,
     GetScreenResolution,
 
     // Process commands
     ListProcesses,
+    /// Browse processes interactively; the TUI opens the process manager, other callers get a table
+    ManageProcesses,
+    /// Send a signal to a process
+    KillProcess {
+        pid: u32,
+        signal: Signal,
+    },
     ForceStop {
         package_name: String,
     },
 
+    // File commands
+    ListDirectory {
+        path: String,
+    },
+
     // Shell commands
     Shell {
         command: String,
@@ -106,6 +256,272 @@ pub enum AdbCommand {
     GetAdbVersion,
 }
 
+impl AdbCommand {
+    /// Every [`AdbCommand::name`], for validating config keys
+    pub const NAMES: [&'static str; 37] = [
+        "list_devices",
+        "get_device_state",
+        "get_serial_number",
+        "list_packages",
+        "get_package_info",
+        "install_package",
+        "install_split_package",
+        "uninstall_package",
+        "clear_package_data",
+        "set_package_enabled",
+        "launch_package",
+        "manage_packages",
+        "reboot",
+        "get_battery_info",
+        "get_memory_info",
+        "get_cpu_info",
+        "get_device_properties",
+        "get_system_log",
+        "stream_logcat",
+        "dashboard",
+        "get_network_info",
+        "get_wifi_status",
+        "tcp_ip",
+        "connect",
+        "disconnect",
+        "pair",
+        "take_screenshot",
+        "record_screen",
+        "mirror_screen",
+        "get_screen_resolution",
+        "list_processes",
+        "manage_processes",
+        "kill_process",
+        "force_stop",
+        "list_directory",
+        "shell",
+        "get_adb_version",
+    ];
+
+    /// Name used in the `[timeouts]` config table, e.g. `get_battery_info`
+    pub fn name(&self) -> &'static str {
+        match self {
+            AdbCommand::ListDevices => "list_devices",
+            AdbCommand::GetDeviceState => "get_device_state",
+            AdbCommand::GetSerialNumber => "get_serial_number",
+            AdbCommand::ListPackages { .. } => "list_packages",
+            AdbCommand::GetPackageInfo { .. } => "get_package_info",
+            AdbCommand::InstallPackage { .. } => "install_package",
+            AdbCommand::InstallSplitPackage { .. } => "install_split_package",
+            AdbCommand::UninstallPackage { .. } => "uninstall_package",
+            AdbCommand::ClearPackageData { .. } => "clear_package_data",
+            AdbCommand::SetPackageEnabled { .. } => "set_package_enabled",
+            AdbCommand::LaunchPackage { .. } => "launch_package",
+            AdbCommand::ManagePackages => "manage_packages",
+            AdbCommand::Reboot { .. } => "reboot",
+            AdbCommand::GetBatteryInfo => "get_battery_info",
+            AdbCommand::GetMemoryInfo => "get_memory_info",
+            AdbCommand::GetCpuInfo => "get_cpu_info",
+            AdbCommand::GetDeviceProperties => "get_device_properties",
+            AdbCommand::GetSystemLog { .. } => "get_system_log",
+            AdbCommand::StreamLogcat => "stream_logcat",
+            AdbCommand::Dashboard => "dashboard",
+            AdbCommand::GetNetworkInfo => "get_network_info",
+            AdbCommand::GetWifiStatus => "get_wifi_status",
+            AdbCommand::TcpIp { .. } => "tcp_ip",
+            AdbCommand::Connect { .. } => "connect",
+            AdbCommand::Disconnect { .. } => "disconnect",
+            AdbCommand::Pair { .. } => "pair",
+            AdbCommand::TakeScreenshot => "take_screenshot",
+            AdbCommand::RecordScreen { .. } => "record_screen",
+            AdbCommand::MirrorScreen => "mirror_screen",
+            AdbCommand::GetScreenResolution => "get_screen_resolution",
+            AdbCommand::ListProcesses => "list_processes",
+            AdbCommand::ManageProcesses => "manage_processes",
+            AdbCommand::KillProcess { .. } => "kill_process",
+            AdbCommand::ForceStop { .. } => "force_stop",
+            AdbCommand::ListDirectory { .. } => "list_directory",
+            AdbCommand::Shell { .. } => "shell",
+            AdbCommand::GetAdbVersion => "get_adb_version",
+        }
+    }
+
+    /// How long the command may run before it is cancelled, unless configured otherwise
+    ///
+    /// Recording and mirroring end on their own or when the user stops them.
+    pub fn default_timeout(&self) -> Option<Duration> {
+        let secs = match self {
+            AdbCommand::RecordScreen { .. } | AdbCommand::MirrorScreen => return None,
+            AdbCommand::InstallPackage { .. } | AdbCommand::InstallSplitPackage { .. } => 300,
+            AdbCommand::Reboot { .. } | AdbCommand::Shell { .. } => 60,
+            AdbCommand::GetMemoryInfo
+            | AdbCommand::GetSystemLog { .. }
+            | AdbCommand::StreamLogcat
+            | AdbCommand::ManagePackages
+            | AdbCommand::TakeScreenshot
+            | AdbCommand::Connect { .. }
+            | AdbCommand::Pair { .. } => 30,
+            _ => 15,
+        };
+        Some(Duration::from_secs(secs))
+    }
+
+    /// Whether the command reboots, wipes, uninstalls or kills something
+    ///
+    /// The TUI asks before running these and read-only mode refuses them.
+    pub fn is_destructive(&self) -> bool {
+        match self {
+            AdbCommand::Reboot { .. }
+            | AdbCommand::UninstallPackage { .. }
+            | AdbCommand::ClearPackageData { .. }
+            | AdbCommand::ForceStop { .. }
+            | AdbCommand::TcpIp { .. } => true,
+            AdbCommand::SetPackageEnabled { enabled, .. } => !enabled,
+            AdbCommand::KillProcess { signal, .. } => *signal != Signal::Cont,
+            AdbCommand::Shell { command } => is_destructive_shell(command),
+            _ => false,
+        }
+    }
+}
+
+/// Characters that end one simple command and start the next
+const SHELL_SEPARATORS: [char; 7] = [';', '&', '|', '\n', '(', ')', '`'];
+
+/// Words that may precede the program of a simple command
+const SHELL_KEYWORDS: [&str; 10] = [
+    "if", "then", "else", "elif", "while", "until", "do", "!", "{", "}",
+];
+
+/// Programs that run a command given in their arguments, e.g. `su -c reboot`
+const SHELL_WRAPPERS: [&str; 14] = [
+    "su", "sh", "bash", "mksh", "ash", "toybox", "busybox", "env", "nohup", "exec", "nice", "time",
+    "timeout", "xargs",
+];
+
+/// Whether any command in a shell command line is destructive
+///
+/// Commands chained with `;`, `&&`, `||`, `|` or in subshells are checked one by one,
+/// by program name and subcommand. Programs are compared by basename, and wrappers
+/// such as `su`, `sh -c`, `env` or `toybox` are looked through.
+pub fn is_destructive_shell(command: &str) -> bool {
+    command
+        .split(SHELL_SEPARATORS)
+        .any(|part| is_destructive_words(&shell_words(part)))
+}
+
+/// Split a simple command into words, removing quotes and backslashes
+fn shell_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'') | (None, '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, '\\') => {}
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+fn is_destructive_words(words: &[String]) -> bool {
+    // Variable assignments and keywords come before the program
+    let words: Vec<&str> = words
+        .iter()
+        .map(String::as_str)
+        .skip_while(|word| word.contains('=') || SHELL_KEYWORDS.contains(word))
+        .collect();
+    let Some((program, args)) = words.split_first() else {
+        return false;
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+
+    match (program, args) {
+        ("reboot" | "rm" | "rmdir" | "kill" | "killall" | "pkill" | "wipe", _) => true,
+        ("logcat", args) => args.iter().any(|arg| matches!(*arg, "-c" | "--clear")),
+        ("pm", [sub, ..]) | ("cmd", ["package", sub, ..]) => matches!(
+            *sub,
+            "uninstall" | "clear" | "disable" | "disable-user" | "hide" | "revoke"
+        ),
+        ("am", [sub, ..]) => matches!(*sub, "force-stop" | "kill" | "kill-all" | "crash"),
+        ("svc", ["power", sub, ..]) => matches!(*sub, "reboot" | "shutdown"),
+        ("setprop", [name, ..]) => *name == "sys.powerctl",
+        // Options and users of a wrapper look like commands too, so every suffix of
+        // its arguments is tried; quoted scripts (`sh -c '...'`) are split again
+        (program, args) if SHELL_WRAPPERS.contains(&program) => {
+            args.join(" ").split(SHELL_SEPARATORS).any(|part| {
+                let words = shell_words(part);
+                (0..words.len()).any(|start| is_destructive_words(&words[start..]))
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Output of [`AdbManager::execute_output`]
+///
+/// Commands with a typed parser keep their raw text next to the parsed value.
+/// Output the parser doesn't understand comes back as [`AdbOutput::Text`].
+#[derive(Debug, Clone)]
+pub enum AdbOutput {
+    Text(String),
+    Battery {
+        status: BatteryStatus,
+        raw: String,
+    },
+    Memory {
+        info: MemInfo,
+        raw: String,
+    },
+    Connectivity {
+        state: ConnectivityState,
+        raw: String,
+    },
+    /// A shell command with its separate streams and exit status
+    Shell {
+        output: ShellOutput,
+        raw: String,
+    },
+}
+
+impl AdbOutput {
+    /// Raw command output
+    pub fn text(&self) -> &str {
+        match self {
+            AdbOutput::Text(raw)
+            | AdbOutput::Battery { raw, .. }
+            | AdbOutput::Memory { raw, .. }
+            | AdbOutput::Connectivity { raw, .. }
+            | AdbOutput::Shell { raw, .. } => raw,
+        }
+    }
+
+    pub fn into_text(self) -> String {
+        match self {
+            AdbOutput::Text(raw)
+            | AdbOutput::Battery { raw, .. }
+            | AdbOutput::Memory { raw, .. }
+            | AdbOutput::Connectivity { raw, .. }
+            | AdbOutput::Shell { raw, .. } => raw,
+        }
+    }
+
+    /// Whether there is a parsed value to show in a panel besides the text
+    pub fn is_structured(&self) -> bool {
+        !matches!(self, AdbOutput::Text(_) | AdbOutput::Shell { .. })
+    }
+
+    /// Exit status of a shell command, when the device reported one
+    pub fn exit_code(&self) -> Option<u8> {
+        match self {
+            AdbOutput::Shell { output, .. } => output.exit_code,
+            _ => None,
+        }
+    }
+}
+
 /// Package filter options
 #[derive(Debug, Clone)]
 pub enum PackageFilter {
@@ -116,8 +532,110 @@ pub enum PackageFilter {
     Disabled, // -d
 }
 
+/// Mode a device reboots into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RebootTarget {
+    #[default]
+    System,
+    Bootloader,
+    Recovery,
+    Sideload,
+    /// Userspace fastboot (fastbootd)
+    Fastboot,
+}
+
+impl RebootTarget {
+    pub const ALL: [RebootTarget; 5] = [
+        RebootTarget::System,
+        RebootTarget::Bootloader,
+        RebootTarget::Recovery,
+        RebootTarget::Sideload,
+        RebootTarget::Fastboot,
+    ];
+
+    /// Name used on the command line and in the `reboot:` service
+    pub fn name(&self) -> &'static str {
+        match self {
+            RebootTarget::System => "system",
+            RebootTarget::Bootloader => "bootloader",
+            RebootTarget::Recovery => "recovery",
+            RebootTarget::Sideload => "sideload",
+            RebootTarget::Fastboot => "fastboot",
+        }
+    }
+
+    /// Device service performing the reboot
+    pub fn service(&self) -> String {
+        match self {
+            RebootTarget::System => "reboot:".to_string(),
+            target => format!("reboot:{}", target.name()),
+        }
+    }
+
+    /// State the device shows up in once it is back, if adb can see it there
+    ///
+    /// The bootloader and fastbootd only talk to `fastboot`.
+    pub fn wait_state(&self) -> Option<&'static str> {
+        match self {
+            RebootTarget::System => Some("device"),
+            RebootTarget::Recovery => Some("recovery"),
+            RebootTarget::Sideload => Some("sideload"),
+            RebootTarget::Bootloader | RebootTarget::Fastboot => None,
+        }
+    }
+}
+
+impl std::str::FromStr for RebootTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RebootTarget::ALL
+            .into_iter()
+            .find(|target| target.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "expected one of system, bootloader, recovery, sideload, fastboot; got {}",
+                    s
+                )
+            })
+    }
+}
+
+/// Flags passed to `pm install`
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// Reinstall an existing app, keeping its data (`-r`)
+    pub replace: bool,
+    /// Allow a lower version code to replace the installed one (`-d`)
+    pub allow_downgrade: bool,
+    /// Grant all runtime permissions listed in the manifest (`-g`)
+    pub grant_permissions: bool,
+    /// Allow APKs marked `android:testOnly` (`-t`)
+    pub allow_test: bool,
+}
+
+impl InstallOptions {
+    /// Command line flags for `pm install` / `pm install-create`
+    pub fn flags(&self) -> String {
+        let mut flags = String::new();
+        if self.replace {
+            flags.push_str(" -r");
+        }
+        if self.allow_downgrade {
+            flags.push_str(" -d");
+        }
+        if self.grant_permissions {
+            flags.push_str(" -g");
+        }
+        if self.allow_test {
+            flags.push_str(" -t");
+        }
+        flags
+    }
+}
+
 /// Device information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct DeviceInfo {
     pub serial: String,
     pub state: String,
@@ -125,38 +643,122 @@ pub struct DeviceInfo {
     pub device: Option<String>,
 }
 
+impl DeviceInfo {
+    /// Whether the device is ready to accept commands
+    pub fn is_online(&self) -> bool {
+        self.state == "device"
+    }
+
+    /// How the device is attached (USB, network or emulator)
+    pub fn transport(&self) -> Transport {
+        Transport::from_serial(&self.serial)
+    }
+
+    /// Human readable name, preferring the model over the raw serial
+    pub fn display_name(&self) -> String {
+        match &self.model {
+            Some(model) => format!("{} ({})", model.replace('_', " "), self.serial),
+            None => self.serial.clone(),
+        }
+    }
+}
+
 /// ADB Manager - handles connection and command execution
 #[derive(Debug)]
 pub struct AdbManager {
-    server: Option<ADBServer>,
+    backend: Box<dyn AdbBackend>,
+    /// Address as configured, shown in the UI
+    server_address: ServerAddress,
     selected_device: Option<String>,
+    screenshot_dir: PathBuf,
+    cancel: CancelToken,
+    /// Refuse destructive commands
+    read_only: bool,
+    timeouts: CommandTimeouts,
 }
 
 impl AdbManager {
-    /// Create a new ADB manager
+    /// Create a new ADB manager talking to the default server address
     pub fn new() -> Self {
+        let server_address = ServerAddress::default();
+        let server_addr = server_address
+            .resolve()
+            .unwrap_or_else(|_| SocketAddr::from((Ipv4Addr::LOCALHOST, server_address.port)));
         Self {
-            server: None,
+            server_address,
+            ..Self::with_backend(Box::new(ClientBackend::new(server_addr)))
+        }
+    }
+
+    /// Create a manager on top of `backend`, e.g. a [`MockBackend`] in tests
+    pub fn with_backend(backend: Box<dyn AdbBackend>) -> Self {
+        Self {
+            backend,
+            server_address: ServerAddress::default(),
             selected_device: None,
+            screenshot_dir: screencap::default_screenshot_dir(),
+            cancel: CancelToken::default(),
+            read_only: false,
+            timeouts: CommandTimeouts::default(),
         }
     }
 
-    /// Connect to ADB server
-    pub fn connect(&mut self) -> AdbResult<()> {
-        let socket_addr = SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 5037);
-        let server = ADBServer::new(socket_addr);
-        self.server = Some(server);
+    /// Copy of this manager for a background command, aborted through `cancel`
+    ///
+    /// What a cancel interrupts depends on the backend; see [`AdbBackend::for_worker`].
+    pub fn for_worker(&self, cancel: CancelToken) -> Self {
+        Self {
+            backend: self.backend.for_worker(cancel.clone()),
+            server_address: self.server_address.clone(),
+            selected_device: self.selected_device.clone(),
+            screenshot_dir: self.screenshot_dir.clone(),
+            cancel,
+            read_only: self.read_only,
+            timeouts: self.timeouts.clone(),
+        }
+    }
+
+    /// Create a manager for the ADB server at `address`, resolving host names up front
+    pub fn with_server(address: ServerAddress) -> AdbResult<Self> {
+        let server_addr = address.resolve().map_err(|e| {
+            AdbError::ConnectionError(format!("Cannot resolve ADB server {}: {}", address, e))
+        })?;
+        Ok(Self {
+            server_address: address,
+            ..Self::with_backend(Box::new(ClientBackend::new(server_addr)))
+        })
+    }
+
+    /// Switch to another transport for the same server
+    pub fn set_backend(&mut self, kind: BackendKind) -> AdbResult<()> {
+        self.backend = match kind {
+            BackendKind::Client => {
+                let server_addr = self.server_address.resolve().map_err(|e| {
+                    AdbError::ConnectionError(format!(
+                        "Cannot resolve ADB server {}: {}",
+                        self.server_address, e
+                    ))
+                })?;
+                Box::new(ClientBackend::new(server_addr))
+            }
+            BackendKind::Binary => Box::new(BinaryBackend::new(self.server_address.clone())),
+            BackendKind::Mock => Box::new(MockBackend::demo()),
+        };
         Ok(())
     }
 
-    /// Ensure server is connected
-    fn get_server(&mut self) -> AdbResult<&mut ADBServer> {
-        if self.server.is_none() {
-            self.connect()?;
-        }
-        self.server
-            .as_mut()
-            .ok_or(AdbError::ConnectionError("Failed to connect".to_string()))
+    pub fn backend_kind(&self) -> BackendKind {
+        self.backend.kind()
+    }
+
+    /// ADB server this manager talks to
+    pub fn server_address(&self) -> &ServerAddress {
+        &self.server_address
+    }
+
+    /// Check that the ADB server answers
+    pub fn connect(&mut self) -> AdbResult<()> {
+        self.backend.version().map(|_| ())
     }
 
     /// Set the selected device
@@ -164,15 +766,170 @@ impl AdbManager {
         self.selected_device = Some(serial);
     }
 
-    /// Get the selected device serial
-    fn get_selected_device(&self) -> AdbResult<&str> {
-        self.selected_device
-            .as_deref()
-            .ok_or(AdbError::NoDeviceSelected)
+    /// Refuse destructive commands with [`AdbError::ReadOnly`]
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
     }
 
-    /// Execute an ADB command
+    /// Deadlines for [`AdbManager::execute`] and [`AdbManager::execute_output`]
+    pub fn set_timeouts(&mut self, timeouts: CommandTimeouts) {
+        self.timeouts = timeouts;
+    }
+
+    pub fn timeouts(&self) -> &CommandTimeouts {
+        &self.timeouts
+    }
+
+    /// Run `f`, failing with [`AdbError::Timeout`] when it takes longer than `timeout`
+    ///
+    /// Requests go through a copy of the backend that is cancelled at the deadline, or
+    /// as soon as this manager is. What a cancel interrupts depends on the backend.
+    pub fn with_timeout<T>(
+        &mut self,
+        timeout: Option<Duration>,
+        f: impl FnOnce(&mut Self) -> AdbResult<T>,
+    ) -> AdbResult<T> {
+        let Some(timeout) = timeout else {
+            return f(self);
+        };
+        let token = CancelToken::default();
+        let deadline = timeout::Deadline::start(timeout, self.cancel.clone(), token.clone());
+        let worker = self.backend.for_worker(token);
+        let backend = std::mem::replace(&mut self.backend, worker);
+        let result = f(self);
+        self.backend = backend;
+
+        let expired = deadline.finish();
+        match result {
+            Err(_) if expired => Err(AdbError::Timeout(timeout)),
+            result => result,
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Serial of the device commands are currently sent to, if any
+    pub fn selected_device(&self) -> Option<&str> {
+        self.selected_device.as_deref()
+    }
+
+    /// Local directory [`AdbCommand::TakeScreenshot`] saves into
+    pub fn screenshot_dir(&self) -> &Path {
+        &self.screenshot_dir
+    }
+
+    /// Change where screenshots are saved
+    pub fn set_screenshot_dir(&mut self, dir: PathBuf) {
+        self.screenshot_dir = dir;
+    }
+
+    /// Query the server for all attached devices
+    pub fn devices(&mut self) -> AdbResult<Vec<DeviceInfo>> {
+        self.backend.devices()
+    }
+
+    /// Report the attached devices, then again on every change, until cancelled
+    pub fn track_devices(&mut self, on_change: &mut dyn FnMut(Vec<DeviceInfo>)) -> AdbResult<()> {
+        self.backend.track_devices(on_change)
+    }
+
+    /// Serial of the device commands should run on
+    ///
+    /// The selected serial always wins. Without a selection we only fall back to
+    /// the only attached device, so commands never land on an arbitrary device.
+    fn resolve_serial(&mut self) -> AdbResult<String> {
+        if let Some(serial) = &self.selected_device {
+            return Ok(serial.clone());
+        }
+
+        let devices = self.devices()?;
+        match devices.as_slice() {
+            [] => Err(AdbError::DeviceNotFound),
+            [device] => Ok(device.serial.clone()),
+            _ => Err(AdbError::NoDeviceSelected),
+        }
+    }
+
+    /// List a directory on the device
+    pub fn list_remote_dir(&mut self, path: &str) -> AdbResult<Vec<RemoteEntry>> {
+        let serial = self.resolve_serial()?;
+        self.backend.list_dir(&serial, path)
+    }
+
+    /// Whether `path` on the device is a directory, following symlinks
+    pub fn is_remote_dir(&mut self, path: &str) -> AdbResult<bool> {
+        let output = self.shell_command(&format!("[ -d {} ] && echo dir", shell_quote(path)))?;
+        Ok(output.trim() == "dir")
+    }
+
+    /// Size of a file on the device in bytes
+    pub fn file_size(&mut self, remote_path: &str) -> AdbResult<u64> {
+        let serial = self.resolve_serial()?;
+        self.backend.file_size(&serial, remote_path)
+    }
+
+    /// Copy `input` to a file on the device
+    pub fn push(&mut self, input: &mut dyn Read, remote_path: &str) -> AdbResult<()> {
+        let serial = self.resolve_serial()?;
+        self.backend.push(&serial, input, remote_path)
+    }
+
+    /// Copy a file on the device to `out`
+    pub fn pull(&mut self, remote_path: &str, out: &mut dyn Write) -> AdbResult<()> {
+        let serial = self.resolve_serial()?;
+        self.backend.pull(&serial, remote_path, out)
+    }
+
+    /// Read the figures shown on the live dashboard
+    pub fn sample_device(&mut self) -> AdbResult<DeviceSample> {
+        // Some files are unreadable on some devices; the parser skips what is missing
+        let output = self.shell_output(stats::SAMPLE_COMMAND)?;
+        Ok(DeviceSample::parse(
+            &output.stdout,
+            std::time::Instant::now(),
+        ))
+    }
+
+    /// Read the process list and CPU counters for the process manager
+    pub fn sample_processes(&mut self) -> AdbResult<ProcessSample> {
+        let output = self.shell_output(ps::SAMPLE_COMMAND)?;
+        Ok(ProcessSample::parse(&output.stdout))
+    }
+
+    /// Recursively delete a file or directory on the device
+    pub fn delete_remote(&mut self, path: &str) -> AdbResult<String> {
+        if self.read_only {
+            return Err(AdbError::ReadOnly);
+        }
+        self.shell_command(&format!("rm -rf {}", shell_quote(path)))
+    }
+
+    /// Rename or move a file on the device
+    pub fn rename_remote(&mut self, from: &str, to: &str) -> AdbResult<String> {
+        if self.read_only {
+            return Err(AdbError::ReadOnly);
+        }
+        self.shell_command(&format!("mv {} {}", shell_quote(from), shell_quote(to)))
+    }
+
+    /// Execute an ADB command within its timeout
     pub fn execute(&mut self, command: AdbCommand) -> AdbResult<String> {
+        let timeout = self.timeouts.get(&command);
+        self.with_timeout(timeout, |manager| manager.run(command))
+    }
+
+    /// Execute an ADB command, parsing the output of commands that have a typed form
+    pub fn execute_output(&mut self, command: AdbCommand) -> AdbResult<AdbOutput> {
+        let timeout = self.timeouts.get(&command);
+        self.with_timeout(timeout, |manager| manager.run_output(command))
+    }
+
+    fn run(&mut self, command: AdbCommand) -> AdbResult<String> {
+        if self.read_only && command.is_destructive() {
+            return Err(AdbError::ReadOnly);
+        }
         match command {
             AdbCommand::ListDevices => self.list_devices(),
             AdbCommand::GetDeviceState => self.get_device_state(),
@@ -182,29 +939,98 @@ impl AdbManager {
                 filter,
             } => self.list_packages(include_path, filter),
             AdbCommand::GetPackageInfo { package_name } => self.get_package_info(&package_name),
-            AdbCommand::InstallPackage { apk_path } => self.install_package(&apk_path),
+            AdbCommand::InstallPackage { apk_path, options } => {
+                self.install_package(&apk_path, &options)
+            }
+            AdbCommand::InstallSplitPackage { apk_paths, options } => {
+                self.install_split_package(&apk_paths, &options)
+            }
             AdbCommand::UninstallPackage { package_name } => self.uninstall_package(&package_name),
             AdbCommand::ClearPackageData { package_name } => self.clear_package_data(&package_name),
+            AdbCommand::SetPackageEnabled {
+                package_name,
+                enabled,
+            } => self.set_package_enabled(&package_name, enabled),
+            AdbCommand::LaunchPackage { package_name } => self.launch_package(&package_name),
+            AdbCommand::ManagePackages => self.package_table(),
+            AdbCommand::Reboot { target } => {
+                let serial = self.reboot_device(target)?;
+                Ok(format!("Rebooting {} into {}", serial, target.name()))
+            }
             AdbCommand::GetBatteryInfo => self.get_battery_info(),
             AdbCommand::GetMemoryInfo => self.get_memory_info(),
             AdbCommand::GetCpuInfo => self.get_cpu_info(),
             AdbCommand::GetDeviceProperties => self.get_device_properties(),
             AdbCommand::GetSystemLog { lines } => self.get_system_log(lines),
+            AdbCommand::StreamLogcat => self.shell_command("logcat -d -v threadtime"),
+            AdbCommand::Dashboard => self.sample_device().map(|sample| sample.summary()),
             AdbCommand::GetNetworkInfo => self.get_network_info(),
             AdbCommand::GetWifiStatus => self.get_wifi_status(),
+            AdbCommand::TcpIp { port } => self.tcpip(port),
+            AdbCommand::Connect { address } => self.connect_device(&address),
+            AdbCommand::Disconnect { address } => self.disconnect_device(address.as_deref()),
+            AdbCommand::Pair { address, code } => self.pair_device(&address, &code),
             AdbCommand::TakeScreenshot => self.take_screenshot(),
+            AdbCommand::RecordScreen { .. } => Err(AdbError::CommandFailed(
+                "Screen recording only runs in the TUI or through `droidtui run record`"
+                    .to_string(),
+            )),
+            AdbCommand::MirrorScreen => Err(AdbError::CommandFailed(
+                "Terminal mirroring only runs in the TUI".to_string(),
+            )),
             AdbCommand::GetScreenResolution => self.get_screen_resolution(),
             AdbCommand::ListProcesses => self.list_processes(),
+            AdbCommand::ManageProcesses => self.sample_processes().map(|sample| sample.table()),
+            AdbCommand::KillProcess { pid, signal } => self.kill_process(pid, signal),
             AdbCommand::ForceStop { package_name } => self.force_stop(&package_name),
+            AdbCommand::ListDirectory { path } => self.list_directory(&path),
             AdbCommand::Shell { command } => self.shell_command(&command),
             AdbCommand::GetAdbVersion => self.get_adb_version(),
         }
     }
 
+    fn run_output(&mut self, command: AdbCommand) -> AdbResult<AdbOutput> {
+        if self.read_only && command.is_destructive() {
+            return Err(AdbError::ReadOnly);
+        }
+        Ok(match command {
+            AdbCommand::GetBatteryInfo => {
+                let raw = self.get_battery_info()?;
+                match BatteryStatus::parse(&raw) {
+                    Ok(status) => AdbOutput::Battery { status, raw },
+                    Err(_) => AdbOutput::Text(raw),
+                }
+            }
+            AdbCommand::GetMemoryInfo => {
+                let raw = self.get_memory_info()?;
+                match MemInfo::parse(&raw) {
+                    Ok(info) => AdbOutput::Memory { info, raw },
+                    Err(_) => AdbOutput::Text(raw),
+                }
+            }
+            AdbCommand::GetNetworkInfo => {
+                let raw = self.get_network_info()?;
+                match ConnectivityState::parse(&raw) {
+                    Ok(state) => AdbOutput::Connectivity { state, raw },
+                    Err(_) => AdbOutput::Text(raw),
+                }
+            }
+            // A failing shell command still shows what it printed
+            AdbCommand::Shell { command } => {
+                let output = self.shell_output(&command)?;
+                let raw = match output.text() {
+                    text if text.trim().is_empty() && output.success() => NO_OUTPUT.to_string(),
+                    text => text,
+                };
+                AdbOutput::Shell { output, raw }
+            }
+            command => AdbOutput::Text(self.run(command)?),
+        })
+    }
+
     /// List all connected devices
     fn list_devices(&mut self) -> AdbResult<String> {
-        let server = self.get_server()?;
-        let devices = server.devices()?;
+        let devices = self.devices()?;
 
         if devices.is_empty() {
             return Ok("No devices found.\n\nMake sure:\n- Device is connected via USB\n- USB debugging is enabled\n- Device is authorized".to_string());
@@ -212,12 +1038,7 @@ impl AdbManager {
 
         let mut output = String::from("List of devices attached:\n");
         for device in devices {
-            output.push_str(&format!("{}\t{:?}\n", device.identifier, device.state));
-
-            // Auto-select first device if none selected
-            if self.selected_device.is_none() {
-                self.selected_device = Some(device.identifier.clone());
-            }
+            output.push_str(&format!("{}\t{}\n", device.serial, device.state));
         }
 
         Ok(output)
@@ -225,13 +1046,12 @@ impl AdbManager {
 
     /// Get device state
     fn get_device_state(&mut self) -> AdbResult<String> {
-        let serial = self.get_selected_device()?.to_string();
-        let server = self.get_server()?;
-        let devices = server.devices()?;
+        let serial = self.resolve_serial()?;
+        let devices = self.devices()?;
 
         for device in devices {
-            if device.identifier == serial {
-                return Ok(format!("Device state: {:?}", device.state));
+            if device.serial == serial {
+                return Ok(format!("Device state: {}", device.state));
             }
         }
 
@@ -240,7 +1060,7 @@ impl AdbManager {
 
     /// Get device serial number
     fn get_serial_number(&mut self) -> AdbResult<String> {
-        let serial = self.get_selected_device()?;
+        let serial = self.resolve_serial()?;
         Ok(format!("Serial number: {}", serial))
     }
 
@@ -260,33 +1080,175 @@ impl AdbManager {
         self.shell_command(&command)
     }
 
+    /// List installed packages with their APK path, origin and enabled state
+    pub fn packages(&mut self) -> AdbResult<Vec<PackageEntry>> {
+        let all = self.shell_command("pm list packages -f")?;
+        let user = self.shell_command("pm list packages -3")?;
+        let disabled = self.shell_command("pm list packages -d")?;
+        Ok(packages::build_package_entries(&all, &user, &disabled))
+    }
+
+    /// Format [`AdbManager::packages`] as a plain table
+    fn package_table(&mut self) -> AdbResult<String> {
+        let mut output = String::new();
+        for package in self.packages()? {
+            output.push_str(&format!(
+                "{:<6} {:<8} {}  {}\n",
+                if package.system { "system" } else { "user" },
+                if package.enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                },
+                package.name,
+                package.apk_path.unwrap_or_default()
+            ));
+        }
+        Ok(output)
+    }
+
     /// Get package information
     fn get_package_info(&mut self, package_name: &str) -> AdbResult<String> {
+        check_package_name(package_name)?;
         let command = format!("dumpsys package {}", package_name);
         self.shell_command(&command)
     }
 
     /// Install package
-    fn install_package(&mut self, _apk_path: &str) -> AdbResult<String> {
-        // Note: adb_client doesn't have direct install support in the API we're using
-        // We'll use shell command as fallback
-        Err(AdbError::CommandFailed(
-            "Install via adb_client not yet implemented. Use shell command.".to_string(),
+    ///
+    /// Pushes the APK to the device over the sync protocol and installs it with `pm install`.
+    fn install_package(&mut self, apk_path: &str, options: &InstallOptions) -> AdbResult<String> {
+        let remote_path = format!("{}/droidtui-install.apk", REMOTE_TMP_DIR);
+        self.push_apk(apk_path, &remote_path)?;
+
+        let command = format!("pm install{} \"{}\"", options.flags(), remote_path);
+        let result = self.pm_command(&command);
+        let _ = self.shell_command(&format!("rm -f \"{}\"", remote_path));
+
+        result?;
+        Ok(format!("Installed {}", apk_path))
+    }
+
+    /// Install split APKs through a `pm install-create` session
+    fn install_split_package(
+        &mut self,
+        apk_paths: &[String],
+        options: &InstallOptions,
+    ) -> AdbResult<String> {
+        if apk_paths.is_empty() {
+            return Err(AdbError::CommandFailed("No APKs given".to_string()));
+        }
+
+        let mut total_size = 0;
+        for apk_path in apk_paths {
+            total_size += apk_metadata(apk_path)?.len();
+        }
+
+        let created = self.pm_command(&format!(
+            "pm install-create{} -S {}",
+            options.flags(),
+            total_size
+        ))?;
+        let session = parse_install_session(&created)?;
+
+        let mut remote_paths = Vec::new();
+        let result = self.write_install_session(session, apk_paths, &mut remote_paths);
+
+        if result.is_err() {
+            let _ = self.shell_command(&format!("pm install-abandon {}", session));
+        }
+        for remote_path in &remote_paths {
+            let _ = self.shell_command(&format!("rm -f \"{}\"", remote_path));
+        }
+
+        result?;
+        Ok(format!(
+            "Installed {} APKs in session {}",
+            apk_paths.len(),
+            session
         ))
     }
 
+    /// Stage every APK into an open install session and commit it
+    fn write_install_session(
+        &mut self,
+        session: u32,
+        apk_paths: &[String],
+        remote_paths: &mut Vec<String>,
+    ) -> AdbResult<()> {
+        for (index, apk_path) in apk_paths.iter().enumerate() {
+            let remote_path = format!("{}/droidtui-split-{}.apk", REMOTE_TMP_DIR, index);
+            let size = self.push_apk(apk_path, &remote_path)?;
+            remote_paths.push(remote_path.clone());
+
+            self.pm_command(&format!(
+                "pm install-write -S {} {} {}.apk \"{}\"",
+                size, session, index, remote_path
+            ))?;
+        }
+
+        self.pm_command(&format!("pm install-commit {}", session))
+            .map(drop)
+    }
+
+    /// Run a package manager command, reporting `Failure [...]` as [`AdbError::InstallFailed`]
+    fn pm_command(&mut self, command: &str) -> AdbResult<String> {
+        let output = self.shell_output(command)?;
+        check_install_output(&output.text())?;
+        output.into_result()
+    }
+
+    /// Push a local APK to the device, returning its size in bytes
+    fn push_apk(&mut self, apk_path: &str, remote_path: &str) -> AdbResult<u64> {
+        let size = apk_metadata(apk_path)?.len();
+        let mut file = File::open(apk_path)?;
+        self.push(&mut file, remote_path)?;
+        Ok(size)
+    }
+
     /// Uninstall package
     fn uninstall_package(&mut self, package_name: &str) -> AdbResult<String> {
+        check_package_name(package_name)?;
         let command = format!("pm uninstall {}", package_name);
         self.shell_command(&command)
     }
 
     /// Clear package data
     fn clear_package_data(&mut self, package_name: &str) -> AdbResult<String> {
+        check_package_name(package_name)?;
         let command = format!("pm clear {}", package_name);
         self.shell_command(&command)
     }
 
+    /// Enable or disable a package
+    fn set_package_enabled(&mut self, package_name: &str, enabled: bool) -> AdbResult<String> {
+        check_package_name(package_name)?;
+        let command = if enabled {
+            format!("pm enable {}", package_name)
+        } else {
+            format!("pm disable-user --user 0 {}", package_name)
+        };
+        self.shell_command(&command)
+    }
+
+    /// Launch a package through its launcher intent
+    fn launch_package(&mut self, package_name: &str) -> AdbResult<String> {
+        check_package_name(package_name)?;
+        let command = format!(
+            "monkey -p {} -c android.intent.category.LAUNCHER 1",
+            package_name
+        );
+        let output = self.shell_output(&command)?;
+        if output.text().contains("No activities found") {
+            return Err(AdbError::CommandFailed(format!(
+                "{} has no launcher activity",
+                package_name
+            )));
+        }
+        output.into_result()?;
+        Ok(format!("Launched {}", package_name))
+    }
+
     /// Get battery information
     fn get_battery_info(&mut self) -> AdbResult<String> {
         self.shell_command("dumpsys battery")
@@ -323,9 +1285,163 @@ impl AdbManager {
         self.shell_command("ip addr show wlan0")
     }
 
-    /// Take screenshot
+    /// Reboot the target device into `target`, returning its serial
+    ///
+    /// Once this returns the device has left.
+    pub fn reboot_device(&mut self, target: RebootTarget) -> AdbResult<String> {
+        if self.read_only {
+            return Err(AdbError::ReadOnly);
+        }
+        let serial = self.resolve_serial()?;
+        self.backend.reboot(&serial, target)?;
+        Ok(serial)
+    }
+
+    /// Block until `serial` is back after rebooting into `target` (`adb wait-for-device`)
+    pub fn wait_for_device(&mut self, serial: &str, target: RebootTarget) -> AdbResult<String> {
+        let Some(state) = target.wait_state() else {
+            return Ok(format!(
+                "{} is in {} mode; use fastboot to talk to it",
+                serial,
+                target.name()
+            ));
+        };
+        self.backend.wait_for(serial, state)?;
+        Ok(format!("{} is back in {} mode", serial, target.name()))
+    }
+
+    /// Restart adbd in TCP mode on the target device
+    fn tcpip(&mut self, port: u16) -> AdbResult<String> {
+        let serial = self.resolve_serial()?;
+        self.tcpip_device(&serial, port)
+    }
+
+    /// Restart adbd on `serial` in TCP mode and report the address to connect to
+    pub fn tcpip_device(&mut self, serial: &str, port: u16) -> AdbResult<String> {
+        // Look up the Wi-Fi address first; the USB transport drops once adbd restarts
+        let mut output = Vec::new();
+        let address = self
+            .backend
+            .shell(serial, "ip -f inet addr show wlan0", &mut output)
+            .ok()
+            .and_then(|_| parse_inet_address(&String::from_utf8_lossy(&output)));
+        self.backend.tcpip(serial, port)?;
+
+        Ok(match address {
+            Some(ip) => format!(
+                "adbd is listening on port {}.\nConnect with {}:{}",
+                port, ip, port
+            ),
+            None => format!(
+                "adbd is listening on port {}.\nConnect with <device ip>:{}",
+                port, port
+            ),
+        })
+    }
+
+    /// Connect to a network device (`host:connect:`)
+    pub fn connect_device(&mut self, address: &str) -> AdbResult<String> {
+        if address.is_empty() {
+            return Err(AdbError::CommandFailed("No address given".to_string()));
+        }
+        let reply = self.backend.connect(address)?;
+        if reply.starts_with("connected to") || reply.starts_with("already connected to") {
+            Ok(reply)
+        } else {
+            Err(AdbError::ConnectionError(reply))
+        }
+    }
+
+    /// Disconnect a network device, or every network device when `address` is `None`
+    pub fn disconnect_device(&mut self, address: Option<&str>) -> AdbResult<String> {
+        let reply = self.backend.disconnect(address)?;
+        // Forget the selection if its transport just went away
+        let dropped = self
+            .selected_device
+            .as_deref()
+            .is_some_and(|serial| match address {
+                Some(address) => serial == address,
+                None => Transport::from_serial(serial) == Transport::Tcp,
+            });
+        if dropped {
+            self.selected_device = None;
+        }
+        Ok(if reply.is_empty() {
+            "Disconnected everything".to_string()
+        } else {
+            reply
+        })
+    }
+
+    /// Pair with a device using its wireless debugging code (`host:pair:`)
+    pub fn pair_device(&mut self, address: &str, code: &str) -> AdbResult<String> {
+        let reply = self.backend.pair(address, code)?;
+        if reply.starts_with("Successfully paired") {
+            Ok(reply)
+        } else {
+            Err(AdbError::ConnectionError(reply))
+        }
+    }
+
+    /// Capture the screen as PNG bytes
+    pub fn capture_screenshot(&mut self) -> AdbResult<Vec<u8>> {
+        let serial = self.resolve_serial()?;
+        let mut output = Vec::new();
+        self.backend.exec(&serial, "screencap -p", &mut output)?;
+        screencap::extract_png(&output)
+            .map(|png| png.to_vec())
+            .ok_or_else(|| AdbError::ParseError("screencap did not return a PNG".to_string()))
+    }
+
+    /// Stream `screenrecord` H.264 output to `out` until the time limit or cancellation
+    ///
+    /// Runs through the `exec:` service so the stream is not mangled by a terminal.
+    pub fn record_screen(
+        &mut self,
+        config: &StreamConfig,
+        time_limit: Duration,
+        out: &mut impl io::Write,
+    ) -> AdbResult<()> {
+        let serial = self.resolve_serial()?;
+        let command = format!(
+            "screenrecord --output-format=h264 --size {}x{} --bit-rate {} --time-limit {} -",
+            config.width,
+            config.height,
+            config.bitrate,
+            time_limit.as_secs().max(1)
+        );
+        self.backend.exec(&serial, &command, out)
+    }
+
+    /// Size of the screen in pixels, as the display currently uses it
+    pub fn screen_size(&mut self) -> AdbResult<(usize, usize)> {
+        let output = self.shell_bytes("wm size")?;
+        parse_screen_size(&String::from_utf8_lossy(&output))
+            .ok_or_else(|| AdbError::ParseError("Unexpected `wm size` output".to_string()))
+    }
+
+    /// Replay a tap, swipe, key or text on the device
+    pub fn send_input(&mut self, input: &DeviceInput) -> AdbResult<()> {
+        // `input` only prints when it fails
+        let output = self.shell_bytes(&input.shell_command())?;
+        let output = String::from_utf8_lossy(&output);
+        if output.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(AdbError::CommandFailed(output.trim().to_string()))
+        }
+    }
+
+    /// Token cancelling this manager's requests, e.g. to stop a recording from another thread
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// Capture a screenshot and save it to the screenshot directory
     fn take_screenshot(&mut self) -> AdbResult<String> {
-        self.shell_command("screencap -p /sdcard/screenshot.png")
+        let png = self.capture_screenshot()?;
+        let path = screencap::save_screenshot(&self.screenshot_dir, &png)?;
+        Ok(format!("Screenshot saved to {}", path.display()))
     }
 
     /// Get screen resolution
@@ -340,28 +1456,74 @@ impl AdbManager {
         self.shell_command("ps")
     }
 
+    /// Send `signal` to `pid`; `kill` prints nothing unless it fails
+    fn kill_process(&mut self, pid: u32, signal: Signal) -> AdbResult<String> {
+        let output = self.shell_bytes(&format!("kill -{} {}", signal.name(), pid))?;
+        let output = String::from_utf8_lossy(&output);
+        if output.trim().is_empty() {
+            Ok(format!("Sent SIG{} to {}", signal.name(), pid))
+        } else {
+            Err(AdbError::CommandFailed(output.trim().to_string()))
+        }
+    }
+
     /// Force stop application
     fn force_stop(&mut self, package_name: &str) -> AdbResult<String> {
+        check_package_name(package_name)?;
         let command = format!("am force-stop {}", package_name);
         self.shell_command(&command)
     }
 
-    /// Execute shell command
-    fn shell_command(&mut self, command: &str) -> AdbResult<String> {
-        let server = self.get_server()?;
-        let mut device = server
-            .get_device()
-            .map_err(|e| AdbError::ConnectionError(format!("Failed to get device: {}", e)))?;
+    /// List a device directory as text
+    fn list_directory(&mut self, path: &str) -> AdbResult<String> {
+        let entries = self.list_remote_dir(path)?;
+        let mut output = format!("{}:\n", path);
+        for entry in entries {
+            let kind = if entry.is_dir() {
+                'd'
+            } else if entry.is_symlink() {
+                'l'
+            } else {
+                '-'
+            };
+            output.push_str(&format!("{} {:>10}  {}\n", kind, entry.size, entry.name));
+        }
+        Ok(output)
+    }
 
+    /// Execute a shell command and return its raw output
+    fn shell_bytes(&mut self, command: &str) -> AdbResult<Vec<u8>> {
         let mut output = Vec::new();
-        device
-            .shell_command(&[command], &mut output)
-            .map_err(|e| AdbError::CommandFailed(format!("Shell command failed: {}", e)))?;
+        self.shell_stream(command, &mut output)?;
+        Ok(output)
+    }
+
+    /// Run a shell command, writing its output to `out` as it arrives
+    pub fn shell_stream(&mut self, command: &str, out: &mut impl io::Write) -> AdbResult<()> {
+        let serial = self.resolve_serial()?;
+        self.backend.shell(&serial, command, out)
+    }
+
+    /// Run a shell command, keeping stdout, stderr and the exit status apart
+    pub fn shell_output(&mut self, command: &str) -> AdbResult<ShellOutput> {
+        let serial = self.resolve_serial()?;
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let exit_code = self
+            .backend
+            .shell_status(&serial, command, &mut stdout, &mut stderr)?;
+        Ok(ShellOutput {
+            stdout: String::from_utf8_lossy(&stdout).to_string(),
+            stderr: String::from_utf8_lossy(&stderr).to_string(),
+            exit_code,
+        })
+    }
 
-        let result = String::from_utf8_lossy(&output).to_string();
+    /// Execute shell command, failing with [`AdbError::ExitStatus`] when it exits non-zero
+    fn shell_command(&mut self, command: &str) -> AdbResult<String> {
+        let result = self.shell_output(command)?.into_result()?;
 
         if result.trim().is_empty() {
-            Ok("Command executed successfully (no output)".to_string())
+            Ok(NO_OUTPUT.to_string())
         } else {
             Ok(result)
         }
@@ -369,15 +1531,142 @@ impl AdbManager {
 
     /// Get ADB version
     fn get_adb_version(&mut self) -> AdbResult<String> {
-        let server = self.get_server()?;
-        let version = server.version()?;
-        Ok(format!(
-            "ADB server version: {}.{}.{}",
-            version.major, version.minor, version.revision
-        ))
+        let version = self.backend.version()?;
+        Ok(match self.backend.kind() {
+            BackendKind::Client => {
+                format!("ADB server version: {} ({})", version, self.server_address)
+            }
+            kind => format!("ADB version: {} ({} backend)", version, kind.name()),
+        })
+    }
+}
+
+/// Quote a string for use as a single argument in the device shell
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Reject package names that could inject extra shell syntax
+fn check_package_name(package_name: &str) -> AdbResult<()> {
+    let valid = !package_name.is_empty()
+        && package_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AdbError::CommandFailed(format!(
+            "Invalid package name: {}",
+            package_name
+        )))
     }
 }
 
+/// Validate a local APK path and return its metadata
+fn apk_metadata(apk_path: &str) -> AdbResult<std::fs::Metadata> {
+    let path = Path::new(apk_path);
+    let is_apk = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("apk"));
+    if !is_apk {
+        return Err(AdbError::CommandFailed(format!(
+            "{} is not an .apk file",
+            apk_path
+        )));
+    }
+    Ok(std::fs::metadata(path)?)
+}
+
+/// Turn `pm` output into an error when it reports `Failure [...]`
+pub fn check_install_output(output: &str) -> AdbResult<()> {
+    match parse_install_failure(output) {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Parse `Failure [INSTALL_FAILED_X: message]` lines printed by the package manager
+pub fn parse_install_failure(output: &str) -> Option<AdbError> {
+    let line = output
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("Failure") || line.starts_with("Error:"))?;
+
+    let reason = match (line.find('['), line.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &line[start + 1..end],
+        _ => line
+            .trim_start_matches("Failure")
+            .trim_start_matches("Error:")
+            .trim(),
+    };
+
+    let (code, message) = match reason.split_once(':') {
+        Some((code, message)) => (code.trim(), Some(message.trim().to_string())),
+        None => (reason.trim(), None),
+    };
+
+    Some(AdbError::InstallFailed {
+        code: if code.is_empty() {
+            "UNKNOWN".to_string()
+        } else {
+            code.to_string()
+        },
+        message: message.filter(|m| !m.is_empty()),
+    })
+}
+
+/// Extract the session id from `Success: created install session [1234]`
+fn parse_install_session(output: &str) -> AdbResult<u32> {
+    let start = output.find('[');
+    let end = output.find(']');
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => output[start + 1..end]
+            .trim()
+            .parse()
+            .map_err(|_| AdbError::ParseError(format!("Invalid install session: {}", output))),
+        _ => Err(AdbError::ParseError(format!(
+            "No install session in: {}",
+            output.trim()
+        ))),
+    }
+}
+
+/// First IPv4 address in `ip -f inet addr` output
+fn parse_inet_address(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("inet "))
+        .and_then(|rest| rest.split(['/', ' ']).next())
+        .map(str::to_string)
+}
+
+/// Parse `host:devices-l` output: `serial state key:value...` per line
+fn parse_devices_long(output: &str) -> Vec<DeviceInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let serial = fields.next()?.to_string();
+            let state = fields.next()?.to_string();
+            let mut info = DeviceInfo {
+                serial,
+                state,
+                model: None,
+                device: None,
+            };
+            for field in fields {
+                match field.split_once(':') {
+                    Some(("model", value)) => info.model = Some(value.to_string()),
+                    Some(("device", value)) => info.device = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+            Some(info)
+        })
+        .collect()
+}
+
 impl Default for AdbManager {
     fn default() -> Self {
         Self::new()
@@ -391,7 +1680,7 @@ mod tests {
     #[test]
     fn test_adb_manager_creation() {
         let manager = AdbManager::new();
-        assert!(manager.server.is_none());
+        assert_eq!(manager.backend_kind(), BackendKind::Client);
         assert!(manager.selected_device.is_none());
     }
 
@@ -407,9 +1696,311 @@ mod tests {
         assert!(matches!(cmd, AdbCommand::ListDevices));
     }
 
+    #[test]
+    fn test_destructive_commands() {
+        let shell = |command: &str| AdbCommand::Shell {
+            command: command.to_string(),
+        };
+        for command in [
+            "reboot",
+            "reboot recovery",
+            "logcat -c",
+            "pm uninstall com.example",
+            "cmd package clear com.example",
+            "am force-stop com.example",
+            "ls /sdcard && rm -rf /sdcard/tmp",
+            "/system/bin/reboot",
+            "su -c reboot",
+            "su 0 reboot",
+            "su root -c 'pm uninstall com.example'",
+            "sh -c 'rm -rf /sdcard'",
+            "sh -c \"echo hi; reboot\"",
+            "su -c \"sh -c 'reboot'\"",
+            "toybox rm /sdcard/file",
+            "busybox reboot",
+            "env FOO=1 /system/bin/kill 42",
+            "nice -n 10 am force-stop com.example",
+            "setprop sys.powerctl reboot",
+            "FOO=1 reboot",
+            "if true; then reboot; fi",
+            "echo $(reboot)",
+            "re\\boot",
+        ] {
+            assert!(shell(command).is_destructive(), "{}", command);
+        }
+        for command in [
+            "logcat -d *:E",
+            "pm list packages",
+            "dumpsys battery",
+            "ps -A",
+            "su -c 'ls /data'",
+            "sh -c 'dumpsys battery'",
+            "toybox ls /sdcard",
+            "setprop debug.layout true",
+            "echo 'rm is dangerous'",
+        ] {
+            assert!(!shell(command).is_destructive(), "{}", command);
+        }
+
+        assert!(AdbCommand::UninstallPackage {
+            package_name: "com.example".to_string()
+        }
+        .is_destructive());
+        assert!(!AdbCommand::SetPackageEnabled {
+            package_name: "com.example".to_string(),
+            enabled: true
+        }
+        .is_destructive());
+        assert!(!AdbCommand::KillProcess {
+            pid: 1,
+            signal: Signal::Cont
+        }
+        .is_destructive());
+    }
+
+    #[test]
+    fn test_reboot_targets() {
+        assert_eq!(RebootTarget::System.service(), "reboot:");
+        assert_eq!(RebootTarget::Fastboot.service(), "reboot:fastboot");
+        assert_eq!("sideload".parse(), Ok(RebootTarget::Sideload));
+        assert!("download".parse::<RebootTarget>().is_err());
+        assert_eq!(RebootTarget::Recovery.wait_state(), Some("recovery"));
+        assert_eq!(RebootTarget::Bootloader.wait_state(), None);
+        assert!(AdbCommand::Reboot {
+            target: RebootTarget::System
+        }
+        .is_destructive());
+    }
+
+    #[test]
+    fn test_read_only_refuses_destructive() {
+        let mut manager = AdbManager::new();
+        manager.set_read_only(true);
+        assert!(matches!(
+            manager.execute(AdbCommand::Shell {
+                command: "reboot".to_string()
+            }),
+            Err(AdbError::ReadOnly)
+        ));
+        assert!(matches!(
+            manager.delete_remote("/sdcard/file"),
+            Err(AdbError::ReadOnly)
+        ));
+    }
+
+    #[test]
+    fn test_select_device() {
+        let mut manager = AdbManager::new();
+        assert_eq!(manager.selected_device(), None);
+
+        manager.select_device("emulator-5554".to_string());
+        assert_eq!(manager.selected_device(), Some("emulator-5554"));
+    }
+
+    #[test]
+    fn test_device_info_display_name() {
+        let mut info = DeviceInfo {
+            serial: "emulator-5554".to_string(),
+            state: "device".to_string(),
+            model: None,
+            device: None,
+        };
+        assert!(info.is_online());
+        assert_eq!(info.display_name(), "emulator-5554");
+
+        info.model = Some("Pixel_7".to_string());
+        assert_eq!(info.display_name(), "Pixel 7 (emulator-5554)");
+    }
+
+    #[test]
+    fn test_parse_devices_long() {
+        let output = "R58M123ABC             device usb:1-1 product:beyond1 model:SM_G973F device:beyond1 transport_id:1\n\
+                      [fe80::1]:5555         unauthorized transport_id:2\n";
+        let devices = parse_devices_long(output);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].serial, "R58M123ABC");
+        assert!(devices[0].is_online());
+        assert_eq!(devices[0].model.as_deref(), Some("SM_G973F"));
+        assert_eq!(devices[0].device.as_deref(), Some("beyond1"));
+        assert_eq!(devices[1].state, "unauthorized");
+        assert_eq!(devices[1].model, None);
+    }
+
+    #[test]
+    fn test_parse_inet_address() {
+        let output = "30: wlan0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500\n    inet 192.168.1.20/24 brd 192.168.1.255 scope global wlan0\n";
+        assert_eq!(parse_inet_address(output).as_deref(), Some("192.168.1.20"));
+        assert_eq!(parse_inet_address(""), None);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/sdcard/My Photos"), "'/sdcard/My Photos'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn test_install_options_flags() {
+        assert_eq!(InstallOptions::default().flags(), "");
+
+        let options = InstallOptions {
+            replace: true,
+            allow_downgrade: true,
+            grant_permissions: true,
+            allow_test: true,
+        };
+        assert_eq!(options.flags(), " -r -d -g -t");
+    }
+
+    #[test]
+    fn test_parse_install_failure() {
+        assert!(parse_install_failure("Success\n").is_none());
+
+        match parse_install_failure(
+            "Performing Streamed Install\nFailure [INSTALL_FAILED_VERSION_DOWNGRADE]\n",
+        ) {
+            Some(AdbError::InstallFailed { code, message }) => {
+                assert_eq!(code, "INSTALL_FAILED_VERSION_DOWNGRADE");
+                assert_eq!(message, None);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        match parse_install_failure(
+            "Failure [INSTALL_PARSE_FAILED_NO_CERTIFICATES: Failed to collect certificates]",
+        ) {
+            Some(AdbError::InstallFailed { code, message }) => {
+                assert_eq!(code, "INSTALL_PARSE_FAILED_NO_CERTIFICATES");
+                assert_eq!(message.as_deref(), Some("Failed to collect certificates"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_install_session() {
+        assert_eq!(
+            parse_install_session("Success: created install session [1234567]\n").unwrap(),
+            1234567
+        );
+        assert!(parse_install_session("Success").is_err());
+    }
+
+    #[test]
+    fn test_install_rejects_non_apk() {
+        assert!(matches!(
+            apk_metadata("notes.txt"),
+            Err(AdbError::CommandFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_command_names() {
+        let package = || "com.example.app".to_string();
+        let address = || "192.168.1.20:5555".to_string();
+        // One of each variant, in the order of `NAMES`
+        let commands = [
+            AdbCommand::ListDevices,
+            AdbCommand::GetDeviceState,
+            AdbCommand::GetSerialNumber,
+            AdbCommand::ListPackages {
+                include_path: false,
+                filter: PackageFilter::All,
+            },
+            AdbCommand::GetPackageInfo {
+                package_name: package(),
+            },
+            AdbCommand::InstallPackage {
+                apk_path: "app.apk".to_string(),
+                options: InstallOptions::default(),
+            },
+            AdbCommand::InstallSplitPackage {
+                apk_paths: vec!["base.apk".to_string()],
+                options: InstallOptions::default(),
+            },
+            AdbCommand::UninstallPackage {
+                package_name: package(),
+            },
+            AdbCommand::ClearPackageData {
+                package_name: package(),
+            },
+            AdbCommand::SetPackageEnabled {
+                package_name: package(),
+                enabled: true,
+            },
+            AdbCommand::LaunchPackage {
+                package_name: package(),
+            },
+            AdbCommand::ManagePackages,
+            AdbCommand::Reboot {
+                target: RebootTarget::System,
+            },
+            AdbCommand::GetBatteryInfo,
+            AdbCommand::GetMemoryInfo,
+            AdbCommand::GetCpuInfo,
+            AdbCommand::GetDeviceProperties,
+            AdbCommand::GetSystemLog { lines: 100 },
+            AdbCommand::StreamLogcat,
+            AdbCommand::Dashboard,
+            AdbCommand::GetNetworkInfo,
+            AdbCommand::GetWifiStatus,
+            AdbCommand::TcpIp { port: 5555 },
+            AdbCommand::Connect { address: address() },
+            AdbCommand::Disconnect { address: None },
+            AdbCommand::Pair {
+                address: address(),
+                code: "123456".to_string(),
+            },
+            AdbCommand::TakeScreenshot,
+            AdbCommand::RecordScreen {
+                options: RecordOptions::default(),
+            },
+            AdbCommand::MirrorScreen,
+            AdbCommand::GetScreenResolution,
+            AdbCommand::ListProcesses,
+            AdbCommand::ManageProcesses,
+            AdbCommand::KillProcess {
+                pid: 1,
+                signal: Signal::Term,
+            },
+            AdbCommand::ForceStop {
+                package_name: package(),
+            },
+            AdbCommand::ListDirectory {
+                path: "/sdcard".to_string(),
+            },
+            AdbCommand::Shell {
+                command: "ls".to_string(),
+            },
+            AdbCommand::GetAdbVersion,
+        ];
+        let names: Vec<&str> = commands.iter().map(AdbCommand::name).collect();
+        assert_eq!(names, AdbCommand::NAMES);
+        assert_eq!(AdbCommand::MirrorScreen.default_timeout(), None);
+    }
+
     #[test]
     fn test_error_display() {
         let error = AdbError::DeviceNotFound;
         assert_eq!(error.to_string(), "Device not found");
+
+        let error = AdbError::InstallFailed {
+            code: "INSTALL_FAILED_INSUFFICIENT_STORAGE".to_string(),
+            message: None,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Install failed: INSTALL_FAILED_INSUFFICIENT_STORAGE"
+        );
+
+        let error = AdbError::ExitStatus {
+            code: 127,
+            output: String::new(),
+        };
+        assert_eq!(error.to_string(), "Exited with status 127");
+        assert_eq!(error.exit_code(), 6);
+
+        let error = AdbError::Timeout(Duration::from_millis(1500));
+        assert_eq!(error.to_string(), "Timed out after 1.5s");
     }
 }
@@ -0,0 +1,273 @@
+//! ADB server and device addresses
+//!
+//! Accepts the same forms as the `adb` tool: a bare port, `host`, `host:port`,
+//! `[ipv6]:port` and the `tcp:host:port` syntax of `ADB_SERVER_SOCKET`. Device
+//! serials tell how a device is attached.
+
+use std::fmt;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs};
+
+/// Port the ADB server listens on by default
+pub const DEFAULT_SERVER_PORT: u16 = 5037;
+
+/// `tcp:host:port` socket spec used by `adb -L` and the adb tool
+pub const SERVER_SOCKET_ENV: &str = "ADB_SERVER_SOCKET";
+
+/// Server port override honoured by the adb tool
+pub const SERVER_PORT_ENV: &str = "ANDROID_ADB_SERVER_PORT";
+
+/// Server host override honoured by the adb tool
+pub const SERVER_ADDRESS_ENV: &str = "ANDROID_ADB_SERVER_ADDRESS";
+
+/// Where the ADB server listens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerAddress {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for ServerAddress {
+    fn default() -> Self {
+        Self {
+            host: Ipv4Addr::LOCALHOST.to_string(),
+            port: DEFAULT_SERVER_PORT,
+        }
+    }
+}
+
+impl fmt::Display for ServerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl ServerAddress {
+    /// Parse a server address; missing parts fall back to `127.0.0.1:5037`
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        let input = input.strip_prefix("tcp:").unwrap_or(input);
+        if input.is_empty() {
+            return Err("Empty ADB server address".to_string());
+        }
+
+        if let Ok(port) = input.parse::<u16>() {
+            return Ok(Self {
+                port,
+                ..Self::default()
+            });
+        }
+
+        let (host, port) = if let Some(rest) = input.strip_prefix('[') {
+            let (host, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| format!("Missing ']' in {}", input))?;
+            match rest.strip_prefix(':') {
+                Some(port) => (host, Some(port)),
+                None if rest.is_empty() => (host, None),
+                None => return Err(format!("Invalid ADB server address: {}", input)),
+            }
+        } else if input.parse::<IpAddr>().is_ok_and(|ip| ip.is_ipv6()) {
+            (input, None)
+        } else {
+            match input.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (input, None),
+            }
+        };
+
+        let port = match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| format!("Invalid ADB server port: {}", port))?,
+            None => DEFAULT_SERVER_PORT,
+        };
+        if host.is_empty() {
+            return Err(format!("Missing host in {}", input));
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+        })
+    }
+
+    /// Address from `ADB_SERVER_SOCKET`, or `ANDROID_ADB_SERVER_ADDRESS` / `ANDROID_ADB_SERVER_PORT`
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        Self::from_vars(
+            var(SERVER_SOCKET_ENV).as_deref(),
+            var(SERVER_ADDRESS_ENV).as_deref(),
+            var(SERVER_PORT_ENV).as_deref(),
+        )
+    }
+
+    fn from_vars(
+        socket: Option<&str>,
+        host: Option<&str>,
+        port: Option<&str>,
+    ) -> Result<Option<Self>, String> {
+        if let Some(socket) = socket {
+            return Self::parse(socket)
+                .map(Some)
+                .map_err(|e| format!("{}: {}", SERVER_SOCKET_ENV, e));
+        }
+        if host.is_none() && port.is_none() {
+            return Ok(None);
+        }
+
+        let mut address = Self::default();
+        if let Some(host) = host {
+            address.host = host.trim().trim_matches(['[', ']']).to_string();
+        }
+        if let Some(port) = port {
+            address.port = port
+                .trim()
+                .parse()
+                .map_err(|_| format!("{}: invalid port {}", SERVER_PORT_ENV, port))?;
+        }
+        Ok(Some(address))
+    }
+
+    /// Resolve to a socket address, preferring IPv4 when a name has both
+    pub fn resolve(&self) -> io::Result<SocketAddr> {
+        let addrs: Vec<SocketAddr> = (self.host.as_str(), self.port).to_socket_addrs()?.collect();
+        addrs
+            .iter()
+            .find(|addr| addr.is_ipv4())
+            .or_else(|| addrs.first())
+            .copied()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} did not resolve to an address", self.host),
+                )
+            })
+    }
+}
+
+/// How a device is attached to the ADB server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Usb,
+    Tcp,
+    Emulator,
+}
+
+impl Transport {
+    /// Infer the transport from a device serial
+    ///
+    /// Network devices are listed as `host:port` (or an mDNS service name for
+    /// Android 11+ wireless debugging); emulators as `emulator-<port>`.
+    pub fn from_serial(serial: &str) -> Self {
+        if serial.starts_with("emulator-") {
+            Transport::Emulator
+        } else if serial.contains("._adb-tls-connect.") || split_host_port(serial).is_some() {
+            Transport::Tcp
+        } else {
+            Transport::Usb
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Transport::Usb => "USB",
+            Transport::Tcp => "TCP",
+            Transport::Emulator => "EMU",
+        }
+    }
+}
+
+/// Split `host:port` / `[v6]:port` into its parts
+pub(crate) fn split_host_port(address: &str) -> Option<(&str, u16)> {
+    let (host, port) = address.rsplit_once(':')?;
+    let port = port.parse().ok()?;
+    let host = match host.strip_prefix('[') {
+        Some(v6) => v6.strip_suffix(']')?,
+        // A bare IPv6 address without brackets is ambiguous
+        None if host.contains(':') => return None,
+        None => host,
+    };
+    (!host.is_empty()).then_some((host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(host: &str, port: u16) -> ServerAddress {
+        ServerAddress {
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn test_parse_server_address() {
+        assert_eq!(
+            ServerAddress::parse("5038").unwrap(),
+            address("127.0.0.1", 5038)
+        );
+        assert_eq!(
+            ServerAddress::parse("adb-host").unwrap(),
+            address("adb-host", 5037)
+        );
+        assert_eq!(
+            ServerAddress::parse("tcp:10.0.0.5:5039").unwrap(),
+            address("10.0.0.5", 5039)
+        );
+        assert_eq!(
+            ServerAddress::parse("[::1]:5040").unwrap(),
+            address("::1", 5040)
+        );
+        assert_eq!(
+            ServerAddress::parse("fd00::2").unwrap(),
+            address("fd00::2", 5037)
+        );
+        assert!(ServerAddress::parse("host:abc").is_err());
+        assert!(ServerAddress::parse("[::1").is_err());
+    }
+
+    #[test]
+    fn test_display_brackets_ipv6() {
+        assert_eq!(address("::1", 5037).to_string(), "[::1]:5037");
+        assert_eq!(address("10.0.0.5", 5037).to_string(), "10.0.0.5:5037");
+    }
+
+    #[test]
+    fn test_from_vars_precedence() {
+        assert_eq!(ServerAddress::from_vars(None, None, None).unwrap(), None);
+        assert_eq!(
+            ServerAddress::from_vars(Some("tcp:build-box:6000"), Some("ignored"), Some("1"))
+                .unwrap(),
+            Some(address("build-box", 6000))
+        );
+        assert_eq!(
+            ServerAddress::from_vars(None, None, Some("5555")).unwrap(),
+            Some(address("127.0.0.1", 5555))
+        );
+        assert!(ServerAddress::from_vars(None, None, Some("port")).is_err());
+    }
+
+    #[test]
+    fn test_resolve_ip_literals() {
+        assert!(address("127.0.0.1", 5037).resolve().unwrap().is_ipv4());
+        assert!(address("::1", 5037).resolve().unwrap().is_ipv6());
+    }
+
+    #[test]
+    fn test_transport_from_serial() {
+        assert_eq!(Transport::from_serial("R58M123ABC"), Transport::Usb);
+        assert_eq!(Transport::from_serial("192.168.1.20:5555"), Transport::Tcp);
+        assert_eq!(Transport::from_serial("[fe80::1]:5555"), Transport::Tcp);
+        assert_eq!(
+            Transport::from_serial("adb-R58M123ABC-x1y2z3._adb-tls-connect._tcp"),
+            Transport::Tcp
+        );
+        assert_eq!(Transport::from_serial("emulator-5554"), Transport::Emulator);
+    }
+}
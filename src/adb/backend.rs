@@ -0,0 +1,158 @@
+//! Transports behind [`AdbManager`](super::AdbManager)
+//!
+//! A backend knows how to reach devices; the manager builds every command on top of
+//! the handful of primitives here. The client backend talks to the server
+//! directly, the binary backend runs the `adb` executable and the mock backend
+//! answers from memory so the UI can be exercised without hardware.
+
+use super::{AdbError, AdbResult, CancelToken, DeviceInfo, RebootTarget, RemoteEntry};
+use std::fmt::Debug;
+use std::io::{self, Read, Write};
+
+/// Which [`AdbBackend`] a manager uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackendKind {
+    /// ADB server protocol through our own host and sync requests
+    #[default]
+    Client,
+    /// The `adb` executable found on `PATH`
+    Binary,
+    /// Canned in-memory device, for demos and tests
+    Mock,
+}
+
+impl BackendKind {
+    pub const ALL: [BackendKind; 3] = [BackendKind::Client, BackendKind::Binary, BackendKind::Mock];
+
+    /// Name used on the command line and in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackendKind::Client => "client",
+            BackendKind::Binary => "binary",
+            BackendKind::Mock => "mock",
+        }
+    }
+}
+
+impl std::str::FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "client" | "adb_client" => Ok(BackendKind::Client),
+            "binary" | "adb" => Ok(BackendKind::Binary),
+            "mock" => Ok(BackendKind::Mock),
+            _ => Err(format!("expected one of client, binary, mock; got {}", s)),
+        }
+    }
+}
+
+/// Device and server primitives a manager is built on
+///
+/// Methods taking a serial talk to that device; the manager picks it.
+/// Requests should return [`AdbError::Cancelled`] once the backend's
+/// [`CancelToken`] fired.
+pub trait AdbBackend: Send + Debug {
+    fn kind(&self) -> BackendKind;
+
+    /// Copy of this backend for a worker thread, aborted through `cancel`
+    fn for_worker(&self, cancel: CancelToken) -> Box<dyn AdbBackend>;
+
+    /// Server version, e.g. `1.0.41`
+    fn version(&mut self) -> AdbResult<String>;
+
+    /// All attached devices, with model names where known
+    fn devices(&mut self) -> AdbResult<Vec<DeviceInfo>>;
+
+    /// Report the attached devices, then again on every change, until cancelled
+    /// or the server goes away
+    fn track_devices(&mut self, on_change: &mut dyn FnMut(Vec<DeviceInfo>)) -> AdbResult<()>;
+
+    /// Run a shell command, copying stdout and stderr to `out` as they arrive
+    fn shell(&mut self, serial: &str, command: &str, out: &mut dyn Write) -> AdbResult<()>;
+
+    /// Run a shell command keeping stdout and stderr apart, returning its exit status
+    ///
+    /// The status is `None` when the device can't report it.
+    fn shell_status(
+        &mut self,
+        serial: &str,
+        command: &str,
+        stdout: &mut dyn Write,
+        stderr: &mut dyn Write,
+    ) -> AdbResult<Option<u8>>;
+
+    /// Run a command without a terminal (`exec-out`), for binary output
+    fn exec(&mut self, serial: &str, command: &str, out: &mut dyn Write) -> AdbResult<()>;
+
+    /// Reboot into `target`, returning once the device went down
+    fn reboot(&mut self, serial: &str, target: RebootTarget) -> AdbResult<()>;
+
+    /// Block until `serial` is attached in `state` (`device`, `recovery`, `sideload`)
+    fn wait_for(&mut self, serial: &str, state: &str) -> AdbResult<()>;
+
+    /// Restart adbd listening on a TCP port
+    fn tcpip(&mut self, serial: &str, port: u16) -> AdbResult<()>;
+
+    /// Connect to a network device, returning the server's reply
+    fn connect(&mut self, address: &str) -> AdbResult<String>;
+
+    /// Disconnect one network device, or all of them, returning the server's reply
+    fn disconnect(&mut self, address: Option<&str>) -> AdbResult<String>;
+
+    /// Pair using a wireless debugging code, returning the server's reply
+    fn pair(&mut self, address: &str, code: &str) -> AdbResult<String>;
+
+    /// Directory entries of `path`, excluding `.` and `..`
+    fn list_dir(&mut self, serial: &str, path: &str) -> AdbResult<Vec<RemoteEntry>>;
+
+    /// Size of a file on the device in bytes
+    fn file_size(&mut self, serial: &str, path: &str) -> AdbResult<u64>;
+
+    fn push(&mut self, serial: &str, input: &mut dyn Read, path: &str) -> AdbResult<()>;
+
+    fn pull(&mut self, serial: &str, path: &str, out: &mut dyn Write) -> AdbResult<()>;
+}
+
+/// Map an I/O error from a device request, naming what failed in `context`
+pub(crate) fn io_error(e: io::Error, context: &str) -> AdbError {
+    match e.kind() {
+        io::ErrorKind::Interrupted => AdbError::Cancelled,
+        io::ErrorKind::ConnectionRefused => AdbError::ConnectionError(e.to_string()),
+        io::ErrorKind::BrokenPipe => AdbError::IoError(e),
+        _ => AdbError::CommandFailed(format!("{}: {}", context, e)),
+    }
+}
+
+/// Error context for an `exec` command, e.g. `screenrecord failed`
+pub(crate) fn exec_context(command: &str) -> String {
+    format!(
+        "{} failed",
+        command.split_whitespace().next().unwrap_or("exec")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_kind_names() {
+        for kind in BackendKind::ALL {
+            assert_eq!(kind.name().parse(), Ok(kind));
+        }
+        assert_eq!("adb".parse(), Ok(BackendKind::Binary));
+        assert!("usb".parse::<BackendKind>().is_err());
+    }
+
+    #[test]
+    fn test_io_error() {
+        let cancelled = io::Error::new(io::ErrorKind::Interrupted, "Cancelled");
+        assert!(matches!(io_error(cancelled, "x"), AdbError::Cancelled));
+        let failed = io::Error::other("closed");
+        assert_eq!(
+            io_error(failed, "Shell command failed").to_string(),
+            "Command failed: Shell command failed: closed"
+        );
+    }
+}
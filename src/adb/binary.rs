@@ -0,0 +1,571 @@
+//! Backend running the `adb` executable
+//!
+//! Every request is one `adb -H host -P port ...` invocation. Cancelling kills the
+//! process. Streamed shell commands run with stderr folded into stdout, so anything adb
+//! itself prints on stderr is an error of the transport rather than of the command.
+//! Commands asking for an exit status keep the streams apart and rely on adb's
+//! `adb:` / `error:` prefixes instead.
+
+use super::backend::{exec_context, io_error, AdbBackend, BackendKind};
+use super::{
+    parse_devices_long, AdbError, AdbResult, CancelToken, DeviceInfo, RebootTarget, RemoteEntry,
+    ServerAddress,
+};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// How often a running adb process checks for cancellation
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+static NEXT_TEMP_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+pub struct BinaryBackend {
+    /// Program to run, `adb` unless overridden
+    program: PathBuf,
+    server: ServerAddress,
+    cancel: CancelToken,
+}
+
+/// Exit status and stderr of a finished adb process
+struct Finished {
+    status: ExitStatus,
+    stderr: String,
+}
+
+impl Finished {
+    /// stderr, or the exit status when adb printed nothing
+    fn message(&self) -> String {
+        match self.stderr.trim() {
+            "" => format!("adb exited with {}", self.status),
+            stderr => stderr.trim_start_matches("adb: ").to_string(),
+        }
+    }
+}
+
+impl BinaryBackend {
+    pub fn new(server: ServerAddress) -> Self {
+        Self {
+            program: PathBuf::from("adb"),
+            server,
+            cancel: CancelToken::default(),
+        }
+    }
+
+    /// Run a different adb executable
+    pub fn with_program(mut self, program: impl Into<PathBuf>) -> Self {
+        self.program = program.into();
+        self
+    }
+
+    /// Start adb with `args` and the given stdin and stdout
+    fn spawn(&self, args: &[&str], stdin: Stdio, stdout: Stdio) -> AdbResult<Child> {
+        if self.cancel.is_cancelled() {
+            return Err(AdbError::Cancelled);
+        }
+        Command::new(&self.program)
+            .args(["-H", &self.server.host, "-P", &self.server.port.to_string()])
+            .args(args)
+            .stdin(stdin)
+            .stdout(stdout)
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => AdbError::ConnectionError(format!(
+                    "{} was not found; install the Android platform tools or use another backend",
+                    self.program.display()
+                )),
+                _ => AdbError::IoError(e),
+            })
+    }
+
+    /// Run adb with `args`, copying its stdout to `out`
+    fn run(&self, args: &[&str], out: &mut dyn Write) -> AdbResult<Finished> {
+        let mut child = self.spawn(args, Stdio::null(), Stdio::piped())?;
+        let Some(mut stdout) = child.stdout.take() else {
+            let _ = child.kill();
+            return Err(AdbError::CommandFailed("adb has no pipes".to_string()));
+        };
+        self.supervise(child, "adb output", || io::copy(&mut stdout, out))
+    }
+
+    /// Run adb with `args`, feeding `input` to its stdin; returns how many bytes it took
+    fn run_with_input(&self, args: &[&str], input: &mut dyn Read) -> AdbResult<(Finished, u64)> {
+        let mut child = self.spawn(args, Stdio::piped(), Stdio::null())?;
+        let Some(mut stdin) = child.stdin.take() else {
+            let _ = child.kill();
+            return Err(AdbError::CommandFailed("adb has no pipes".to_string()));
+        };
+        let mut sent = 0;
+        let finished = self.supervise(child, "adb input", || {
+            let copied = io::copy(input, &mut stdin);
+            // Closing stdin ends the transfer
+            drop(stdin);
+            match copied {
+                Ok(n) => sent = n,
+                // adb exited early; its stderr says why
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+                Err(e) => return Err(e),
+            }
+            Ok(sent)
+        })?;
+        Ok((finished, sent))
+    }
+
+    /// Wait for `child` while `transfer` feeds or drains it, killing it when cancelled
+    fn supervise(
+        &self,
+        mut child: Child,
+        context: &str,
+        transfer: impl FnOnce() -> io::Result<u64>,
+    ) -> AdbResult<Finished> {
+        let Some(mut stderr) = child.stderr.take() else {
+            let _ = child.kill();
+            return Err(AdbError::CommandFailed("adb has no pipes".to_string()));
+        };
+
+        let child = Mutex::new(child);
+        let done = AtomicBool::new(false);
+        let (copied, stderr) = thread::scope(|scope| {
+            // Killing the process closes its pipes, which ends the transfer below
+            scope.spawn(|| {
+                while !done.load(Ordering::SeqCst) {
+                    if self.cancel.is_cancelled() {
+                        let _ = lock(&child).kill();
+                        break;
+                    }
+                    thread::sleep(CANCEL_POLL);
+                }
+            });
+            let stderr = scope.spawn(move || {
+                let mut text = String::new();
+                let _ = stderr.read_to_string(&mut text);
+                text
+            });
+            let copied = transfer();
+            // Stop a transfer that can no longer go on
+            if copied.is_err() {
+                let _ = lock(&child).kill();
+            }
+            let stderr = stderr.join().unwrap_or_default();
+            done.store(true, Ordering::SeqCst);
+            (copied, stderr)
+        });
+
+        let status = lock(&child).wait()?;
+        if self.cancel.is_cancelled() {
+            return Err(AdbError::Cancelled);
+        }
+        copied.map_err(|e| io_error(e, context))?;
+        Ok(Finished { status, stderr })
+    }
+
+    /// Run adb and return its stdout, failing when it exits unsuccessfully
+    fn output(&self, args: &[&str]) -> AdbResult<String> {
+        let mut output = Vec::new();
+        let finished = self.run(args, &mut output)?;
+        if !finished.status.success() {
+            return Err(AdbError::CommandFailed(finished.message()));
+        }
+        Ok(String::from_utf8_lossy(&output).to_string())
+    }
+
+    /// Run adb and return everything it printed; `connect` and friends report failure as text
+    fn reply(&self, args: &[&str]) -> AdbResult<String> {
+        let mut output = Vec::new();
+        let finished = self.run(args, &mut output)?;
+        let stdout = String::from_utf8_lossy(&output);
+        let parts = [stdout.trim(), finished.stderr.trim()];
+        Ok(parts
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    /// New private local file for `adb pull` to write into
+    ///
+    /// Created exclusively and readable only by us, so nothing planted at the name
+    /// in the shared temp directory, such as a symlink, is ever followed.
+    fn temp_file() -> io::Result<PathBuf> {
+        loop {
+            let path = std::env::temp_dir().join(format!(
+                "droidtui-{}-{}",
+                std::process::id(),
+                NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed)
+            ));
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            match options.open(&path) {
+                Ok(_) => return Ok(path),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+fn lock(child: &Mutex<Child>) -> std::sync::MutexGuard<'_, Child> {
+    child.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Splits `adb track-devices` output back into the server's length-prefixed lists
+struct DeviceLists<'a> {
+    pending: Vec<u8>,
+    on_change: &'a mut dyn FnMut(Vec<DeviceInfo>),
+}
+
+impl Write for DeviceLists<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        while self.pending.len() >= 4 {
+            let len = std::str::from_utf8(&self.pending[..4])
+                .ok()
+                .and_then(|len| usize::from_str_radix(len, 16).ok())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Bad device list"))?;
+            if self.pending.len() < 4 + len {
+                break;
+            }
+            let list: Vec<u8> = self.pending.drain(..4 + len).skip(4).collect();
+            (self.on_change)(parse_devices_long(&String::from_utf8_lossy(&list)));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AdbBackend for BinaryBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Binary
+    }
+
+    fn for_worker(&self, cancel: CancelToken) -> Box<dyn AdbBackend> {
+        Box::new(Self {
+            cancel,
+            ..self.clone()
+        })
+    }
+
+    /// Version of the adb tool, which matches the server it starts
+    fn version(&mut self) -> AdbResult<String> {
+        let output = self.output(&["version"])?;
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix("Android Debug Bridge version "))
+            .map(|version| version.trim().to_string())
+            .ok_or_else(|| AdbError::ParseError(format!("Unexpected adb version: {}", output)))
+    }
+
+    fn devices(&mut self) -> AdbResult<Vec<DeviceInfo>> {
+        let output = self.output(&["devices", "-l"])?;
+        let listing: String = output
+            .lines()
+            .filter(|line| !line.starts_with("List of devices") && !line.starts_with('*'))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        Ok(parse_devices_long(&listing))
+    }
+
+    /// adb prints the lists as the server frames them, until the server goes away
+    fn track_devices(&mut self, on_change: &mut dyn FnMut(Vec<DeviceInfo>)) -> AdbResult<()> {
+        let mut lists = DeviceLists {
+            pending: Vec::new(),
+            on_change,
+        };
+        let finished = self.run(&["track-devices", "-l"], &mut lists)?;
+        Err(AdbError::ConnectionError(finished.message()))
+    }
+
+    fn shell(&mut self, serial: &str, command: &str, out: &mut dyn Write) -> AdbResult<()> {
+        let command = format!("exec 2>&1; {}", command);
+        let finished = self.run(&["-s", serial, "shell", &command], out)?;
+        // The command's own exit status is not a transport error
+        if !finished.status.success() && !finished.stderr.trim().is_empty() {
+            return Err(AdbError::CommandFailed(format!(
+                "Shell command failed: {}",
+                finished.message()
+            )));
+        }
+        Ok(())
+    }
+
+    /// adb passes the exit status on; its own failures are prefixed on stderr
+    fn shell_status(
+        &mut self,
+        serial: &str,
+        command: &str,
+        stdout: &mut dyn Write,
+        stderr: &mut dyn Write,
+    ) -> AdbResult<Option<u8>> {
+        let finished = self.run(&["-s", serial, "shell", command], stdout)?;
+        if !finished.status.success() && is_adb_error(&finished.stderr) {
+            return Err(AdbError::CommandFailed(format!(
+                "Shell command failed: {}",
+                finished.message()
+            )));
+        }
+        stderr.write_all(finished.stderr.as_bytes())?;
+        Ok(finished.status.code().map(|code| code as u8))
+    }
+
+    fn exec(&mut self, serial: &str, command: &str, out: &mut dyn Write) -> AdbResult<()> {
+        let finished = self.run(&["-s", serial, "exec-out", command], out)?;
+        if !finished.status.success() && !finished.stderr.trim().is_empty() {
+            return Err(AdbError::CommandFailed(format!(
+                "{}: {}",
+                exec_context(command),
+                finished.message()
+            )));
+        }
+        Ok(())
+    }
+
+    fn reboot(&mut self, serial: &str, target: RebootTarget) -> AdbResult<()> {
+        let mut args = vec!["-s", serial, "reboot"];
+        if target != RebootTarget::System {
+            args.push(target.name());
+        }
+        self.output(&args).map(|_| ())
+    }
+
+    fn wait_for(&mut self, serial: &str, state: &str) -> AdbResult<()> {
+        let wait = format!("wait-for-{}", state);
+        self.output(&["-s", serial, &wait]).map(|_| ())
+    }
+
+    fn tcpip(&mut self, serial: &str, port: u16) -> AdbResult<()> {
+        self.output(&["-s", serial, "tcpip", &port.to_string()])
+            .map(|_| ())
+    }
+
+    fn connect(&mut self, address: &str) -> AdbResult<String> {
+        self.reply(&["connect", address])
+    }
+
+    fn disconnect(&mut self, address: Option<&str>) -> AdbResult<String> {
+        let mut args = vec!["disconnect"];
+        args.extend(address);
+        let reply = self.reply(&args)?;
+        // The adb tool answers what the server leaves empty
+        Ok(match reply.as_str() {
+            "disconnected everything" => String::new(),
+            _ => reply,
+        })
+    }
+
+    fn pair(&mut self, address: &str, code: &str) -> AdbResult<String> {
+        self.reply(&["pair", address, code])
+    }
+
+    /// Listed through `stat` in the device shell, which every Android since 6 has
+    fn list_dir(&mut self, serial: &str, path: &str) -> AdbResult<Vec<RemoteEntry>> {
+        let command = format!(
+            "cd {} && for f in .* *; do [ -e \"$f\" ] || [ -L \"$f\" ] && stat -c '%f %s %Y %n' \"$f\"; done",
+            super::shell_quote(path)
+        );
+        let mut output = Vec::new();
+        self.shell(serial, &command, &mut output)?;
+        let output = String::from_utf8_lossy(&output);
+        if let Some(line) = output.lines().find(|line| line.contains("cd: ")) {
+            return Err(AdbError::CommandFailed(line.trim().to_string()));
+        }
+        Ok(parse_stat_listing(&output))
+    }
+
+    fn file_size(&mut self, serial: &str, path: &str) -> AdbResult<u64> {
+        let mut output = Vec::new();
+        let command = format!("stat -c %s {}", super::shell_quote(path));
+        self.shell(serial, &command, &mut output)?;
+        let output = String::from_utf8_lossy(&output);
+        output
+            .trim()
+            .parse()
+            .map_err(|_| AdbError::CommandFailed(output.trim().to_string()))
+    }
+
+    /// Streamed into `cat` on the device with `exec-in`, so input is read as it is sent
+    ///
+    /// `exec-in` passes on neither the exit status nor the errors of `cat`; the size
+    /// of the file on the device tells whether everything arrived.
+    fn push(&mut self, serial: &str, input: &mut dyn Read, path: &str) -> AdbResult<()> {
+        let command = format!("cat > {}", super::shell_quote(path));
+        let (finished, sent) = self.run_with_input(&["-s", serial, "exec-in", &command], input)?;
+        if !finished.status.success() {
+            return Err(AdbError::CommandFailed(format!(
+                "Push failed: {}",
+                finished.message()
+            )));
+        }
+        match self.file_size(serial, path) {
+            Ok(size) if size == sent => Ok(()),
+            Ok(size) => Err(AdbError::CommandFailed(format!(
+                "Push failed: {} of {} bytes arrived",
+                size, sent
+            ))),
+            Err(e) => Err(AdbError::CommandFailed(format!("Push failed: {}", e))),
+        }
+    }
+
+    /// Staged through a local temporary file for `adb pull`
+    fn pull(&mut self, serial: &str, path: &str, out: &mut dyn Write) -> AdbResult<()> {
+        let temp = Self::temp_file()?;
+        let result = (|| {
+            let local = temp.to_string_lossy();
+            self.output(&["-s", serial, "pull", path, &local])
+                .map_err(|e| AdbError::CommandFailed(format!("Pull failed: {}", e)))?;
+            io::copy(&mut File::open(&temp)?, out)?;
+            Ok(())
+        })();
+        let _ = fs::remove_file(&temp);
+        result
+    }
+}
+
+/// Whether stderr comes from adb itself, e.g. `adb: device 'x' not found`
+fn is_adb_error(stderr: &str) -> bool {
+    stderr.starts_with("adb: ") || stderr.starts_with("error: ")
+}
+
+/// Parse `stat -c '%f %s %Y %n'` lines (hex mode, size, mtime, name)
+fn parse_stat_listing(output: &str) -> Vec<RemoteEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, ' ');
+            let mode = u32::from_str_radix(fields.next()?, 16).ok()?;
+            let size = fields.next()?.parse::<u64>().ok()?;
+            let mtime = fields.next()?.parse().ok()?;
+            let name = fields.next()?.to_string();
+            Some(RemoteEntry {
+                name,
+                mode,
+                size,
+                mtime,
+            })
+        })
+        .filter(|entry| entry.name != "." && entry.name != "..")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_listing() {
+        let output = "41f9 3452 1700000000 .\n41f9 3452 1700000000 ..\n\
+                      41f9 3452 1700000100 DCIM\n81b0 12 1700000200 notes two.txt\n\
+                      81b0 5368709120 1700000300 video.mp4\n\
+                      stat: 'x': No such file or directory\n";
+        let entries = parse_stat_listing(output);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].size, 5 * 1024 * 1024 * 1024);
+        assert!(entries[0].is_dir());
+        assert_eq!(entries[1].name, "notes two.txt");
+        assert_eq!(entries[1].size, 12);
+        assert!(!entries[1].is_dir());
+    }
+
+    #[test]
+    fn test_device_lists() {
+        let mut lists = Vec::new();
+        let mut on_change = |devices: Vec<DeviceInfo>| lists.push(devices);
+        let mut writer = DeviceLists {
+            pending: Vec::new(),
+            on_change: &mut on_change,
+        };
+        let list = "emulator-5554          device product:sdk model:Pixel_8 device:emu64xa\n";
+        let output = format!("0000{:04x}{}", list.len(), list);
+        // Split mid-frame the way a pipe may deliver it
+        writer.write_all(&output.as_bytes()[..10]).unwrap();
+        writer.write_all(&output.as_bytes()[10..]).unwrap();
+        assert!(writer.write_all(b"zzzz").is_err());
+
+        assert_eq!(lists.len(), 2);
+        assert!(lists[0].is_empty());
+        assert_eq!(lists[1][0].serial, "emulator-5554");
+        assert_eq!(lists[1][0].model.as_deref(), Some("Pixel_8"));
+    }
+
+    #[test]
+    fn test_is_adb_error() {
+        assert!(is_adb_error("adb: device 'x' not found\n"));
+        assert!(is_adb_error("error: device offline\n"));
+        assert!(!is_adb_error(
+            "/system/bin/sh: nope: inaccessible or not found\n"
+        ));
+    }
+
+    #[test]
+    fn test_missing_program() {
+        let mut backend =
+            BinaryBackend::new(ServerAddress::default()).with_program("/nonexistent/droidtui-adb");
+        assert!(matches!(
+            backend.devices(),
+            Err(AdbError::ConnectionError(_))
+        ));
+    }
+
+    #[test]
+    fn test_temp_file_is_new() {
+        let first = BinaryBackend::temp_file().unwrap();
+        let second = BinaryBackend::temp_file().unwrap();
+        assert_ne!(first, second);
+        assert_eq!(fs::metadata(&first).unwrap().len(), 0);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&first).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+
+    /// An `adb` that runs `shell` and `exec-in` commands on the host
+    #[cfg(unix)]
+    #[test]
+    fn test_push_streams_input() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("droidtui-fake-adb-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let program = dir.join("adb");
+        fs::write(
+            &program,
+            "#!/bin/sh\nshift 6\ncase \"$1\" in\n  shell|exec-in) exec sh -c \"$2\" ;;\nesac\nexit 1\n",
+        )
+        .unwrap();
+        fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut backend = BinaryBackend::new(ServerAddress::default()).with_program(&program);
+        let target = dir.join("pushed.txt");
+        let target = target.to_string_lossy();
+        backend
+            .push("emulator-5554", &mut &b"pushed"[..], &target)
+            .unwrap();
+        assert_eq!(fs::read(&*target).unwrap(), b"pushed");
+
+        let missing = dir.join("missing").join("pushed.txt");
+        assert!(backend
+            .push(
+                "emulator-5554",
+                &mut &b"pushed"[..],
+                &missing.to_string_lossy()
+            )
+            .is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
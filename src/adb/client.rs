@@ -0,0 +1,235 @@
+//! Backend talking to the ADB server over TCP
+//!
+//! Every request goes through our own host and sync protocol code, so it can be
+//! cancelled and reach IPv6 servers. adb_client is only used to start a local server.
+
+use super::backend::{exec_context, io_error, AdbBackend, BackendKind};
+use super::{
+    host, parse_devices_long, shell, sync, AdbError, AdbResult, CancelToken, DeviceInfo,
+    RebootTarget, RemoteEntry,
+};
+use adb_client::ADBServer;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+pub struct ClientBackend {
+    server_addr: SocketAddr,
+    /// Whether a local server has been started for our own host protocol requests
+    server_started: bool,
+    /// Whether each device speaks shell protocol v2, once asked; shared with workers
+    shell_v2: Arc<Mutex<HashMap<String, bool>>>,
+    cancel: CancelToken,
+}
+
+impl ClientBackend {
+    pub fn new(server_addr: SocketAddr) -> Self {
+        Self {
+            server_addr,
+            server_started: false,
+            shell_v2: Arc::default(),
+            cancel: CancelToken::default(),
+        }
+    }
+
+    /// Start a local server with `adb start-server` before the first request
+    fn ensure_server_started(&mut self) {
+        if self.server_started {
+            return;
+        }
+        self.server_started = true;
+        if self.server_addr.ip().is_loopback() {
+            ADBServer::start(&HashMap::new(), &None);
+        }
+    }
+
+    /// Send a one-shot request to the ADB server
+    fn host_query(&mut self, request: &str) -> AdbResult<String> {
+        self.ensure_server_started();
+        host::query(self.server_addr, request, &self.cancel).map_err(|e| match e.kind() {
+            io::ErrorKind::Interrupted => AdbError::Cancelled,
+            _ => AdbError::ConnectionError(e.to_string()),
+        })
+    }
+
+    /// Whether `serial` lists `shell_v2` among its features
+    fn supports_shell_v2(&mut self, serial: &str) -> bool {
+        if let Some(&supported) = self.shell_v2_cache().get(serial) {
+            return supported;
+        }
+        let Ok(features) = self.host_query(&format!("host-serial:{}:features", serial)) else {
+            return false;
+        };
+        let supported = features.split(',').any(|feature| feature == "shell_v2");
+        self.shell_v2_cache().insert(serial.to_string(), supported);
+        supported
+    }
+
+    fn shell_v2_cache(&self) -> std::sync::MutexGuard<'_, HashMap<String, bool>> {
+        self.shell_v2.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Open a device service, copying its output to `out`
+    fn service(&mut self, serial: &str, service: &str, out: &mut dyn Write) -> io::Result<()> {
+        self.ensure_server_started();
+        host::service_to(self.server_addr, serial, service, &self.cancel, out)
+    }
+}
+
+impl AdbBackend for ClientBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Client
+    }
+
+    fn for_worker(&self, cancel: CancelToken) -> Box<dyn AdbBackend> {
+        Box::new(Self {
+            server_addr: self.server_addr,
+            server_started: self.server_started,
+            shell_v2: self.shell_v2.clone(),
+            cancel,
+        })
+    }
+
+    fn version(&mut self) -> AdbResult<String> {
+        let version = self.host_query("host:version")?;
+        let revision = u32::from_str_radix(version.trim(), 16)
+            .map_err(|_| AdbError::ParseError(format!("Invalid version: {}", version)))?;
+        Ok(format!("1.0.{}", revision))
+    }
+
+    fn devices(&mut self) -> AdbResult<Vec<DeviceInfo>> {
+        Ok(parse_devices_long(&self.host_query("host:devices-l")?))
+    }
+
+    fn track_devices(&mut self, on_change: &mut dyn FnMut(Vec<DeviceInfo>)) -> AdbResult<()> {
+        self.ensure_server_started();
+        host::track_devices(self.server_addr, &self.cancel, &mut |list| {
+            on_change(parse_devices_long(list))
+        })
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::Interrupted => AdbError::Cancelled,
+            _ => AdbError::ConnectionError(e.to_string()),
+        })
+    }
+
+    fn shell(&mut self, serial: &str, command: &str, out: &mut dyn Write) -> AdbResult<()> {
+        self.ensure_server_started();
+        host::shell_to(self.server_addr, serial, command, &self.cancel, out)
+            .map_err(|e| io_error(e, "Shell command failed"))
+    }
+
+    /// Devices older than Android 7 fall back to the legacy shell without an exit status
+    fn shell_status(
+        &mut self,
+        serial: &str,
+        command: &str,
+        stdout: &mut dyn Write,
+        stderr: &mut dyn Write,
+    ) -> AdbResult<Option<u8>> {
+        if !self.supports_shell_v2(serial) {
+            return self.shell(serial, command, stdout).map(|()| None);
+        }
+        shell::run(
+            self.server_addr,
+            serial,
+            command,
+            &self.cancel,
+            stdout,
+            stderr,
+        )
+        .map_err(|e| io_error(e, "Shell command failed"))
+    }
+
+    fn exec(&mut self, serial: &str, command: &str, out: &mut dyn Write) -> AdbResult<()> {
+        self.service(serial, &format!("exec:{}", command), out)
+            .map_err(|e| io_error(e, &exec_context(command)))
+    }
+
+    /// adbd holds the connection open until the device goes down; a reset
+    /// connection means the same
+    fn reboot(&mut self, serial: &str, target: RebootTarget) -> AdbResult<()> {
+        let mut output = Vec::new();
+        match self.service(serial, &target.service(), &mut output) {
+            Ok(()) => {}
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::ConnectionReset
+                        | io::ErrorKind::ConnectionAborted
+                        | io::ErrorKind::UnexpectedEof
+                ) => {}
+            Err(e) => return Err(io_error(e, "Reboot failed")),
+        }
+
+        // adbd only writes something when the reboot was refused
+        let output = String::from_utf8_lossy(&output);
+        if output.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(AdbError::CommandFailed(output.trim().to_string()))
+        }
+    }
+
+    fn wait_for(&mut self, serial: &str, state: &str) -> AdbResult<()> {
+        self.ensure_server_started();
+        host::wait_for(self.server_addr, serial, state, &self.cancel).map_err(|e| match e.kind() {
+            io::ErrorKind::Interrupted => AdbError::Cancelled,
+            _ => AdbError::ConnectionError(e.to_string()),
+        })
+    }
+
+    /// adbd confirms with "restarting in TCP mode port: <port>"
+    fn tcpip(&mut self, serial: &str, port: u16) -> AdbResult<()> {
+        let mut output = Vec::new();
+        self.service(serial, &format!("tcpip:{}", port), &mut output)
+            .map_err(|e| io_error(e, "tcpip failed"))?;
+        let output = String::from_utf8_lossy(&output);
+        if output.starts_with("restarting") {
+            Ok(())
+        } else {
+            Err(AdbError::CommandFailed(output.trim().to_string()))
+        }
+    }
+
+    fn connect(&mut self, address: &str) -> AdbResult<String> {
+        self.host_query(&format!("host:connect:{}", address))
+    }
+
+    fn disconnect(&mut self, address: Option<&str>) -> AdbResult<String> {
+        self.host_query(&format!("host:disconnect:{}", address.unwrap_or("")))
+    }
+
+    fn pair(&mut self, address: &str, code: &str) -> AdbResult<String> {
+        self.host_query(&format!("host:pair:{}:{}", code, address))
+    }
+
+    fn list_dir(&mut self, serial: &str, path: &str) -> AdbResult<Vec<RemoteEntry>> {
+        self.ensure_server_started();
+        Ok(sync::list_dir(
+            self.server_addr,
+            serial,
+            path,
+            &self.cancel,
+        )?)
+    }
+
+    fn file_size(&mut self, serial: &str, path: &str) -> AdbResult<u64> {
+        self.ensure_server_started();
+        sync::file_size(self.server_addr, serial, path, &self.cancel)
+            .map_err(|e| io_error(e, "Stat failed"))
+    }
+
+    fn push(&mut self, serial: &str, input: &mut dyn Read, path: &str) -> AdbResult<()> {
+        self.ensure_server_started();
+        sync::push(self.server_addr, serial, input, path, &self.cancel)
+            .map_err(|e| io_error(e, "Push failed"))
+    }
+
+    fn pull(&mut self, serial: &str, path: &str, out: &mut dyn Write) -> AdbResult<()> {
+        self.ensure_server_started();
+        sync::pull(self.server_addr, serial, path, &self.cancel, out)
+            .map_err(|e| io_error(e, "Pull failed"))
+    }
+}
@@ -0,0 +1,445 @@
+//! Parsers for `dumpsys` output
+//!
+//! `dumpsys` prints human readable text whose layout shifts between Android
+//! releases. The parsers only pick out the fields we show and ignore the rest,
+//! so unknown lines never fail a parse.
+
+use super::{AdbError, AdbResult};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// `key: value` pairs of an indented dump; the first occurrence of a key wins
+fn fields(text: &str) -> HashMap<&str, &str> {
+    let mut fields = HashMap::new();
+    for (key, value) in text.lines().filter_map(|line| line.trim().split_once(':')) {
+        fields.entry(key.trim()).or_insert(value.trim());
+    }
+    fields
+}
+
+/// Kilobytes from a meminfo figure such as `3,844,252K` or `3844252 kB`
+fn parse_kb(value: &str) -> Option<u64> {
+    value
+        .split_whitespace()
+        .next()?
+        .trim_end_matches('K')
+        .replace(',', "")
+        .parse()
+        .ok()
+}
+
+/// Text between `start` and the next `end` after it
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let rest = &text[text.find(start)? + start.len()..];
+    Some(&rest[..rest.find(end)?])
+}
+
+/// Whitespace-delimited word following `key`
+fn word_after<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text[text.find(key)? + key.len()..]
+        .split_whitespace()
+        .next()
+}
+
+/// Charging state reported by `dumpsys battery`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChargeStatus {
+    Unknown,
+    Charging,
+    Discharging,
+    NotCharging,
+    Full,
+}
+
+impl ChargeStatus {
+    /// `BatteryManager.BATTERY_STATUS_*`
+    fn from_code(code: &str) -> Self {
+        match code {
+            "2" => ChargeStatus::Charging,
+            "3" => ChargeStatus::Discharging,
+            "4" => ChargeStatus::NotCharging,
+            "5" => ChargeStatus::Full,
+            _ => ChargeStatus::Unknown,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChargeStatus::Unknown => "Unknown",
+            ChargeStatus::Charging => "Charging",
+            ChargeStatus::Discharging => "Discharging",
+            ChargeStatus::NotCharging => "Not charging",
+            ChargeStatus::Full => "Full",
+        }
+    }
+}
+
+/// Battery health reported by `dumpsys battery`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryHealth {
+    Unknown,
+    Good,
+    Overheat,
+    Dead,
+    OverVoltage,
+    Failure,
+    Cold,
+}
+
+impl BatteryHealth {
+    /// `BatteryManager.BATTERY_HEALTH_*`
+    fn from_code(code: &str) -> Self {
+        match code {
+            "2" => BatteryHealth::Good,
+            "3" => BatteryHealth::Overheat,
+            "4" => BatteryHealth::Dead,
+            "5" => BatteryHealth::OverVoltage,
+            "6" => BatteryHealth::Failure,
+            "7" => BatteryHealth::Cold,
+            _ => BatteryHealth::Unknown,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BatteryHealth::Unknown => "Unknown",
+            BatteryHealth::Good => "Good",
+            BatteryHealth::Overheat => "Overheat",
+            BatteryHealth::Dead => "Dead",
+            BatteryHealth::OverVoltage => "Over voltage",
+            BatteryHealth::Failure => "Failure",
+            BatteryHealth::Cold => "Cold",
+        }
+    }
+}
+
+/// Parsed `dumpsys battery`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatteryStatus {
+    pub level: u32,
+    pub scale: u32,
+    pub status: ChargeStatus,
+    pub health: BatteryHealth,
+    /// Chargers currently supplying power, e.g. `["USB"]`
+    pub plugged: Vec<String>,
+    pub voltage_mv: Option<u32>,
+    pub temperature_c: Option<f32>,
+    pub technology: Option<String>,
+}
+
+impl BatteryStatus {
+    pub fn parse(text: &str) -> AdbResult<Self> {
+        let fields = fields(text);
+        let level = fields
+            .get("level")
+            .and_then(|level| level.parse().ok())
+            .ok_or_else(|| AdbError::ParseError("No battery level in dumpsys output".into()))?;
+
+        let plugged = ["AC", "USB", "Wireless", "Dock"]
+            .into_iter()
+            .filter(|source| fields.get(format!("{} powered", source).as_str()) == Some(&"true"))
+            .map(str::to_string)
+            .collect();
+
+        Ok(Self {
+            level,
+            scale: fields
+                .get("scale")
+                .and_then(|scale| scale.parse().ok())
+                .filter(|&scale| scale > 0)
+                .unwrap_or(100),
+            status: ChargeStatus::from_code(fields.get("status").copied().unwrap_or_default()),
+            health: BatteryHealth::from_code(fields.get("health").copied().unwrap_or_default()),
+            plugged,
+            voltage_mv: fields.get("voltage").and_then(|v| v.parse().ok()),
+            // Reported in tenths of a degree
+            temperature_c: fields
+                .get("temperature")
+                .and_then(|t| t.parse::<i32>().ok())
+                .map(|t| t as f32 / 10.0),
+            technology: fields
+                .get("technology")
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_string()),
+        })
+    }
+
+    /// Charge level in percent
+    pub fn percent(&self) -> u16 {
+        (self.level * 100 / self.scale).min(100) as u16
+    }
+}
+
+/// One row of meminfo's "by process" table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProcessMemory {
+    pub name: String,
+    pub pid: Option<u32>,
+    pub kb: u64,
+}
+
+/// Parsed `dumpsys meminfo`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemInfo {
+    pub total_kb: u64,
+    pub free_kb: u64,
+    pub used_kb: u64,
+    pub lost_kb: Option<u64>,
+    /// Processes by PSS, largest first
+    pub processes: Vec<ProcessMemory>,
+}
+
+impl MemInfo {
+    pub fn parse(text: &str) -> AdbResult<Self> {
+        let fields = fields(text);
+        let total_kb = fields
+            .get("Total RAM")
+            .and_then(|v| parse_kb(v))
+            .ok_or_else(|| AdbError::ParseError("No total RAM in dumpsys output".into()))?;
+        let free_kb = fields
+            .get("Free RAM")
+            .and_then(|v| parse_kb(v))
+            .unwrap_or(0);
+        let used_kb = fields
+            .get("Used RAM")
+            .and_then(|v| parse_kb(v))
+            .unwrap_or(total_kb.saturating_sub(free_kb));
+
+        Ok(Self {
+            total_kb,
+            free_kb,
+            used_kb,
+            lost_kb: fields.get("Lost RAM").and_then(|v| parse_kb(v)),
+            processes: parse_processes(text),
+        })
+    }
+
+    /// Used RAM as a fraction of the total
+    pub fn used_ratio(&self) -> f64 {
+        if self.total_kb == 0 {
+            return 0.0;
+        }
+        (self.used_kb as f64 / self.total_kb as f64).min(1.0)
+    }
+}
+
+/// Rows of "Total PSS by process", or the first per-process table on newer releases
+fn parse_processes(text: &str) -> Vec<ProcessMemory> {
+    let lines: Vec<&str> = text.lines().collect();
+    let Some(header) = lines
+        .iter()
+        .position(|line| line.trim() == "Total PSS by process:")
+        .or_else(|| {
+            lines
+                .iter()
+                .position(|line| line.trim().ends_with("by process:"))
+        })
+    else {
+        return Vec::new();
+    };
+
+    lines[header + 1..]
+        .iter()
+        .map(|line| line.trim())
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| {
+            let (kb, rest) = line.split_once(": ")?;
+            let (name, pid) = match rest.split_once(" (pid ") {
+                Some((name, pid)) => (
+                    name,
+                    pid.split(|c: char| !c.is_ascii_digit())
+                        .next()
+                        .and_then(|pid| pid.parse().ok()),
+                ),
+                None => (rest, None),
+            };
+            Some(ProcessMemory {
+                name: name.to_string(),
+                pid,
+                kb: parse_kb(kb)?,
+            })
+        })
+        .collect()
+}
+
+/// A network known to ConnectivityService
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetworkStatus {
+    pub id: u32,
+    /// Transports such as `WIFI` or `CELLULAR`
+    pub transports: Vec<String>,
+    pub state: Option<String>,
+    pub interface: Option<String>,
+    pub addresses: Vec<String>,
+    pub ssid: Option<String>,
+    /// Internet access has been confirmed
+    pub validated: bool,
+    pub metered: bool,
+}
+
+/// Parsed `dumpsys connectivity`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConnectivityState {
+    pub default_network: Option<u32>,
+    pub networks: Vec<NetworkStatus>,
+}
+
+impl ConnectivityState {
+    pub fn parse(text: &str) -> AdbResult<Self> {
+        let default = fields(text).get("Active default network").copied();
+        let networks: Vec<NetworkStatus> = current_networks(text)
+            .filter_map(parse_network_agent)
+            .collect();
+
+        if default.is_none() && networks.is_empty() {
+            return Err(AdbError::ParseError(
+                "No networks in dumpsys output".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            // "none" when the device is offline
+            default_network: default.and_then(|id| id.parse().ok()),
+            networks,
+        })
+    }
+
+    /// Network used for traffic by default
+    pub fn default(&self) -> Option<&NetworkStatus> {
+        self.networks
+            .iter()
+            .find(|network| Some(network.id) == self.default_network)
+    }
+}
+
+/// `NetworkAgentInfo{…}` lines of the "Current Networks" section
+fn current_networks(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .skip_while(|line| line.trim() != "Current Networks:")
+        .skip(1)
+        // The section ends at the next unindented header
+        .take_while(|line| line.is_empty() || line.starts_with(' '))
+        .map(str::trim)
+        .filter(|line| line.starts_with("NetworkAgentInfo{"))
+}
+
+fn parse_network_agent(line: &str) -> Option<NetworkStatus> {
+    let id = between(line, "network{", "}")?.parse().ok()?;
+    let capabilities: Vec<&str> = word_after(line, "Capabilities: ")
+        .map(|caps| caps.split('&').collect())
+        .unwrap_or_default();
+
+    // `ni{WIFI CONNECTED extra: }`, or `ni{[type: WIFI[], state: CONNECTED/CONNECTED, …]}`
+    let state = match between(line, "ni{", "}") {
+        Some(info) if info.starts_with('[') => {
+            between(info, "state: ", ",").map(|s| s.split('/').next().unwrap_or(s))
+        }
+        Some(info) => info.split_whitespace().nth(1),
+        None => None,
+    };
+
+    Some(NetworkStatus {
+        id,
+        transports: word_after(line, "Transports: ")
+            .map(|t| t.split('|').map(str::to_string).collect())
+            .unwrap_or_default(),
+        state: state.map(str::to_string),
+        interface: word_after(line, "InterfaceName: ").map(str::to_string),
+        addresses: between(line, "LinkAddresses: [", "]")
+            .map(|list| {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|address| !address.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ssid: between(line, "SSID: \"", "\"").map(str::to_string),
+        validated: capabilities.contains(&"VALIDATED"),
+        metered: !capabilities.contains(&"NOT_METERED"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BATTERY: &str = include_str!("../../tests/fixtures/dumpsys_battery.txt");
+    const MEMINFO: &str = include_str!("../../tests/fixtures/dumpsys_meminfo.txt");
+    const CONNECTIVITY: &str = include_str!("../../tests/fixtures/dumpsys_connectivity.txt");
+
+    #[test]
+    fn test_parse_battery() {
+        let battery = BatteryStatus::parse(BATTERY).unwrap();
+        assert_eq!(battery.percent(), 85);
+        assert_eq!(battery.status, ChargeStatus::Charging);
+        assert_eq!(battery.health, BatteryHealth::Good);
+        assert_eq!(battery.plugged, ["USB"]);
+        assert_eq!(battery.voltage_mv, Some(4262));
+        assert_eq!(battery.temperature_c, Some(28.5));
+        assert_eq!(battery.technology.as_deref(), Some("Li-ion"));
+
+        assert!(BatteryStatus::parse("Can't find service: battery").is_err());
+    }
+
+    #[test]
+    fn test_parse_meminfo() {
+        let mem = MemInfo::parse(MEMINFO).unwrap();
+        assert_eq!(mem.total_kb, 3_844_252);
+        assert_eq!(mem.free_kb, 1_795_716);
+        assert_eq!(mem.used_kb, 1_923_544);
+        assert_eq!(mem.lost_kb, Some(125_004));
+        assert_eq!(mem.processes.len(), 5);
+        assert_eq!(
+            mem.processes[1],
+            ProcessMemory {
+                name: "com.google.android.apps.nexuslauncher".to_string(),
+                pid: Some(2590),
+                kb: 187_404,
+            }
+        );
+        assert!((mem.used_ratio() - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_meminfo_old_format() {
+        let mem =
+            MemInfo::parse("Total RAM: 1000 kB (status normal)\n Free RAM: 250 kB\n").unwrap();
+        assert_eq!((mem.total_kb, mem.free_kb, mem.used_kb), (1000, 250, 750));
+        assert!(mem.processes.is_empty());
+    }
+
+    #[test]
+    fn test_parse_connectivity() {
+        let state = ConnectivityState::parse(CONNECTIVITY).unwrap();
+        assert_eq!(state.default_network, Some(101));
+        assert_eq!(state.networks.len(), 2);
+
+        let cellular = &state.networks[0];
+        assert_eq!(cellular.transports, ["CELLULAR"]);
+        assert_eq!(cellular.interface.as_deref(), Some("rmnet_data1"));
+        assert!(cellular.validated && cellular.metered);
+
+        let wifi = state.default().unwrap();
+        assert_eq!(wifi.state.as_deref(), Some("CONNECTED"));
+        assert_eq!(wifi.ssid.as_deref(), Some("HomeNet"));
+        assert_eq!(
+            wifi.addresses,
+            ["fe80::a8bb:ccff:fedd:eeff/64", "192.168.1.23/24"]
+        );
+        assert!(wifi.validated && !wifi.metered);
+    }
+
+    #[test]
+    fn test_parse_connectivity_offline() {
+        let state = ConnectivityState::parse(
+            "Active default network: none\n\nCurrent Networks:\n\nStatus for known UIDs:\n",
+        )
+        .unwrap();
+        assert_eq!(state.default_network, None);
+        assert!(state.networks.is_empty() && state.default().is_none());
+        assert!(ConnectivityState::parse("").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! ADB host protocol helpers
+//!
+//! Requests are sent as a 4 digit hex length followed by the request text; the
+//! server answers `OKAY` or `FAIL` with a length-prefixed message.
+
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Cancels the server connection a background command is blocked on
+///
+/// Connections opened through [`connect`] are registered with the token; cancelling
+/// shuts the current one down so blocked reads return, and refuses new ones.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelInner>,
+}
+
+#[derive(Debug, Default)]
+struct CancelInner {
+    cancelled: AtomicBool,
+    stream: Mutex<Option<TcpStream>>,
+}
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        if let Some(stream) = self.lock().take() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<TcpStream>> {
+        self.inner
+            .stream
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn check(&self) -> io::Result<()> {
+        if self.is_cancelled() {
+            Err(io::Error::new(io::ErrorKind::Interrupted, "Cancelled"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Connect to the server, registering the connection with `cancel`
+pub(crate) fn connect(server: SocketAddr, cancel: &CancelToken) -> io::Result<TcpStream> {
+    cancel.check()?;
+    let stream = TcpStream::connect(server)?;
+    *cancel.lock() = Some(stream.try_clone()?);
+    // Cancelled while connecting: the token never saw this stream
+    if let Err(e) = cancel.check() {
+        let _ = stream.shutdown(Shutdown::Both);
+        return Err(e);
+    }
+    Ok(stream)
+}
+
+/// Send a host protocol request and wait for `OKAY`
+pub(crate) fn send_request(stream: &mut TcpStream, request: &str) -> io::Result<()> {
+    stream.write_all(format!("{:04x}{}", request.len(), request).as_bytes())?;
+    read_status(stream)
+}
+
+/// Read an `OKAY` / `FAIL <hex len><message>` status
+pub(crate) fn read_status(stream: &mut TcpStream) -> io::Result<()> {
+    let mut status = [0u8; 4];
+    stream.read_exact(&mut status)?;
+    match &status {
+        b"OKAY" => Ok(()),
+        b"FAIL" => {
+            let message = read_hex_string(stream)?;
+            Err(io::Error::other(message))
+        }
+        other => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unexpected response {:?}", String::from_utf8_lossy(other)),
+        )),
+    }
+}
+
+/// Read a length-prefixed (4 hex digits) string
+pub(crate) fn read_hex_string(stream: &mut impl Read) -> io::Result<String> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let len = usize::from_str_radix(&String::from_utf8_lossy(&len), 16)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body)?;
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+/// Send a one-shot host request and read its length-prefixed reply
+pub(crate) fn query(server: SocketAddr, request: &str, cancel: &CancelToken) -> io::Result<String> {
+    cancellable(cancel, || {
+        let mut stream = connect(server, cancel)?;
+        send_request(&mut stream, request)?;
+        read_hex_string(&mut stream)
+    })
+}
+
+/// Run `command` on `serial`, copying its output to `out` as it arrives
+pub(crate) fn shell_to(
+    server: SocketAddr,
+    serial: &str,
+    command: &str,
+    cancel: &CancelToken,
+    out: &mut (impl Write + ?Sized),
+) -> io::Result<()> {
+    service_to(server, serial, &format!("shell:{}", command), cancel, out)
+}
+
+/// Open a device service such as `reboot:recovery`, copying its output to `out` until EOF
+pub(crate) fn service_to(
+    server: SocketAddr,
+    serial: &str,
+    service: &str,
+    cancel: &CancelToken,
+    out: &mut (impl Write + ?Sized),
+) -> io::Result<()> {
+    cancellable(cancel, || {
+        let mut stream = connect(server, cancel)?;
+        send_request(&mut stream, &format!("host:transport:{}", serial))?;
+        send_request(&mut stream, service)?;
+        io::copy(&mut stream, out)?;
+        Ok(())
+    })
+}
+
+/// Follow `host:track-devices-l`, passing every device list the server sends to `on_change`
+///
+/// The server sends the current list right away and a new one on each change. The
+/// connection only ends when the server goes away or `cancel` fires.
+pub(crate) fn track_devices(
+    server: SocketAddr,
+    cancel: &CancelToken,
+    on_change: &mut dyn FnMut(&str),
+) -> io::Result<()> {
+    cancellable(cancel, || {
+        let mut stream = connect(server, cancel)?;
+        send_request(&mut stream, "host:track-devices-l")?;
+        loop {
+            on_change(&read_hex_string(&mut stream)?);
+        }
+    })
+}
+
+/// Block until `serial` is attached in `state` (`device`, `recovery`, `sideload`, ...)
+///
+/// The server acknowledges the request, then sends a second `OKAY` once the state is reached.
+pub(crate) fn wait_for(
+    server: SocketAddr,
+    serial: &str,
+    state: &str,
+    cancel: &CancelToken,
+) -> io::Result<()> {
+    cancellable(cancel, || {
+        let mut stream = connect(server, cancel)?;
+        send_request(
+            &mut stream,
+            &format!("host-serial:{}:wait-for-any-{}", serial, state),
+        )?;
+        read_status(&mut stream)
+    })
+}
+
+/// Run `f`, reporting any outcome as cancelled once `cancel` fired
+///
+/// A shut down socket reads as EOF, so partial output must not pass as success.
+pub(crate) fn cancellable<T>(
+    cancel: &CancelToken,
+    f: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    let result = f();
+    cancel.check()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_cancel_unblocks_read() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let cancel = CancelToken::default();
+
+        let reader = {
+            let cancel = cancel.clone();
+            std::thread::spawn(move || {
+                shell_to(addr, "serial", "sleep 100", &cancel, &mut Vec::new())
+            })
+        };
+        // Acknowledge both requests, then go quiet
+        let (mut server, _) = listener.accept().unwrap();
+        for _ in 0..2 {
+            let mut len = [0u8; 4];
+            server.read_exact(&mut len).unwrap();
+            let len = usize::from_str_radix(std::str::from_utf8(&len).unwrap(), 16).unwrap();
+            server.read_exact(&mut vec![0u8; len]).unwrap();
+            server.write_all(b"OKAY").unwrap();
+        }
+
+        cancel.cancel();
+        let error = reader.join().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+        assert!(connect(addr, &cancel).is_err());
+    }
+
+    #[test]
+    fn test_wait_for_reads_second_okay() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let waiter = std::thread::spawn(move || {
+            wait_for(addr, "emulator-5554", "recovery", &CancelToken::default())
+        });
+        let (mut server, _) = listener.accept().unwrap();
+        let request = read_hex_string(&mut server).unwrap();
+        assert_eq!(request, "host-serial:emulator-5554:wait-for-any-recovery");
+        server.write_all(b"OKAY").unwrap();
+        assert!(!waiter.is_finished());
+        server.write_all(b"OKAY").unwrap();
+        waiter.join().unwrap().unwrap();
+    }
+}
@@ -0,0 +1,121 @@
+//! Input replayed on the device
+//!
+//! Taps, swipes, key events and text become `input` shell commands.
+
+use super::shell_quote;
+use std::time::Duration;
+
+/// Android key codes sent with `input keyevent`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keycode {
+    Back,
+    Home,
+    AppSwitch,
+    Menu,
+    Power,
+    VolumeUp,
+    VolumeDown,
+    Enter,
+    Del,
+    ForwardDel,
+    Tab,
+    DpadUp,
+    DpadDown,
+    DpadLeft,
+    DpadRight,
+    MoveHome,
+    MoveEnd,
+    PageUp,
+    PageDown,
+}
+
+impl Keycode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Keycode::Back => "KEYCODE_BACK",
+            Keycode::Home => "KEYCODE_HOME",
+            Keycode::AppSwitch => "KEYCODE_APP_SWITCH",
+            Keycode::Menu => "KEYCODE_MENU",
+            Keycode::Power => "KEYCODE_POWER",
+            Keycode::VolumeUp => "KEYCODE_VOLUME_UP",
+            Keycode::VolumeDown => "KEYCODE_VOLUME_DOWN",
+            Keycode::Enter => "KEYCODE_ENTER",
+            Keycode::Del => "KEYCODE_DEL",
+            Keycode::ForwardDel => "KEYCODE_FORWARD_DEL",
+            Keycode::Tab => "KEYCODE_TAB",
+            Keycode::DpadUp => "KEYCODE_DPAD_UP",
+            Keycode::DpadDown => "KEYCODE_DPAD_DOWN",
+            Keycode::DpadLeft => "KEYCODE_DPAD_LEFT",
+            Keycode::DpadRight => "KEYCODE_DPAD_RIGHT",
+            Keycode::MoveHome => "KEYCODE_MOVE_HOME",
+            Keycode::MoveEnd => "KEYCODE_MOVE_END",
+            Keycode::PageUp => "KEYCODE_PAGE_UP",
+            Keycode::PageDown => "KEYCODE_PAGE_DOWN",
+        }
+    }
+}
+
+/// Input to replay on the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceInput {
+    Tap {
+        x: u32,
+        y: u32,
+    },
+    /// A drag, or a long press when both ends are the same
+    Swipe {
+        from: (u32, u32),
+        to: (u32, u32),
+        duration: Duration,
+    },
+    Key(Keycode),
+    Text(String),
+}
+
+impl DeviceInput {
+    /// Shell command replaying the input
+    pub fn shell_command(&self) -> String {
+        match self {
+            DeviceInput::Tap { x, y } => format!("input tap {} {}", x, y),
+            DeviceInput::Swipe { from, to, duration } => format!(
+                "input swipe {} {} {} {} {}",
+                from.0,
+                from.1,
+                to.0,
+                to.1,
+                duration.as_millis()
+            ),
+            DeviceInput::Key(key) => format!("input keyevent {}", key.name()),
+            // `input text` reads %s as a space
+            DeviceInput::Text(text) => {
+                format!("input text {}", shell_quote(&text.replace(' ', "%s")))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_commands() {
+        assert_eq!(
+            DeviceInput::Key(Keycode::Back).shell_command(),
+            "input keyevent KEYCODE_BACK"
+        );
+        assert_eq!(
+            DeviceInput::Text("it's a test".to_string()).shell_command(),
+            "input text 'it'\\''s%sa%stest'"
+        );
+        assert_eq!(
+            DeviceInput::Swipe {
+                from: (1, 2),
+                to: (3, 4),
+                duration: Duration::from_millis(250),
+            }
+            .shell_command(),
+            "input swipe 1 2 3 4 250"
+        );
+    }
+}
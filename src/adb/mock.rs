@@ -0,0 +1,441 @@
+//! In-memory backend
+//!
+//! Answers shell commands from a table of canned responses and keeps files in a map.
+//! Copies made for workers share the same state, so a test can script a device,
+//! drive the UI and then check which commands reached it.
+
+use super::backend::{AdbBackend, BackendKind};
+use super::{
+    AdbError, AdbResult, CancelToken, DeviceInfo, RebootTarget, RemoteEntry, ShellOutput, Transport,
+};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+/// Mode bits of a regular file and a directory
+const FILE_MODE: u32 = 0o100644;
+const DIR_MODE: u32 = 0o040755;
+
+/// How often device tracking looks for changes
+const TRACK_POLL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Default)]
+struct MockState {
+    devices: Vec<DeviceInfo>,
+    /// Shell command prefix and its output; the longest matching prefix wins
+    responses: Vec<(String, ShellOutput)>,
+    /// Device files by absolute path
+    files: BTreeMap<String, Vec<u8>>,
+    /// Requests received, e.g. `shell emulator-5554 getprop`
+    calls: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MockBackend {
+    state: Arc<Mutex<MockState>>,
+    cancel: CancelToken,
+}
+
+impl MockBackend {
+    /// A backend without devices
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An emulator with battery, memory, packages, processes and a few files
+    pub fn demo() -> Self {
+        Self::new()
+            .with_device("emulator-5554", Some("sdk_gphone64_x86_64"))
+            .respond("getprop ro.product.model", "sdk_gphone64_x86_64\n")
+            .respond("getprop", "[ro.product.model]: [sdk_gphone64_x86_64]\n[ro.build.version.release]: [14]\n[ro.build.version.sdk]: [34]\n")
+            .respond("dumpsys battery", "Current Battery Service state:\n  AC powered: true\n  USB powered: false\n  Wireless powered: false\n  status: 2\n  health: 2\n  present: true\n  level: 87\n  scale: 100\n  voltage: 4200\n  temperature: 250\n  technology: Li-ion\n")
+            .respond("dumpsys meminfo", "Total RAM: 2,014,540K (status normal)\n Free RAM:   912,332K\n Used RAM: 1,102,208K\n")
+            .respond("wm size", "Physical size: 1080x2400\n")
+            .respond("wm density", "Physical density: 420\n")
+            .respond("pm list packages -f", "package:/data/app/com.example.app/base.apk=com.example.app\npackage:/system/app/Settings/Settings.apk=com.android.settings\n")
+            .respond("pm list packages -3", "package:com.example.app\n")
+            .respond("pm list packages -d", "")
+            .respond("pm list packages", "package:com.example.app\npackage:com.android.settings\n")
+            .respond("ps", "USER           PID  PPID     VSZ    RSS WCHAN            ADDR S NAME\nroot             1     0 1093340   4100 0                   0 S init\nu0_a123       4242   321 1432100  90112 0                   0 S com.example.app\n")
+            .respond("ip addr show wlan0", "3: wlan0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500\n    inet 10.0.2.16/24 brd 10.0.2.255 scope global wlan0\n")
+            .respond("ip -f inet addr show wlan0", "    inet 10.0.2.16/24 brd 10.0.2.255 scope global wlan0\n")
+            .respond_with(
+                "pm uninstall com.android.settings",
+                ShellOutput {
+                    stdout: "Failure [DELETE_FAILED_INTERNAL_ERROR]\n".to_string(),
+                    stderr: String::new(),
+                    exit_code: Some(1),
+                },
+            )
+            .respond("logcat", "10-17 09:14:02.123  1234  1301 I ActivityManager: Start proc 4242:com.example.app/u0a123\n")
+            .with_file("/sdcard/Download/readme.txt", b"Served by the mock backend\n")
+            .with_file("/sdcard/DCIM/Camera/IMG_0001.jpg", &[0xff, 0xd8, 0xff, 0xd9])
+    }
+
+    /// Attach an online device
+    pub fn with_device(self, serial: &str, model: Option<&str>) -> Self {
+        self.lock().devices.push(DeviceInfo {
+            serial: serial.to_string(),
+            state: "device".to_string(),
+            model: model.map(str::to_string),
+            device: None,
+        });
+        self
+    }
+
+    /// Move an attached device to `state`, e.g. `offline` or `unauthorized`
+    pub fn set_device_state(&self, serial: &str, state: &str) {
+        for device in self.lock().devices.iter_mut() {
+            if device.serial == serial {
+                device.state = state.to_string();
+            }
+        }
+    }
+
+    /// Detach a device
+    pub fn unplug(&self, serial: &str) {
+        self.lock().devices.retain(|device| device.serial != serial);
+    }
+
+    /// Answer shell and exec commands starting with `prefix` with `output`
+    pub fn respond(self, prefix: &str, output: &str) -> Self {
+        self.respond_with(
+            prefix,
+            ShellOutput {
+                stdout: output.to_string(),
+                stderr: String::new(),
+                exit_code: Some(0),
+            },
+        )
+    }
+
+    /// Answer commands starting with `prefix` with separate streams and an exit status
+    pub fn respond_with(self, prefix: &str, output: ShellOutput) -> Self {
+        self.lock().responses.push((prefix.to_string(), output));
+        self
+    }
+
+    /// Put a file on every device
+    pub fn with_file(self, path: &str, contents: &[u8]) -> Self {
+        self.lock()
+            .files
+            .insert(path.to_string(), contents.to_vec());
+        self
+    }
+
+    /// Requests received so far, oldest first
+    pub fn calls(&self) -> Vec<String> {
+        self.lock().calls.clone()
+    }
+
+    /// Contents of a device file
+    pub fn file(&self, path: &str) -> Option<Vec<u8>> {
+        self.lock().files.get(path).cloned()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Log a request, failing when cancelled
+    fn call(&self, request: String) -> AdbResult<MutexGuard<'_, MockState>> {
+        if self.cancel.is_cancelled() {
+            return Err(AdbError::Cancelled);
+        }
+        let mut state = self.lock();
+        state.calls.push(request);
+        Ok(state)
+    }
+
+    /// Log a request to `serial`, failing when it is not attached
+    fn device_call(&self, serial: &str, request: String) -> AdbResult<MutexGuard<'_, MockState>> {
+        let state = self.call(request)?;
+        if state.devices.iter().any(|device| device.serial == serial) {
+            Ok(state)
+        } else {
+            Err(AdbError::DeviceNotFound)
+        }
+    }
+
+    /// Log a request and look up its response; unknown commands succeed silently
+    fn respond_to(&self, serial: &str, request: &str, command: &str) -> AdbResult<ShellOutput> {
+        let state = self.device_call(serial, format!("{} {} {}", request, serial, command))?;
+        Ok(state
+            .responses
+            .iter()
+            .filter(|(prefix, _)| command.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, output)| output.clone())
+            .unwrap_or_default())
+    }
+}
+
+impl AdbBackend for MockBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Mock
+    }
+
+    fn for_worker(&self, cancel: CancelToken) -> Box<dyn AdbBackend> {
+        Box::new(Self {
+            state: self.state.clone(),
+            cancel,
+        })
+    }
+
+    fn version(&mut self) -> AdbResult<String> {
+        drop(self.call("version".to_string())?);
+        Ok("1.0.41".to_string())
+    }
+
+    fn devices(&mut self) -> AdbResult<Vec<DeviceInfo>> {
+        Ok(self.call("devices".to_string())?.devices.clone())
+    }
+
+    /// Reports the devices whenever a test changes them
+    fn track_devices(&mut self, on_change: &mut dyn FnMut(Vec<DeviceInfo>)) -> AdbResult<()> {
+        drop(self.call("track-devices".to_string())?);
+        let mut reported = None;
+        while !self.cancel.is_cancelled() {
+            let devices = self.lock().devices.clone();
+            if reported.as_ref() != Some(&devices) {
+                on_change(devices.clone());
+                reported = Some(devices);
+            }
+            thread::sleep(TRACK_POLL);
+        }
+        Err(AdbError::Cancelled)
+    }
+
+    fn shell(&mut self, serial: &str, command: &str, out: &mut dyn Write) -> AdbResult<()> {
+        let output = self.respond_to(serial, "shell", command)?;
+        out.write_all(output.text().as_bytes())?;
+        Ok(())
+    }
+
+    fn shell_status(
+        &mut self,
+        serial: &str,
+        command: &str,
+        stdout: &mut dyn Write,
+        stderr: &mut dyn Write,
+    ) -> AdbResult<Option<u8>> {
+        let output = self.respond_to(serial, "shell", command)?;
+        stdout.write_all(output.stdout.as_bytes())?;
+        stderr.write_all(output.stderr.as_bytes())?;
+        Ok(output.exit_code)
+    }
+
+    fn exec(&mut self, serial: &str, command: &str, out: &mut dyn Write) -> AdbResult<()> {
+        let output = self.respond_to(serial, "exec", command)?;
+        out.write_all(output.stdout.as_bytes())?;
+        Ok(())
+    }
+
+    fn reboot(&mut self, serial: &str, target: RebootTarget) -> AdbResult<()> {
+        self.device_call(serial, format!("reboot {} {}", serial, target.name()))
+            .map(drop)
+    }
+
+    fn wait_for(&mut self, serial: &str, state: &str) -> AdbResult<()> {
+        self.device_call(serial, format!("wait-for {} {}", serial, state))
+            .map(drop)
+    }
+
+    fn tcpip(&mut self, serial: &str, port: u16) -> AdbResult<()> {
+        self.device_call(serial, format!("tcpip {} {}", serial, port))
+            .map(drop)
+    }
+
+    fn connect(&mut self, address: &str) -> AdbResult<String> {
+        let mut state = self.call(format!("connect {}", address))?;
+        if state.devices.iter().any(|device| device.serial == address) {
+            return Ok(format!("already connected to {}", address));
+        }
+        state.devices.push(DeviceInfo {
+            serial: address.to_string(),
+            state: "device".to_string(),
+            model: None,
+            device: None,
+        });
+        Ok(format!("connected to {}", address))
+    }
+
+    fn disconnect(&mut self, address: Option<&str>) -> AdbResult<String> {
+        let mut state = self.call(format!("disconnect {}", address.unwrap_or("")))?;
+        let before = state.devices.len();
+        state.devices.retain(|device| match address {
+            Some(address) => device.serial != address,
+            None => device.transport() != Transport::Tcp,
+        });
+        Ok(match address {
+            Some(address) if state.devices.len() < before => format!("disconnected {}", address),
+            Some(address) => format!("no such device '{}'", address),
+            None => String::new(),
+        })
+    }
+
+    fn pair(&mut self, address: &str, code: &str) -> AdbResult<String> {
+        drop(self.call(format!("pair {} {}", address, code))?);
+        Ok(format!(
+            "Successfully paired to {} [guid=adb-mock]",
+            address
+        ))
+    }
+
+    /// Directories exist wherever a file lies below them
+    fn list_dir(&mut self, serial: &str, path: &str) -> AdbResult<Vec<RemoteEntry>> {
+        let state = self.device_call(serial, format!("list {} {}", serial, path))?;
+        let prefix = format!("{}/", path.trim_end_matches('/'));
+        let mut entries: Vec<RemoteEntry> = Vec::new();
+        for (file, contents) in &state.files {
+            let Some(rest) = file.strip_prefix(&prefix) else {
+                continue;
+            };
+            let (name, is_dir) = match rest.split_once('/') {
+                Some((dir, _)) => (dir, true),
+                None => (rest, false),
+            };
+            if entries.iter().any(|entry| entry.name == name) {
+                continue;
+            }
+            entries.push(RemoteEntry {
+                name: name.to_string(),
+                mode: if is_dir { DIR_MODE } else { FILE_MODE },
+                size: if is_dir { 0 } else { contents.len() as u64 },
+                mtime: 0,
+            });
+        }
+        if entries.is_empty() && path != "/" {
+            return Err(AdbError::CommandFailed(format!(
+                "{}: No such file or directory",
+                path
+            )));
+        }
+        Ok(entries)
+    }
+
+    fn file_size(&mut self, serial: &str, path: &str) -> AdbResult<u64> {
+        let state = self.device_call(serial, format!("stat {} {}", serial, path))?;
+        state
+            .files
+            .get(path)
+            .map(|contents| contents.len() as u64)
+            .ok_or_else(|| AdbError::CommandFailed(format!("{}: No such file", path)))
+    }
+
+    fn push(&mut self, serial: &str, input: &mut dyn Read, path: &str) -> AdbResult<()> {
+        drop(self.device_call(serial, format!("push {} {}", serial, path))?);
+        let mut contents = Vec::new();
+        input.read_to_end(&mut contents)?;
+        self.lock().files.insert(path.to_string(), contents);
+        Ok(())
+    }
+
+    fn pull(&mut self, serial: &str, path: &str, out: &mut dyn Write) -> AdbResult<()> {
+        let state = self.device_call(serial, format!("pull {} {}", serial, path))?;
+        let contents = state.files.get(path).cloned().ok_or_else(|| {
+            AdbError::CommandFailed(format!("Pull failed: {}: No such file", path))
+        })?;
+        drop(state);
+        out.write_all(&contents)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let mut backend = MockBackend::demo();
+        let mut output = Vec::new();
+        backend
+            .shell("emulator-5554", "pm list packages -3", &mut output)
+            .unwrap();
+        assert_eq!(output, b"package:com.example.app\n");
+
+        output.clear();
+        backend
+            .shell("emulator-5554", "unknown-command", &mut output)
+            .unwrap();
+        assert!(output.is_empty());
+
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let code = backend
+            .shell_status(
+                "emulator-5554",
+                "pm uninstall com.android.settings",
+                &mut stdout,
+                &mut stderr,
+            )
+            .unwrap();
+        assert_eq!(code, Some(1));
+        assert_eq!(stdout, b"Failure [DELETE_FAILED_INTERNAL_ERROR]\n");
+        assert!(matches!(
+            backend.shell("missing", "ls", &mut output),
+            Err(AdbError::DeviceNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_files() {
+        let mut backend = MockBackend::demo();
+        let entries = backend.list_dir("emulator-5554", "/sdcard").unwrap();
+        let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["DCIM", "Download"]);
+        assert!(entries[0].is_dir());
+
+        backend
+            .push("emulator-5554", &mut &b"hello"[..], "/sdcard/hello.txt")
+            .unwrap();
+        assert_eq!(
+            backend
+                .file_size("emulator-5554", "/sdcard/hello.txt")
+                .unwrap(),
+            5
+        );
+        let mut pulled = Vec::new();
+        backend
+            .pull("emulator-5554", "/sdcard/hello.txt", &mut pulled)
+            .unwrap();
+        assert_eq!(pulled, b"hello");
+    }
+
+    #[test]
+    fn test_track_devices() {
+        let backend = MockBackend::demo();
+        let cancel = CancelToken::default();
+        let mut worker = backend.for_worker(cancel.clone());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let tracker = thread::spawn(move || {
+            worker.track_devices(&mut |devices| {
+                let _ = sender.send(devices);
+            })
+        });
+
+        let timeout = Duration::from_secs(5);
+        assert_eq!(receiver.recv_timeout(timeout).unwrap()[0].state, "device");
+        backend.set_device_state("emulator-5554", "offline");
+        assert_eq!(receiver.recv_timeout(timeout).unwrap()[0].state, "offline");
+        backend.unplug("emulator-5554");
+        assert!(receiver.recv_timeout(timeout).unwrap().is_empty());
+
+        cancel.cancel();
+        assert!(matches!(tracker.join().unwrap(), Err(AdbError::Cancelled)));
+    }
+
+    #[test]
+    fn test_workers_share_state() {
+        let backend = MockBackend::demo();
+        let cancel = CancelToken::default();
+        let mut worker = backend.for_worker(cancel.clone());
+        worker.connect("192.168.1.20:5555").unwrap();
+        assert_eq!(backend.calls(), ["connect 192.168.1.20:5555"]);
+
+        cancel.cancel();
+        assert!(matches!(worker.devices(), Err(AdbError::Cancelled)));
+    }
+}
@@ -0,0 +1,104 @@
+//! Parser for `pm list packages` output
+//!
+//! The full `-f` listing gives names and APK paths; the user (`-3`) and disabled
+//! (`-d`) listings are only used to flag each entry.
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// An installed package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageEntry {
+    pub name: String,
+    /// Path of the base APK, when listed with `-f`
+    pub apk_path: Option<String>,
+    /// Installed as part of the system image rather than by the user
+    pub system: bool,
+    pub enabled: bool,
+}
+
+/// Parse `pm list packages` output into `(name, apk path)` pairs
+///
+/// Handles both `package:com.foo` and the `-f` form `package:/data/app/.../base.apk=com.foo`.
+/// The APK path may itself contain `=`, so the name is taken after the last one.
+pub fn parse_package_list(output: &str) -> Vec<(String, Option<String>)> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("package:"))
+        .filter(|rest| !rest.is_empty())
+        .map(|rest| match rest.rsplit_once('=') {
+            Some((path, name)) if path.starts_with('/') => {
+                (name.to_string(), Some(path.to_string()))
+            }
+            _ => (rest.to_string(), None),
+        })
+        .collect()
+}
+
+/// Combine the full `-f` listing with the user (`-3`) and disabled (`-d`) listings
+pub fn build_package_entries(all: &str, user: &str, disabled: &str) -> Vec<PackageEntry> {
+    let names = |output: &str| -> HashSet<String> {
+        parse_package_list(output)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    };
+    let user = names(user);
+    let disabled = names(disabled);
+
+    let mut entries: Vec<PackageEntry> = parse_package_list(all)
+        .into_iter()
+        .map(|(name, apk_path)| PackageEntry {
+            system: !user.contains(&name),
+            enabled: !disabled.contains(&name),
+            apk_path,
+            name,
+        })
+        .collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: &str = "package:/data/app/~~a1==/com.example.app-b2==/base.apk=com.example.app\n\
+                       package:/system/priv-app/Settings/Settings.apk=com.android.settings\n\
+                       package:/system/app/Calendar/Calendar.apk=com.android.calendar\n";
+
+    #[test]
+    fn test_parse_package_list() {
+        let packages = parse_package_list(ALL);
+        assert_eq!(packages.len(), 3);
+        assert_eq!(packages[0].0, "com.example.app");
+        assert_eq!(
+            packages[0].1.as_deref(),
+            Some("/data/app/~~a1==/com.example.app-b2==/base.apk")
+        );
+
+        let plain = parse_package_list("package:com.foo\nCommand executed successfully\n");
+        assert_eq!(plain, vec![("com.foo".to_string(), None)]);
+    }
+
+    #[test]
+    fn test_build_package_entries() {
+        let entries = build_package_entries(
+            ALL,
+            "package:com.example.app\n",
+            "package:com.android.calendar\n",
+        );
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "com.android.calendar",
+                "com.android.settings",
+                "com.example.app"
+            ]
+        );
+        assert!(entries[0].system && !entries[0].enabled);
+        assert!(entries[1].system && entries[1].enabled);
+        assert!(!entries[2].system && entries[2].enabled);
+    }
+}
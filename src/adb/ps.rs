@@ -0,0 +1,178 @@
+//! Parsers for the process list
+//!
+//! `ps` gives the process table; CPU usage is derived by the caller from the
+//! per-process ticks in `/proc/<pid>/stat` between two samples.
+
+use super::stats::{sections, CpuTimes};
+use std::collections::HashMap;
+
+/// Shell command printing everything a [`ProcessSample`] is built from
+pub const SAMPLE_COMMAND: &str = "ps -A -o PID,PPID,USER,RSS,VSZ,S,NAME; echo --droidtui--; \
+     head -n 1 /proc/stat; echo --droidtui--; \
+     cat /proc/[0-9]*/stat 2>/dev/null";
+
+/// A running process
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub ppid: u32,
+    pub user: String,
+    pub rss_kb: u64,
+    pub vsz_kb: u64,
+    /// Scheduler state, e.g. `S` (sleeping) or `R` (running)
+    pub state: String,
+    pub name: String,
+    /// Percent of total CPU time since the previous refresh
+    pub cpu: Option<f32>,
+}
+
+impl ProcessEntry {
+    /// Package an app process belongs to; `com.foo:remote` runs in `com.foo`
+    pub fn package(&self) -> Option<&str> {
+        // App processes run as `u0_a123`-style users
+        let app_user = self
+            .user
+            .split_once('_')
+            .is_some_and(|(user, app)| user.starts_with('u') && app.starts_with('a'));
+        let package = self.name.split(':').next()?;
+        (app_user && package.contains('.')).then_some(package)
+    }
+}
+
+/// Parse `ps -A -o PID,PPID,USER,RSS,VSZ,S,NAME`
+pub fn parse_ps(output: &str) -> Vec<ProcessEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            Some(ProcessEntry {
+                pid: fields[0].parse().ok()?,
+                ppid: fields[1].parse().ok()?,
+                user: fields[2].to_string(),
+                rss_kb: fields[3].parse().ok()?,
+                vsz_kb: fields[4].parse().ok()?,
+                state: fields[5].to_string(),
+                name: fields[6..].join(" "),
+                cpu: None,
+            })
+        })
+        .collect()
+}
+
+/// User plus system time per pid from concatenated `/proc/<pid>/stat` files
+pub fn parse_stat_ticks(output: &str) -> HashMap<u32, u64> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let pid = line.split_once(' ')?.0.parse().ok()?;
+            // The command name is in parentheses and may contain spaces
+            let fields: Vec<&str> = line[line.rfind(')')? + 1..].split_whitespace().collect();
+            let utime: u64 = fields.get(11)?.parse().ok()?;
+            let stime: u64 = fields.get(12)?.parse().ok()?;
+            Some((pid, utime + stime))
+        })
+        .collect()
+}
+
+/// One refresh of the process list
+#[derive(Debug, Clone)]
+pub struct ProcessSample {
+    pub processes: Vec<ProcessEntry>,
+    pub cpu: Option<CpuTimes>,
+    pub ticks: HashMap<u32, u64>,
+}
+
+impl ProcessSample {
+    /// Parse the output of [`SAMPLE_COMMAND`]
+    pub fn parse(output: &str) -> Self {
+        let sections = sections(output);
+        let section = |index: usize| sections.get(index).map(String::as_str).unwrap_or("");
+        Self {
+            processes: parse_ps(section(0)),
+            cpu: section(1).lines().next().and_then(CpuTimes::parse),
+            ticks: parse_stat_ticks(section(2)),
+        }
+    }
+
+    /// Plain text table by memory use, for callers without the process view
+    pub fn table(&self) -> String {
+        let mut processes: Vec<&ProcessEntry> = self.processes.iter().collect();
+        processes.sort_by(|a, b| b.rss_kb.cmp(&a.rss_kb).then(a.pid.cmp(&b.pid)));
+
+        let mut output = format!(
+            "{:>6} {:>6} {:<12} {:>9} S NAME\n",
+            "PID", "PPID", "USER", "RSS"
+        );
+        for process in processes {
+            output.push_str(&format!(
+                "{:>6} {:>6} {:<12} {:>8}K {} {}\n",
+                process.pid,
+                process.ppid,
+                process.user,
+                process.rss_kb,
+                process.state,
+                process.name
+            ));
+        }
+        output
+    }
+}
+
+/// Signals the process view can send
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Term,
+    Kill,
+    Stop,
+    Cont,
+}
+
+impl Signal {
+    /// Name as accepted by `kill -<name>`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Signal::Term => "TERM",
+            Signal::Kill => "KILL",
+            Signal::Stop => "STOP",
+            Signal::Cont => "CONT",
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const PS: &str = "  PID  PPID USER            RSS    VSZ S NAME
+    1     0 root           9876  10968 S init
+  658     1 system        41233 123456 S surfaceflinger
+ 2467  1100 u0_a145       98112 654321 S com.google.android.gms.persistent
+ 2590  1100 u0_a120      187404 765432 R com.google.android.apps.nexuslauncher
+ 3001  1100 u0_a145       12000 500000 S com.google.android.gms:remote service
+";
+
+    pub(crate) const STAT: &str = "1 (init) S 0 1 0 0 -1 4194560 100 0 0 0 10 20 0 0 20 0 1 0 5 0 0
+658 (surfaceflinger) S 1 658 0 0 -1 4194560 100 0 0 0 100 50 0 0 20 0 1 0 5 0 0
+2590 (nexus launcher) R 1100 0 0 0 -1 4194560 100 0 0 0 300 100 0 0 20 0 1 0 5 0 0
+";
+
+    #[test]
+    fn test_parse_ps() {
+        let processes = parse_ps(PS);
+        assert_eq!(processes.len(), 5);
+        assert_eq!(processes[1].name, "surfaceflinger");
+        assert_eq!(processes[3].rss_kb, 187_404);
+        assert_eq!(processes[3].state, "R");
+        assert_eq!(processes[4].name, "com.google.android.gms:remote service");
+    }
+
+    #[test]
+    fn test_parse_stat_ticks() {
+        let ticks = parse_stat_ticks(STAT);
+        assert_eq!(ticks.get(&1), Some(&30));
+        assert_eq!(ticks.get(&2590), Some(&400));
+    }
+}
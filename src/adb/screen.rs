@@ -0,0 +1,77 @@
+//! Screen size, streaming and recording settings
+
+use std::time::Duration;
+
+/// Longest recording `screenrecord` accepts before Android 14
+pub const MAX_DURATION: Duration = Duration::from_secs(180);
+
+/// How to record
+#[derive(Debug, Clone)]
+pub struct RecordOptions {
+    /// Video size and bitrate
+    pub config: StreamConfig,
+    /// Stop after this long, at most [`MAX_DURATION`]
+    pub max_duration: Duration,
+    /// Show the stream in a window while recording
+    pub show_window: bool,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        Self {
+            config: StreamConfig::default(),
+            max_duration: MAX_DURATION,
+            show_window: false,
+        }
+    }
+}
+
+/// Configuration for screen streaming
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    /// Video width
+    pub width: usize,
+    /// Video height
+    pub height: usize,
+    /// Video bitrate
+    pub bitrate: String,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self {
+            width: 1080,
+            height: 1920,
+            bitrate: "8M".to_string(),
+        }
+    }
+}
+
+/// Screen size from `wm size` output, preferring an override over the physical size
+pub(crate) fn parse_screen_size(output: &str) -> Option<(usize, usize)> {
+    let size = |prefix: &str| {
+        output.lines().find_map(|line| {
+            let (w, h) = line.strip_prefix(prefix)?.trim().split_once('x')?;
+            Some((w.trim().parse().ok()?, h.trim().parse().ok()?))
+        })
+    };
+    size("Override size:").or_else(|| size("Physical size:"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_screen_size() {
+        assert_eq!(
+            parse_screen_size("Physical size: 1080x2400\n"),
+            Some((1080, 2400))
+        );
+        assert_eq!(
+            parse_screen_size("Physical size: 1440x3120\nOverride size: 1080x2340\n"),
+            Some((1080, 2340))
+        );
+        assert_eq!(parse_screen_size("wm: not found"), None);
+    }
+}
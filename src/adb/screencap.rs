@@ -0,0 +1,101 @@
+//! Screenshots saved to the host
+//!
+//! Captures are taken with `screencap -p` and written as timestamped PNGs into a
+//! local directory.
+
+use chrono::{DateTime, Local};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding where screenshots are saved
+pub const SCREENSHOT_DIR_ENV: &str = "DROIDTUI_SCREENSHOT_DIR";
+
+/// PNG file signature
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Directory screenshots are saved to when nothing else is configured
+///
+/// `$DROIDTUI_SCREENSHOT_DIR`, then `~/Pictures/droidtui`, then `./screenshots`.
+pub fn default_screenshot_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(SCREENSHOT_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    match std::env::var_os("HOME").filter(|home| !home.is_empty()) {
+        Some(home) => Path::new(&home).join("Pictures").join("droidtui"),
+        None => PathBuf::from("screenshots"),
+    }
+}
+
+/// File name for a screenshot taken at `time`, e.g. `screenshot-20240131-142502.png`
+pub fn screenshot_file_name(time: DateTime<Local>) -> String {
+    format!("screenshot-{}.png", time.format("%Y%m%d-%H%M%S"))
+}
+
+/// Strip anything before the PNG signature
+///
+/// Some devices print warnings on the same stream before the image data.
+pub fn extract_png(data: &[u8]) -> Option<&[u8]> {
+    data.windows(PNG_SIGNATURE.len())
+        .position(|window| window == PNG_SIGNATURE)
+        .map(|start| &data[start..])
+}
+
+/// Write PNG bytes to a new timestamped file in `dir`, creating it if needed
+pub fn save_screenshot(dir: &Path, png: &[u8]) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = unused_path(dir, &screenshot_file_name(Local::now()));
+    fs::write(&path, png)?;
+    Ok(path)
+}
+
+/// `dir/name`, with a numeric suffix if that file already exists
+///
+/// Several captures within the same second would otherwise overwrite each other.
+pub fn unused_path(dir: &Path, name: &str) -> PathBuf {
+    let path = Path::new(name);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut path = dir.join(name);
+    let mut suffix = 1;
+    while path.exists() {
+        path = dir.join(format!("{}-{}{}", stem, suffix, extension));
+        suffix += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_screenshot_file_name() {
+        let time = Local.with_ymd_and_hms(2024, 1, 31, 14, 25, 2).unwrap();
+        assert_eq!(screenshot_file_name(time), "screenshot-20240131-142502.png");
+    }
+
+    #[test]
+    fn test_extract_png() {
+        let mut data = b"WARNING: linker noise\n".to_vec();
+        data.extend_from_slice(PNG_SIGNATURE);
+        data.extend_from_slice(b"IHDR");
+        assert_eq!(&extract_png(&data).unwrap()[..8], PNG_SIGNATURE);
+        assert!(extract_png(b"not an image").is_none());
+    }
+
+    #[test]
+    fn test_save_screenshot_unique_names() {
+        let dir = std::env::temp_dir().join(format!("droidtui-shots-{}", std::process::id()));
+        let first = save_screenshot(&dir, PNG_SIGNATURE).unwrap();
+        let second = save_screenshot(&dir, PNG_SIGNATURE).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(fs::read(&second).unwrap(), PNG_SIGNATURE);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,155 @@
+//! Shell protocol v2 client
+//!
+//! The `shell,v2:` service frames output as packets: a one byte id, a little endian
+//! length and the payload. stdout and stderr arrive as separate packets and the
+//! command's exit status comes last, which the legacy `shell:` service can't report.
+
+use super::host::{cancellable, connect, send_request, CancelToken};
+use super::{AdbError, AdbResult};
+use serde::Serialize;
+use std::io::{self, Read, Write};
+use std::net::SocketAddr;
+
+/// Packet ids of the shell protocol
+const ID_STDOUT: u8 = 1;
+const ID_STDERR: u8 = 2;
+const ID_EXIT: u8 = 3;
+
+/// stdout, stderr and exit status of a shell command
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ShellOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the device only speaks the legacy shell protocol
+    pub exit_code: Option<u8>,
+}
+
+impl ShellOutput {
+    /// Exited with status 0, or with an unknown status
+    pub fn success(&self) -> bool {
+        self.exit_code.is_none_or(|code| code == 0)
+    }
+
+    /// stdout followed by stderr
+    pub fn text(&self) -> String {
+        let mut text = self.stdout.clone();
+        if !text.is_empty() && !text.ends_with('\n') && !self.stderr.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&self.stderr);
+        text
+    }
+
+    /// stdout and stderr, or [`AdbError::ExitStatus`] when the command failed
+    pub fn into_result(self) -> AdbResult<String> {
+        match self.exit_code {
+            Some(code) if code != 0 => Err(AdbError::ExitStatus {
+                code,
+                output: self.text(),
+            }),
+            _ => Ok(self.text()),
+        }
+    }
+}
+
+/// Run `command` on `serial`, copying stdout and stderr apart and returning the exit status
+pub(crate) fn run(
+    server: SocketAddr,
+    serial: &str,
+    command: &str,
+    cancel: &CancelToken,
+    stdout: &mut (impl Write + ?Sized),
+    stderr: &mut (impl Write + ?Sized),
+) -> io::Result<Option<u8>> {
+    cancellable(cancel, || {
+        let mut stream = connect(server, cancel)?;
+        send_request(&mut stream, &format!("host:transport:{}", serial))?;
+        send_request(&mut stream, &format!("shell,v2,raw:{}", command))?;
+        read_packets(&mut stream, stdout, stderr)
+    })
+}
+
+/// Demultiplex packets until the exit status, or `None` if the stream ends first
+fn read_packets(
+    stream: &mut impl Read,
+    stdout: &mut (impl Write + ?Sized),
+    stderr: &mut (impl Write + ?Sized),
+) -> io::Result<Option<u8>> {
+    loop {
+        let mut header = [0u8; 5];
+        match stream.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let len = u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as u64;
+        let mut payload = stream.by_ref().take(len);
+        let copied = match header[0] {
+            ID_STDOUT => io::copy(&mut payload, stdout)?,
+            ID_STDERR => io::copy(&mut payload, stderr)?,
+            ID_EXIT => {
+                let mut code = Vec::new();
+                payload.read_to_end(&mut code)?;
+                return Ok(code.first().copied());
+            }
+            // Window size changes and stdin acknowledgements carry nothing for us
+            _ => io::copy(&mut payload, &mut io::sink())?,
+        };
+        if copied < len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(id: u8, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![id];
+        packet.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        packet.extend_from_slice(data);
+        packet
+    }
+
+    #[test]
+    fn test_read_packets() {
+        let stream = [
+            packet(ID_STDOUT, b"Success\n"),
+            packet(ID_STDERR, b"warning\n"),
+            packet(5, b"ignored"),
+            packet(ID_STDOUT, b"done\n"),
+            packet(ID_EXIT, &[1]),
+        ]
+        .concat();
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let code = read_packets(&mut stream.as_slice(), &mut stdout, &mut stderr).unwrap();
+        assert_eq!(code, Some(1));
+        assert_eq!(stdout, b"Success\ndone\n");
+        assert_eq!(stderr, b"warning\n");
+
+        // Closed before the exit packet
+        let stream = packet(ID_STDOUT, b"partial");
+        let code = read_packets(&mut stream.as_slice(), &mut Vec::new(), &mut Vec::new()).unwrap();
+        assert_eq!(code, None);
+
+        let truncated = &packet(ID_STDOUT, b"partial")[..8];
+        assert!(read_packets(&mut &truncated[..], &mut Vec::new(), &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_shell_output() {
+        let output = ShellOutput {
+            stdout: "Failure".to_string(),
+            stderr: "Exception occurred\n".to_string(),
+            exit_code: Some(1),
+        };
+        assert!(!output.success());
+        assert_eq!(output.text(), "Failure\nException occurred\n");
+        assert_eq!(
+            output.into_result().unwrap_err().to_string(),
+            "Exited with status 1: Failure\nException occurred"
+        );
+        assert!(ShellOutput::default().success());
+    }
+}
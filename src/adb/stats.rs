@@ -0,0 +1,294 @@
+//! Device statistics sampled for the live dashboard
+//!
+//! A single shell round trip prints `/proc/loadavg`, `/proc/stat`, `/proc/meminfo`,
+//! `/proc/net/dev` and `dumpsys battery`; sources that can't be read are skipped.
+
+use super::BatteryStatus;
+use std::time::Instant;
+
+/// Line printed between the sources of a sample
+pub(crate) const SEPARATOR: &str = "--droidtui--";
+
+/// Shell command printing every source a sample is built from
+pub const SAMPLE_COMMAND: &str = "cat /proc/loadavg; echo --droidtui--; \
+     head -n 1 /proc/stat; echo --droidtui--; \
+     cat /proc/meminfo; echo --droidtui--; \
+     cat /proc/net/dev; echo --droidtui--; \
+     dumpsys battery";
+
+/// Split the output of commands joined with `echo --droidtui--`
+pub(crate) fn sections(output: &str) -> Vec<String> {
+    let mut sections = vec![String::new()];
+    for line in output.lines() {
+        if line.trim() == SEPARATOR {
+            sections.push(String::new());
+        } else if let Some(section) = sections.last_mut() {
+            section.push_str(line);
+            section.push('\n');
+        }
+    }
+    sections
+}
+
+/// Aggregate CPU time from the first line of `/proc/stat`, in clock ticks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuTimes {
+    pub busy: u64,
+    pub total: u64,
+}
+
+impl CpuTimes {
+    /// Parse `cpu  user nice system idle iowait irq softirq steal …`
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        if fields.next()? != "cpu" {
+            return None;
+        }
+        // Guest time is already counted in user time
+        let times: Vec<u64> = fields.take(8).filter_map(|f| f.parse().ok()).collect();
+        if times.len() < 4 {
+            return None;
+        }
+        let idle = times[3] + times.get(4).copied().unwrap_or(0);
+        let total = times.iter().sum();
+        Some(Self {
+            busy: total - idle,
+            total,
+        })
+    }
+
+    /// CPU usage in percent between `earlier` and this sample
+    pub fn percent_since(&self, earlier: &CpuTimes) -> u64 {
+        let total = self.total.saturating_sub(earlier.total);
+        if total == 0 {
+            return 0;
+        }
+        (self.busy.saturating_sub(earlier.busy) * 100 / total).min(100)
+    }
+}
+
+/// RAM figures from `/proc/meminfo`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemorySample {
+    pub total_kb: u64,
+    pub available_kb: u64,
+}
+
+impl MemorySample {
+    pub fn parse(text: &str) -> Option<Self> {
+        let value = |key: &str| {
+            text.lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+                .and_then(|v| v.split_whitespace().next()?.parse::<u64>().ok())
+        };
+        let total_kb = value("MemTotal")?;
+        // MemAvailable is missing before Linux 3.14
+        let available_kb = value("MemAvailable").or_else(|| {
+            Some(value("MemFree")? + value("Cached").unwrap_or(0) + value("Buffers").unwrap_or(0))
+        })?;
+        Some(Self {
+            total_kb,
+            available_kb: available_kb.min(total_kb),
+        })
+    }
+
+    pub fn used_kb(&self) -> u64 {
+        self.total_kb - self.available_kb
+    }
+
+    pub fn used_percent(&self) -> u64 {
+        if self.total_kb == 0 {
+            return 0;
+        }
+        self.used_kb() * 100 / self.total_kb
+    }
+}
+
+/// Byte counters of all interfaces but loopback, from `/proc/net/dev`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+impl NetCounters {
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut counters = None;
+        for (name, stats) in text.lines().filter_map(|line| line.split_once(':')) {
+            if name.trim() == "lo" {
+                continue;
+            }
+            let fields: Vec<u64> = stats
+                .split_whitespace()
+                .filter_map(|f| f.parse().ok())
+                .collect();
+            if fields.len() < 9 {
+                continue;
+            }
+            let total = counters.get_or_insert(NetCounters {
+                rx_bytes: 0,
+                tx_bytes: 0,
+            });
+            total.rx_bytes += fields[0];
+            total.tx_bytes += fields[8];
+        }
+        counters
+    }
+}
+
+/// One poll of the device; sources that couldn't be read are `None`
+#[derive(Debug, Clone)]
+pub struct DeviceSample {
+    pub taken: Instant,
+    pub load: Option<[f32; 3]>,
+    pub cpu: Option<CpuTimes>,
+    pub memory: Option<MemorySample>,
+    pub network: Option<NetCounters>,
+    pub battery: Option<BatteryStatus>,
+}
+
+impl DeviceSample {
+    /// Parse the output of [`SAMPLE_COMMAND`]
+    pub fn parse(output: &str, taken: Instant) -> Self {
+        let sections = sections(output);
+        let section = |index: usize| sections.get(index).map(String::as_str).unwrap_or("");
+
+        let load: Vec<f32> = section(0)
+            .split_whitespace()
+            .take(3)
+            .filter_map(|f| f.parse().ok())
+            .collect();
+
+        Self {
+            taken,
+            load: <[f32; 3]>::try_from(load).ok(),
+            cpu: section(1).lines().next().and_then(CpuTimes::parse),
+            memory: MemorySample::parse(section(2)),
+            network: NetCounters::parse(section(3)),
+            battery: BatteryStatus::parse(section(4)).ok(),
+        }
+    }
+
+    /// Plain text overview for callers without the dashboard
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some([one, five, fifteen]) = self.load {
+            lines.push(format!(
+                "Load average: {:.2} {:.2} {:.2}",
+                one, five, fifteen
+            ));
+        }
+        if let Some(memory) = &self.memory {
+            lines.push(format!(
+                "Memory: {} / {} used ({}%)",
+                format_size(memory.used_kb() * 1024),
+                format_size(memory.total_kb * 1024),
+                memory.used_percent()
+            ));
+        }
+        if let Some(network) = &self.network {
+            lines.push(format!(
+                "Network: {} received, {} sent",
+                format_size(network.rx_bytes),
+                format_size(network.tx_bytes)
+            ));
+        }
+        if let Some(battery) = &self.battery {
+            let mut line = format!(
+                "Battery: {}% ({}",
+                battery.percent(),
+                battery.status.label()
+            );
+            if let Some(temperature) = battery.temperature_c {
+                line.push_str(&format!(", {:.1} °C", temperature));
+            }
+            line.push(')');
+            lines.push(line);
+        }
+        if lines.is_empty() {
+            return "No device statistics available".to_string();
+        }
+        lines.join("\n")
+    }
+}
+
+/// Format a byte count for display
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Output of [`SAMPLE_COMMAND`] on an emulator, shared with the dashboard tests
+    pub(crate) const OUTPUT: &str = "0.52 0.58 0.59 2/1402 12345
+--droidtui--
+cpu  2255 34 2290 22625563 6290 127 456 0 0 0
+--droidtui--
+MemTotal:        3844252 kB
+MemFree:          211532 kB
+MemAvailable:    1795716 kB
+--droidtui--
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:    4000      40    0    0    0     0          0         0     4000      40    0    0    0     0       0          0
+ wlan0: 1000000    900    0    0    0     0          0         0   200000     500    0    0    0     0       0          0
+--droidtui--
+Current Battery Service state:
+  level: 85
+  scale: 100
+  temperature: 285
+";
+
+    #[test]
+    fn test_parse_sample() {
+        let sample = DeviceSample::parse(OUTPUT, Instant::now());
+        assert_eq!(sample.load, Some([0.52, 0.58, 0.59]));
+        assert_eq!(
+            sample.cpu,
+            Some(CpuTimes {
+                busy: 2255 + 34 + 2290 + 127 + 456,
+                total: 2255 + 34 + 2290 + 22625563 + 6290 + 127 + 456,
+            })
+        );
+        assert_eq!(sample.memory.unwrap().used_kb(), 3844252 - 1795716);
+        assert_eq!(
+            sample.network,
+            Some(NetCounters {
+                rx_bytes: 1_000_000,
+                tx_bytes: 200_000,
+            })
+        );
+        assert_eq!(sample.battery.as_ref().unwrap().percent(), 85);
+        assert!(sample.summary().contains("Battery: 85%"));
+    }
+
+    #[test]
+    fn test_parse_unreadable_sources() {
+        let sample = DeviceSample::parse(
+            "cat: /proc/loadavg: Permission denied\n--droidtui--\n",
+            Instant::now(),
+        );
+        assert!(sample.load.is_none() && sample.cpu.is_none() && sample.battery.is_none());
+        assert_eq!(sample.summary(), "No device statistics available");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+}
@@ -0,0 +1,281 @@
+//! Minimal ADB sync protocol client
+//!
+//! Talks to the ADB server directly for listings, stat, push and pull, so they work
+//! with any server address, IPv6 included.
+
+use super::host::{cancellable, connect, send_request, CancelToken};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File type bits of a POSIX mode
+const S_IFMT: u32 = 0o170000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFLNK: u32 = 0o120000;
+
+/// Largest `DATA` payload adbd accepts
+const SYNC_DATA_MAX: usize = 64 * 1024;
+
+/// A directory entry on the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteEntry {
+    pub name: String,
+    pub mode: u32,
+    pub size: u64,
+    /// Modification time in seconds since the Unix epoch
+    pub mtime: u32,
+}
+
+impl RemoteEntry {
+    pub fn is_dir(&self) -> bool {
+        self.mode & S_IFMT == S_IFDIR
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.mode & S_IFMT == S_IFLNK
+    }
+}
+
+fn read_u32(stream: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    stream.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+/// Open a sync session with `serial` through the ADB server at `server`
+fn open_sync(server: SocketAddr, serial: &str, cancel: &CancelToken) -> io::Result<TcpStream> {
+    let mut stream = connect(server, cancel)?;
+    send_request(&mut stream, &format!("host:transport:{}", serial))?;
+    send_request(&mut stream, "sync:")?;
+    Ok(stream)
+}
+
+/// Send a sync request: 4 byte id followed by a little endian length and payload
+fn send_sync(stream: &mut TcpStream, id: &[u8; 4], payload: &[u8]) -> io::Result<()> {
+    let mut request = Vec::with_capacity(8 + payload.len());
+    request.extend_from_slice(id);
+    request.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    request.extend_from_slice(payload);
+    stream.write_all(&request)
+}
+
+/// Read the message following a `FAIL` id
+fn read_fail(stream: &mut impl Read) -> io::Error {
+    let message = read_u32(stream).and_then(|len| {
+        let mut message = vec![0u8; len as usize];
+        stream.read_exact(&mut message)?;
+        Ok(String::from_utf8_lossy(&message).to_string())
+    });
+    match message {
+        Ok(message) => io::Error::other(message),
+        Err(e) => e,
+    }
+}
+
+fn unexpected(id: &[u8; 4]) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Unexpected sync response {:?}", String::from_utf8_lossy(id)),
+    )
+}
+
+/// Size of a file on the device
+///
+/// The v1 `STAT` reply only carries 32 bits of the size.
+pub fn file_size(
+    server: SocketAddr,
+    serial: &str,
+    path: &str,
+    cancel: &CancelToken,
+) -> io::Result<u64> {
+    cancellable(cancel, || {
+        let mut stream = open_sync(server, serial, cancel)?;
+        send_sync(&mut stream, b"STAT", path.as_bytes())?;
+        let mut id = [0u8; 4];
+        stream.read_exact(&mut id)?;
+        if &id != b"STAT" {
+            return Err(unexpected(&id));
+        }
+        let mode = read_u32(&mut stream)?;
+        let size = read_u32(&mut stream)?;
+        let _mtime = read_u32(&mut stream)?;
+        let _ = send_sync(&mut stream, b"QUIT", &[]);
+        // adbd answers a missing path with an all-zero reply
+        if mode == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{}: No such file or directory", path),
+            ));
+        }
+        Ok(u64::from(size))
+    })
+}
+
+/// Copy `path` from the device to `out`
+pub fn pull(
+    server: SocketAddr,
+    serial: &str,
+    path: &str,
+    cancel: &CancelToken,
+    out: &mut (impl Write + ?Sized),
+) -> io::Result<()> {
+    cancellable(cancel, || {
+        let mut stream = open_sync(server, serial, cancel)?;
+        send_sync(&mut stream, b"RECV", path.as_bytes())?;
+        let mut chunk = vec![0u8; SYNC_DATA_MAX];
+        loop {
+            let mut id = [0u8; 4];
+            stream.read_exact(&mut id)?;
+            match &id {
+                b"DATA" => {
+                    let len = read_u32(&mut stream)? as usize;
+                    if len > SYNC_DATA_MAX {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Sync chunk of {} bytes is too large", len),
+                        ));
+                    }
+                    stream.read_exact(&mut chunk[..len])?;
+                    out.write_all(&chunk[..len])?;
+                }
+                b"DONE" => {
+                    read_u32(&mut stream)?;
+                    break;
+                }
+                b"FAIL" => return Err(read_fail(&mut stream)),
+                other => return Err(unexpected(other)),
+            }
+        }
+        let _ = send_sync(&mut stream, b"QUIT", &[]);
+        Ok(())
+    })
+}
+
+/// Copy `input` to `path` on the device as a 0644 file
+pub fn push(
+    server: SocketAddr,
+    serial: &str,
+    input: &mut (impl Read + ?Sized),
+    path: &str,
+    cancel: &CancelToken,
+) -> io::Result<()> {
+    cancellable(cancel, || {
+        let mut stream = open_sync(server, serial, cancel)?;
+        send_sync(
+            &mut stream,
+            b"SEND",
+            format!("{},{}", path, 0o100644).as_bytes(),
+        )?;
+        let mut chunk = vec![0u8; SYNC_DATA_MAX];
+        loop {
+            let len = input.read(&mut chunk)?;
+            if len == 0 {
+                break;
+            }
+            send_sync(&mut stream, b"DATA", &chunk[..len])?;
+        }
+        let mtime = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as u32);
+        stream.write_all(b"DONE")?;
+        stream.write_all(&mtime.to_le_bytes())?;
+
+        let mut id = [0u8; 4];
+        stream.read_exact(&mut id)?;
+        match &id {
+            b"OKAY" => {
+                read_u32(&mut stream)?;
+                let _ = send_sync(&mut stream, b"QUIT", &[]);
+                Ok(())
+            }
+            b"FAIL" => Err(read_fail(&mut stream)),
+            other => Err(unexpected(other)),
+        }
+    })
+}
+
+/// List a directory on the device, excluding `.` and `..`
+pub fn list_dir(
+    server: SocketAddr,
+    serial: &str,
+    path: &str,
+    cancel: &CancelToken,
+) -> io::Result<Vec<RemoteEntry>> {
+    cancellable(cancel, || {
+        let mut stream = open_sync(server, serial, cancel)?;
+        read_listing(&mut stream, path)
+    })
+}
+
+/// Send `LIST` and collect the `DENT` replies
+fn read_listing(stream: &mut TcpStream, path: &str) -> io::Result<Vec<RemoteEntry>> {
+    send_sync(stream, b"LIST", path.as_bytes())?;
+
+    let mut entries = Vec::new();
+    loop {
+        let mut id = [0u8; 4];
+        stream.read_exact(&mut id)?;
+        match &id {
+            b"DENT" => {
+                let mode = read_u32(stream)?;
+                // The v1 `DENT` reply only carries 32 bits of the size
+                let size = u64::from(read_u32(stream)?);
+                let mtime = read_u32(stream)?;
+                let name_len = read_u32(stream)? as usize;
+                let mut name = vec![0u8; name_len];
+                stream.read_exact(&mut name)?;
+                let name = String::from_utf8_lossy(&name).to_string();
+                if name != "." && name != ".." {
+                    entries.push(RemoteEntry {
+                        name,
+                        mode,
+                        size,
+                        mtime,
+                    });
+                }
+            }
+            b"DONE" => {
+                // DONE carries the same (zeroed) fields as DENT
+                let mut rest = [0u8; 16];
+                stream.read_exact(&mut rest)?;
+                break;
+            }
+            b"FAIL" => return Err(read_fail(stream)),
+            other => return Err(unexpected(other)),
+        }
+    }
+
+    let _ = send_sync(stream, b"QUIT", &[]);
+    entries.sort_by(|a, b| {
+        b.is_dir()
+            .cmp(&a.is_dir())
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_kind() {
+        let dir = RemoteEntry {
+            name: "DCIM".to_string(),
+            mode: 0o040771,
+            size: 4096,
+            mtime: 0,
+        };
+        assert!(dir.is_dir());
+        assert!(!dir.is_symlink());
+
+        let link = RemoteEntry {
+            name: "sdcard".to_string(),
+            mode: 0o120777,
+            size: 21,
+            mtime: 0,
+        };
+        assert!(link.is_symlink());
+        assert!(!link.is_dir());
+    }
+}
@@ -0,0 +1,166 @@
+//! Command deadlines and hung-device detection
+//!
+//! Every [`AdbCommand`] has a default timeout that the `[timeouts]` config table can
+//! override. A command running past its deadline is cancelled like a user cancel and
+//! reported as [`AdbError::Timeout`](super::AdbError::Timeout). The [`Watchdog`]
+//! counts timeouts in a row per device so the UI can tell a wedged device from a
+//! slow command.
+
+use super::{AdbCommand, CancelToken};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Timeouts in a row after which a device counts as unresponsive
+pub const UNRESPONSIVE_AFTER: u32 = 2;
+
+/// How often a deadline checks whether its parent was cancelled
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+/// Timeouts per command, by [`AdbCommand::name`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandTimeouts {
+    /// `None` runs the command without a deadline
+    overrides: HashMap<String, Option<Duration>>,
+}
+
+impl CommandTimeouts {
+    /// Override the timeout of the command called `name`
+    pub fn set(&mut self, name: &str, timeout: Option<Duration>) -> Result<(), String> {
+        if !AdbCommand::NAMES.contains(&name) {
+            return Err(format!("unknown command {}", name));
+        }
+        self.overrides.insert(name.to_string(), timeout);
+        Ok(())
+    }
+
+    /// Timeout for `command`, `None` when it may run forever
+    pub fn get(&self, command: &AdbCommand) -> Option<Duration> {
+        match self.overrides.get(command.name()) {
+            Some(timeout) => *timeout,
+            None => command.default_timeout(),
+        }
+    }
+}
+
+/// Cancels a token once a timeout passes, or as soon as its parent is cancelled
+pub(crate) struct Deadline {
+    done: mpsc::Sender<()>,
+    watcher: JoinHandle<bool>,
+}
+
+impl Deadline {
+    pub(crate) fn start(timeout: Duration, parent: CancelToken, token: CancelToken) -> Self {
+        let (done, finished) = mpsc::channel();
+        let expires = Instant::now() + timeout;
+        let watcher = thread::spawn(move || loop {
+            let now = Instant::now();
+            if now >= expires {
+                token.cancel();
+                return true;
+            }
+            if parent.is_cancelled() {
+                token.cancel();
+                return false;
+            }
+            match finished.recv_timeout((expires - now).min(CANCEL_POLL)) {
+                Err(RecvTimeoutError::Timeout) => {}
+                _ => return false,
+            }
+        });
+        Self { done, watcher }
+    }
+
+    /// Stop watching, returning whether the timeout passed first
+    pub(crate) fn finish(self) -> bool {
+        drop(self.done);
+        self.watcher.join().unwrap_or(false)
+    }
+}
+
+/// Consecutive timeouts per device
+#[derive(Debug, Clone, Default)]
+pub struct Watchdog {
+    timeouts: HashMap<String, u32>,
+}
+
+impl Watchdog {
+    /// Count a timeout on `serial`, returning whether it just became unresponsive
+    pub fn record_timeout(&mut self, serial: &str) -> bool {
+        let count = self.timeouts.entry(serial.to_string()).or_default();
+        *count += 1;
+        *count == UNRESPONSIVE_AFTER
+    }
+
+    /// Any answer, even an error, shows the device is alive
+    pub fn record_response(&mut self, serial: &str) {
+        self.timeouts.remove(serial);
+    }
+
+    /// Timeouts in a row on `serial`
+    pub fn timeouts(&self, serial: &str) -> u32 {
+        self.timeouts.get(serial).copied().unwrap_or(0)
+    }
+
+    pub fn is_unresponsive(&self, serial: &str) -> bool {
+        self.timeouts(serial) >= UNRESPONSIVE_AFTER
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_timeouts() {
+        let mut timeouts = CommandTimeouts::default();
+        let battery = AdbCommand::GetBatteryInfo;
+        assert_eq!(timeouts.get(&battery), battery.default_timeout());
+
+        timeouts
+            .set("get_battery_info", Some(Duration::from_secs(3)))
+            .unwrap();
+        assert_eq!(timeouts.get(&battery), Some(Duration::from_secs(3)));
+        timeouts.set("get_battery_info", None).unwrap();
+        assert_eq!(timeouts.get(&battery), None);
+        assert!(timeouts.set("battery", None).is_err());
+    }
+
+    #[test]
+    fn test_deadline() {
+        let (parent, token) = (CancelToken::default(), CancelToken::default());
+        let deadline = Deadline::start(Duration::from_millis(10), parent, token.clone());
+        thread::sleep(Duration::from_millis(30));
+        assert!(token.is_cancelled());
+        assert!(deadline.finish());
+
+        // Finishing first leaves the token alone
+        let (parent, token) = (CancelToken::default(), CancelToken::default());
+        let deadline = Deadline::start(Duration::from_secs(60), parent.clone(), token.clone());
+        assert!(!deadline.finish());
+        assert!(!token.is_cancelled());
+
+        // A cancelled parent is not a timeout
+        let token = CancelToken::default();
+        let deadline = Deadline::start(Duration::from_secs(60), parent.clone(), token.clone());
+        parent.cancel();
+        thread::sleep(Duration::from_millis(100));
+        assert!(token.is_cancelled());
+        assert!(!deadline.finish());
+    }
+
+    #[test]
+    fn test_watchdog() {
+        let mut watchdog = Watchdog::default();
+        assert!(!watchdog.record_timeout("emulator-5554"));
+        assert!(watchdog.record_timeout("emulator-5554"));
+        assert!(watchdog.is_unresponsive("emulator-5554"));
+        // Only reported once
+        assert!(!watchdog.record_timeout("emulator-5554"));
+        assert!(!watchdog.is_unresponsive("0123456789ABCDEF"));
+
+        watchdog.record_response("emulator-5554");
+        assert_eq!(watchdog.timeouts("emulator-5554"), 0);
+    }
+}
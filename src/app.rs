@@ -1,10 +1,26 @@
 use crate::{
+    adb::{AdbCommand, AdbManager},
+    dashboard::{Dashboard, DashboardEvent},
     event::{AppEvent, Event, EventHandler},
+    files::{FileInput, ListingEvent, TransferEvent},
+    hotplug::{spawn_tracker, DeviceListEvent},
+    logcat::{LogcatEvent, LogcatField},
+    menu::Menu,
     message::Message,
+    mirror::MirrorEvent,
     model::{AppState, Model},
+    preview::{self, GraphicsProtocol},
+    processes::ProcessEvent,
+    reboot::RebootEvent,
+    record::RecordingEvent,
+    stream::StreamEvent,
     update,
+    wireless::WirelessPrompt,
+    worker::{ActionEvent, CommandEvent},
 };
-use ratatui::{crossterm::event::KeyCode, DefaultTerminal};
+use ratatui::{crossterm::event::KeyCode, layout::Rect, DefaultTerminal};
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Main application following Elm architecture
 /// This is a thin wrapper that connects the event loop to the Model-Update-View cycle
@@ -14,17 +30,51 @@ pub struct App {
 
     /// Event handler
     pub events: EventHandler,
+
+    /// Image and area currently drawn with a terminal graphics protocol
+    preview_drawn: Option<(Drawn, Rect)>,
+}
+
+/// Image drawn with a terminal graphics protocol
+#[derive(Debug, Clone, PartialEq)]
+enum Drawn {
+    Screenshot(PathBuf),
+    /// Mirror frame, by the number of frames shown before it
+    Mirror(u64),
 }
 
 impl App {
     /// Create a new application
     pub fn new() -> Self {
+        Self::with_adb_manager(AdbManager::new())
+    }
+
+    /// Create an application using an already configured ADB manager
+    pub fn with_adb_manager(adb_manager: AdbManager) -> Self {
+        let events = EventHandler::new();
+        let mut model = Model::new();
+        model.device_tracker = Some(spawn_tracker(&adb_manager, events.sender()));
+        model.adb_manager = adb_manager;
+        model.event_sender = Some(events.sender());
         Self {
-            model: Model::new(),
-            events: EventHandler::new(),
+            model,
+            events,
+            preview_drawn: None,
         }
     }
 
+    /// Replace the built-in menu, e.g. with one customised by the config file
+    pub fn with_menu(mut self, menu: Menu) -> Self {
+        self.model.menu = menu;
+        self
+    }
+
+    /// Poll the live dashboard every `interval`
+    pub fn with_dashboard_interval(mut self, interval: Duration) -> Self {
+        self.model.dashboard = Dashboard::new(interval);
+        self
+    }
+
     /// Main application loop following Elm architecture:
     /// 1. Wait for events
     /// 2. Convert events to messages
@@ -36,6 +86,7 @@ impl App {
             terminal.draw(|frame| {
                 crate::view::render(&mut self.model, frame.area(), frame.buffer_mut())
             })?;
+            self.draw_preview(&mut terminal)?;
 
             // Event: Wait for next event
             let event = self.events.next().await?;
@@ -50,6 +101,73 @@ impl App {
         Ok(())
     }
 
+    /// Draw the screenshot preview or mirror frame with the terminal's graphics protocol
+    ///
+    /// Graphics protocols bypass the ratatui buffer, so the image is only written when
+    /// it or its area changes, and the screen is cleared when it goes away.
+    fn draw_preview(&mut self, terminal: &mut DefaultTerminal) -> color_eyre::Result<()> {
+        let protocol = self.model.graphics;
+        if protocol == GraphicsProtocol::HalfBlocks {
+            return Ok(());
+        }
+
+        let wanted = match self.model.state {
+            AppState::Screenshot => match (&self.model.screenshot, self.model.screenshot_area) {
+                (Some(screenshot), Some(area)) => {
+                    Some((Drawn::Screenshot(screenshot.path.clone()), area))
+                }
+                _ => None,
+            },
+            AppState::Mirror => match (&self.model.mirror, self.model.mirror_area) {
+                (Some(mirror), Some(area)) => Some((Drawn::Mirror(mirror.shown), area)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if wanted == self.preview_drawn {
+            return Ok(());
+        }
+
+        // The next mirror frame in the same place is drawn over the last one
+        let next_frame = matches!(
+            (&self.preview_drawn, &wanted),
+            (Some((Drawn::Mirror(_), old)), Some((Drawn::Mirror(_), new))) if old == new
+        );
+        if !next_frame && self.preview_drawn.take().is_some() {
+            if let Some(sequence) = preview::clear_sequence(protocol) {
+                preview::write_at(Rect::default(), sequence)?;
+            }
+            // Sixel and iTerm2 pixels stay until the cells are repainted
+            terminal.clear()?;
+            return Ok(());
+        }
+
+        let Some((drawn, area)) = wanted else {
+            return Ok(());
+        };
+        let cell = preview::cell_size();
+        let sequence = match (&drawn, &self.model.screenshot, &self.model.mirror) {
+            (Drawn::Screenshot(_), Some(screenshot), _) => {
+                preview::encode(protocol, &screenshot.image, &screenshot.png, area, cell)
+            }
+            (Drawn::Mirror(_), _, Some(mirror)) => mirror.frame.as_ref().and_then(|frame| {
+                let sequence = preview::encode_frame(protocol, frame, area, cell)?;
+                // Kitty stacks images, so the last frame goes first
+                Some(format!(
+                    "{}{}",
+                    preview::clear_sequence(protocol).unwrap_or_default(),
+                    sequence
+                ))
+            }),
+            _ => None,
+        };
+        if let Some(sequence) = sequence {
+            preview::write_at(area, &sequence)?;
+        }
+        self.preview_drawn = Some((drawn, area));
+        Ok(())
+    }
+
     /// Convert events to messages (Elm architecture pattern)
     fn event_to_message(&self, event: Event) -> color_eyre::Result<Option<Message>> {
         match event {
@@ -63,6 +181,122 @@ impl App {
                 }
             }
 
+            // Output of a stopped or replaced stream is dropped
+            Event::Logcat(event) => {
+                let current = self.model.logcat_handle.as_ref().map(|handle| handle.id);
+                Ok(match event {
+                    LogcatEvent::Lines { id, lines } if current == Some(id) => {
+                        Some(Message::LogcatLines(lines))
+                    }
+                    LogcatEvent::Ended { id, error } if current == Some(id) => {
+                        Some(Message::LogcatEnded(error))
+                    }
+                    _ => None,
+                })
+            }
+
+            Event::Transfer(TransferEvent::Progress { transferred, total }) => {
+                Ok(Some(Message::TransferProgress { transferred, total }))
+            }
+            Event::Transfer(TransferEvent::Finished(result)) => {
+                Ok(Some(Message::TransferFinished(result)))
+            }
+
+            Event::RemoteListing(ListingEvent { id, result }) => {
+                let current = self.model.files.listing.as_ref().map(|poll| poll.id);
+                Ok((current == Some(id)).then_some(Message::RemoteListed(result)))
+            }
+
+            Event::FileAction(ActionEvent { id, result }) => {
+                let current = self.model.files.running.as_ref().map(|job| job.0.id);
+                Ok((current == Some(id)).then_some(Message::FileActionDone(result)))
+            }
+
+            // Only the job still in flight reports; cancelled ones are ignored
+            Event::Command(event) => {
+                let current = self.model.command_job.as_ref().map(|job| job.id);
+                Ok(match event {
+                    CommandEvent::Started(id) if current == Some(id) => {
+                        Some(Message::CommandStarted)
+                    }
+                    CommandEvent::Finished { id, result } if current == Some(id) => {
+                        Some(Message::CommandCompleted(result))
+                    }
+                    _ => None,
+                })
+            }
+
+            // Samples from a poll the dashboard no longer waits for are dropped
+            Event::Dashboard(DashboardEvent { id, result }) => {
+                let current = self.model.dashboard.poll.as_ref().map(|poll| poll.id);
+                Ok((current == Some(id)).then_some(Message::DashboardSample(result)))
+            }
+
+            Event::Processes(ProcessEvent { id, result }) => {
+                let current = self.model.processes.poll.as_ref().map(|poll| poll.id);
+                Ok((current == Some(id)).then_some(Message::ProcessSample(result)))
+            }
+
+            // Actions report to the view that started them, even after it was left
+            Event::PackageAction(ActionEvent { id, result }) => {
+                let current = self.model.packages.running.as_ref().map(|job| job.0.id);
+                Ok((current == Some(id)).then_some(Message::PackageActionDone(result)))
+            }
+
+            Event::ProcessAction(ActionEvent { id, result }) => {
+                let current = self.model.processes.running.as_ref().map(|job| job.0.id);
+                Ok((current == Some(id)).then_some(Message::ProcessActionDone(result)))
+            }
+
+            Event::Reboot(RebootEvent { id, result }) => {
+                let current = self
+                    .model
+                    .reboot
+                    .as_ref()
+                    .and_then(|watch| watch.poll.as_ref())
+                    .map(|poll| poll.id);
+                Ok((current == Some(id)).then_some(Message::RebootProgress(result)))
+            }
+
+            Event::Mirror(MirrorEvent::Frame) => {
+                Ok(self.model.mirror.is_some().then_some(Message::MirrorFrame))
+            }
+            Event::Mirror(MirrorEvent::Ended { id, result }) => {
+                let current = self.model.mirror.as_ref().map(|mirror| mirror.poll.id);
+                Ok((current == Some(id)).then_some(Message::MirrorEnded(result)))
+            }
+
+            Event::Stream(StreamEvent::Started) => Ok(self
+                .model
+                .stream_state
+                .is_some()
+                .then_some(Message::StreamStarted)),
+            Event::Stream(StreamEvent::Ended { id, result }) => {
+                let current = self
+                    .model
+                    .stream_state
+                    .as_ref()
+                    .map(|stream| stream.poll.id);
+                Ok((current == Some(id)).then_some(Message::StreamEnded(result)))
+            }
+
+            Event::Devices(event) => Ok(Some(Message::DevicesChanged(event))),
+
+            Event::DeviceList(DeviceListEvent { id, result }) => {
+                let current = self.model.device_poll.as_ref().map(|poll| poll.id);
+                Ok((current == Some(id)).then_some(Message::DevicesListed(result)))
+            }
+
+            Event::Disconnect(ActionEvent { id, result }) => {
+                let current = self.model.disconnect_job.as_ref().map(|job| job.id);
+                Ok((current == Some(id)).then_some(Message::DisconnectDone(result)))
+            }
+
+            Event::Recording(RecordingEvent { id, result }) => {
+                let current = self.model.recording.as_ref().map(|rec| rec.poll.id);
+                Ok((current == Some(id)).then_some(Message::RecordingFinished(result)))
+            }
+
             Event::App(app_event) => Ok(Some(match app_event {
                 AppEvent::MenuUp => Message::MenuUp,
                 AppEvent::MenuDown => Message::MenuDown,
@@ -82,12 +316,24 @@ impl App {
         match self.model.state {
             AppState::Startup => Some(Message::SkipStartup),
 
+            AppState::Menu if self.model.command_prompt.is_some() => match key {
+                KeyCode::Enter => Some(Message::PromptSubmit),
+                KeyCode::Esc => Some(Message::PromptCancel),
+                KeyCode::Backspace => Some(Message::PromptBackspace),
+                KeyCode::Char(c) => Some(Message::PromptInput(c)),
+                _ => None,
+            },
+
             AppState::Menu => match key {
                 KeyCode::Esc | KeyCode::Char('q') => Some(Message::Quit),
                 KeyCode::Up | KeyCode::Char('k') => Some(Message::MenuUp),
                 KeyCode::Down | KeyCode::Char('j') => Some(Message::MenuDown),
+                KeyCode::Char('d') => Some(Message::OpenDevicePicker),
                 KeyCode::Enter => {
-                    if self.model.menu.is_in_child_mode() {
+                    // Items without children (e.g. custom commands) run directly
+                    if self.model.menu.is_in_child_mode()
+                        || self.model.menu.get_selected_item().children.is_empty()
+                    {
                         let command = self.model.get_selected_command();
                         Some(Message::ExecuteCommand(command))
                     } else {
@@ -111,11 +357,196 @@ impl App {
                 _ => None,
             },
 
-            AppState::Executing | AppState::Loading => match key {
+            AppState::DevicePicker if self.model.wireless.prompt.is_some() => match key {
+                KeyCode::Enter => Some(Message::WirelessSubmit),
+                KeyCode::Esc => Some(Message::WirelessCancel),
+                KeyCode::Backspace => Some(Message::WirelessBackspace),
+                KeyCode::Up => Some(Message::WirelessRecent(-1)),
+                KeyCode::Down => Some(Message::WirelessRecent(1)),
+                KeyCode::Char(c) => Some(Message::WirelessInput(c)),
+                _ => None,
+            },
+
+            AppState::DevicePicker => match key {
+                KeyCode::Up | KeyCode::Char('k') => Some(Message::DeviceUp),
+                KeyCode::Down | KeyCode::Char('j') => Some(Message::DeviceDown),
+                KeyCode::Enter => Some(Message::SelectDevice),
+                KeyCode::Char('r') => Some(Message::RefreshDevices),
+                KeyCode::Char('c') => Some(Message::OpenWirelessPrompt(WirelessPrompt::Connect)),
+                KeyCode::Char('p') => Some(Message::OpenWirelessPrompt(WirelessPrompt::Pair)),
+                KeyCode::Char('t') => Some(Message::OpenWirelessPrompt(WirelessPrompt::TcpIp)),
+                KeyCode::Char('x') => Some(Message::DisconnectDevice),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => {
+                    Some(Message::ReturnToMenu)
+                }
+                _ => None,
+            },
+
+            AppState::Logcat if self.model.logcat.input.is_some() => match key {
+                KeyCode::Enter => Some(Message::LogcatSubmitFilter),
+                KeyCode::Esc => Some(Message::LogcatCancelInput),
+                KeyCode::Backspace => Some(Message::LogcatBackspace),
+                KeyCode::Char(c) => Some(Message::LogcatInput(c)),
+                _ => None,
+            },
+
+            AppState::Logcat => match key {
+                KeyCode::Up | KeyCode::Char('k') => Some(Message::LogcatScroll(1)),
+                KeyCode::Down | KeyCode::Char('j') => Some(Message::LogcatScroll(-1)),
+                KeyCode::PageUp => Some(Message::LogcatScroll(10)),
+                KeyCode::PageDown => Some(Message::LogcatScroll(-10)),
+                KeyCode::Home => Some(Message::LogcatScroll(isize::MAX)),
+                KeyCode::End | KeyCode::Char('f') => Some(Message::LogcatFollow),
+                KeyCode::Char('l') => Some(Message::LogcatCycleLevel),
+                KeyCode::Char('t') => Some(Message::LogcatEditFilter(LogcatField::Tag)),
+                KeyCode::Char('p') => Some(Message::LogcatEditFilter(LogcatField::Package)),
+                KeyCode::Char('/') => Some(Message::LogcatEditFilter(LogcatField::Regex)),
+                KeyCode::Char('c') => Some(Message::LogcatClearFilters),
+                KeyCode::Esc | KeyCode::Char('q') => Some(Message::StopLogcat),
+                _ => None,
+            },
+
+            AppState::FileBrowser => match self.model.files.input {
+                Some((FileInput::ConfirmDelete, _)) => match key {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => Some(Message::FileSubmitInput),
+                    _ => Some(Message::FileCancelInput),
+                },
+                Some((FileInput::Rename, _)) => match key {
+                    KeyCode::Enter => Some(Message::FileSubmitInput),
+                    KeyCode::Esc => Some(Message::FileCancelInput),
+                    KeyCode::Backspace => Some(Message::FileBackspace),
+                    KeyCode::Char(c) => Some(Message::FileInput(c)),
+                    _ => None,
+                },
+                None => match key {
+                    KeyCode::Up | KeyCode::Char('k') => Some(Message::FileUp),
+                    KeyCode::Down | KeyCode::Char('j') => Some(Message::FileDown),
+                    KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => Some(Message::FileOpen),
+                    KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => {
+                        Some(Message::FileParent)
+                    }
+                    KeyCode::Tab => Some(Message::FileSwitchPane),
+                    KeyCode::Char('c') => Some(Message::FileCopy),
+                    KeyCode::Char('D') | KeyCode::Delete => Some(Message::FileDelete),
+                    KeyCode::Char('r') => Some(Message::FileRename),
+                    KeyCode::Char('R') => Some(Message::FileRefresh),
+                    KeyCode::Esc | KeyCode::Char('q') => Some(Message::ReturnToMenu),
+                    _ => None,
+                },
+            },
+
+            AppState::Packages if self.model.packages.searching => match key {
+                KeyCode::Enter | KeyCode::Esc => Some(Message::PackageSearchDone),
+                KeyCode::Backspace => Some(Message::PackageBackspace),
+                KeyCode::Up => Some(Message::PackageUp),
+                KeyCode::Down => Some(Message::PackageDown),
+                KeyCode::Char(c) => Some(Message::PackageInput(c)),
+                _ => None,
+            },
+
+            AppState::Packages if self.model.packages.action.is_some() => match key {
+                KeyCode::Up | KeyCode::Char('k') => Some(Message::PackageUp),
+                KeyCode::Down | KeyCode::Char('j') => Some(Message::PackageDown),
+                KeyCode::Enter => Some(Message::PackageRunAction),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => {
+                    Some(Message::PackageCloseActions)
+                }
+                _ => None,
+            },
+
+            AppState::Packages => match key {
+                KeyCode::Up | KeyCode::Char('k') => Some(Message::PackageUp),
+                KeyCode::Down | KeyCode::Char('j') => Some(Message::PackageDown),
+                KeyCode::Char('/') => Some(Message::PackageSearch),
+                KeyCode::Enter => Some(Message::PackageOpenActions),
+                KeyCode::Char('r') => Some(Message::RefreshPackages),
+                KeyCode::Esc | KeyCode::Char('q') => Some(Message::ReturnToMenu),
+                _ => None,
+            },
+
+            AppState::Screenshot => match key {
+                KeyCode::Char('s') | KeyCode::Char('r') => {
+                    Some(Message::ExecuteCommand(AdbCommand::TakeScreenshot))
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter | KeyCode::Backspace => {
+                    Some(Message::ReturnToMenu)
+                }
+                _ => None,
+            },
+
+            AppState::Processes if self.model.processes.searching => match key {
+                KeyCode::Enter | KeyCode::Esc => Some(Message::ProcessSearchDone),
+                KeyCode::Backspace => Some(Message::ProcessBackspace),
+                KeyCode::Up => Some(Message::ProcessMove(-1)),
+                KeyCode::Down => Some(Message::ProcessMove(1)),
+                KeyCode::Char(c) => Some(Message::ProcessInput(c)),
+                _ => None,
+            },
+
+            AppState::Processes if self.model.processes.action.is_some() => match key {
+                KeyCode::Up | KeyCode::Char('k') => Some(Message::ProcessMove(-1)),
+                KeyCode::Down | KeyCode::Char('j') => Some(Message::ProcessMove(1)),
+                KeyCode::Enter => Some(Message::ProcessRunAction),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => {
+                    Some(Message::ProcessCloseActions)
+                }
+                _ => None,
+            },
+
+            AppState::Processes => match key {
+                KeyCode::Up | KeyCode::Char('k') => Some(Message::ProcessMove(-1)),
+                KeyCode::Down | KeyCode::Char('j') => Some(Message::ProcessMove(1)),
+                KeyCode::PageUp => Some(Message::ProcessMove(-10)),
+                KeyCode::PageDown => Some(Message::ProcessMove(10)),
+                KeyCode::Char('/') => Some(Message::ProcessSearch),
+                KeyCode::Char('s') => Some(Message::ProcessCycleSort),
+                KeyCode::Char('o') => Some(Message::ProcessReverseSort),
+                KeyCode::Enter => Some(Message::ProcessOpenActions),
+                KeyCode::Esc | KeyCode::Char('q') => Some(Message::ReturnToMenu),
+                _ => None,
+            },
+
+            AppState::Dashboard => match key {
+                KeyCode::Char('+') | KeyCode::Char('=') => Some(Message::DashboardFaster),
+                KeyCode::Char('-') => Some(Message::DashboardSlower),
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Backspace => {
+                    Some(Message::ReturnToMenu)
+                }
+                _ => None,
+            },
+
+            AppState::Streaming => match key {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('s') => Some(Message::StopStream),
+                _ => None,
+            },
+
+            AppState::Mirror => match key {
                 KeyCode::Esc | KeyCode::Char('q') => Some(Message::ReturnToMenu),
                 _ => None,
             },
 
+            AppState::Recording => match key {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('s') => {
+                    Some(Message::StopRecording)
+                }
+                _ => None,
+            },
+
+            AppState::Rebooting => match key {
+                KeyCode::Esc | KeyCode::Char('q') => Some(Message::ReturnToMenu),
+                _ => None,
+            },
+
+            AppState::Confirm => match key {
+                KeyCode::Char('y') | KeyCode::Char('Y') => Some(Message::ConfirmAccept),
+                _ => Some(Message::ConfirmDecline),
+            },
+
+            AppState::Executing | AppState::Loading => match key {
+                KeyCode::Esc | KeyCode::Char('q') => Some(Message::CancelCommand),
+                _ => None,
+            },
+
             AppState::ShowResult => match key {
                 KeyCode::Up | KeyCode::Char('k') => Some(Message::ScrollUp),
                 KeyCode::Down | KeyCode::Char('j') => Some(Message::ScrollDown),
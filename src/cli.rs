@@ -0,0 +1,721 @@
+//! Command line interface
+//!
+//! Without a subcommand droidtui starts the TUI. Subcommands run a single
+//! [`AdbCommand`] for scripts, print plain text or JSON, and exit with a code
+//! describing the [`AdbError`] on failure.
+
+use crate::adb::{
+    AdbCommand, AdbError, AdbManager, AdbOutput, BackendKind, InstallOptions, RebootTarget,
+    RecordOptions, ServerAddress,
+};
+use crate::config::Config;
+use crate::logcat::{LogLevel, LogRecord, LogcatFilter};
+use crate::record;
+use crate::wireless::DEFAULT_TCP_PORT;
+use clap::{Parser, Subcommand};
+use regex::Regex;
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::sync::atomic::AtomicU64;
+use std::time::Duration;
+
+/// Exit code for invalid arguments, matching clap's
+const USAGE_ERROR: u8 = 2;
+
+/// Exit code for an invalid `config.toml`, server address or backend
+pub const CONFIG_ERROR: u8 = 12;
+
+/// A terminal UI for Android development and ADB commands
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// ADB server address: port, host, host:port, [ipv6]:port or tcp:host:port
+    #[arg(long, global = true, value_name = "ADDR", conflicts_with_all = ["host", "port"])]
+    pub server: Option<String>,
+
+    /// ADB server host name or IP (like `adb -H`)
+    #[arg(short = 'H', long, global = true)]
+    pub host: Option<String>,
+
+    /// ADB server port (like `adb -P`)
+    #[arg(short = 'P', long, global = true)]
+    pub port: Option<u16>,
+
+    /// Serial of the device to use (like `adb -s`)
+    #[arg(short, long, global = true)]
+    pub serial: Option<String>,
+
+    /// Refuse reboots, uninstalls and other destructive commands
+    #[arg(long, global = true)]
+    pub read_only: bool,
+
+    /// How to reach devices: client (ADB server protocol), binary (the adb tool) or mock
+    #[arg(long, global = true, value_name = "KIND")]
+    pub backend: Option<BackendKind>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Scripting subcommands
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run a single command and print its output
+    Run {
+        #[command(subcommand)]
+        command: RunCommand,
+        /// Print `{"output": ...}` instead of plain text
+        #[arg(long)]
+        json: bool,
+    },
+    /// List attached devices
+    Devices {
+        #[arg(long)]
+        json: bool,
+    },
+    /// List installed packages
+    Packages {
+        /// Only packages installed by the user
+        #[arg(long, conflicts_with = "system")]
+        user: bool,
+        /// Only packages from the system image
+        #[arg(long)]
+        system: bool,
+        /// Only disabled packages
+        #[arg(long, conflicts_with = "enabled")]
+        disabled: bool,
+        /// Only enabled packages
+        #[arg(long)]
+        enabled: bool,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the device log, following it unless --dump is given
+    Logcat {
+        /// Minimum level: V, D, I, W, E or F
+        #[arg(short, long, value_parser = parse_level, default_value = "V")]
+        level: LogLevel,
+        /// Only tags containing this text
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Only records whose tag or message matches this regex
+        #[arg(short, long)]
+        grep: Option<String>,
+        /// Print the current buffer and exit
+        #[arg(short, long)]
+        dump: bool,
+        /// One JSON object per record
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Commands available through `droidtui run`
+#[derive(Debug, Clone, Subcommand)]
+pub enum RunCommand {
+    /// List attached devices
+    Devices,
+    /// State of the selected device
+    State,
+    /// Serial of the selected device
+    Serial,
+    /// Battery status
+    Battery,
+    /// Memory usage
+    Memory,
+    /// CPU information
+    Cpu,
+    /// System properties
+    Props,
+    /// Network interfaces
+    Network,
+    /// Wi-Fi status
+    Wifi,
+    /// Screen size and density
+    Screen,
+    /// Running processes
+    Processes,
+    /// ADB server version
+    Version,
+    /// Reboot the device
+    Reboot {
+        /// system, bootloader, recovery, sideload or fastboot
+        #[arg(default_value = "system")]
+        target: RebootTarget,
+        /// Wait until the device is back (like `adb wait-for-device`)
+        #[arg(short, long)]
+        wait: bool,
+    },
+    /// Save a screenshot to the screenshot directory
+    Screenshot,
+    /// Record the screen to an MP4 file in the screenshot directory
+    Record {
+        /// Stop after this many seconds (at most 180)
+        #[arg(short, long, default_value_t = 180)]
+        time_limit: u64,
+        /// Show the stream in a window while recording; closing it stops
+        #[arg(short, long)]
+        window: bool,
+    },
+    /// Last lines of the system log
+    Log {
+        #[arg(short = 'n', long, default_value_t = 100)]
+        lines: usize,
+    },
+    /// Details of an installed package
+    Info { package: String },
+    /// Install one APK, or a base APK with its splits
+    Install {
+        #[arg(required = true)]
+        apks: Vec<String>,
+        /// Replace an existing app, keeping its data
+        #[arg(short, long)]
+        replace: bool,
+        /// Allow a version downgrade
+        #[arg(short = 'd', long)]
+        allow_downgrade: bool,
+        /// Grant all runtime permissions
+        #[arg(short, long)]
+        grant: bool,
+        /// Allow test-only APKs
+        #[arg(short = 't', long)]
+        allow_test: bool,
+    },
+    /// Uninstall a package
+    Uninstall { package: String },
+    /// Clear a package's data
+    Clear { package: String },
+    /// Force stop a package
+    Stop { package: String },
+    /// Start a package's launcher activity
+    Launch { package: String },
+    /// Enable a package
+    Enable { package: String },
+    /// Disable a package for the current user
+    Disable { package: String },
+    /// Connect to a device over the network
+    Connect { address: String },
+    /// Disconnect a network device, or all of them
+    Disconnect { address: Option<String> },
+    /// Pair with a device using its wireless debugging code
+    Pair { address: String, code: String },
+    /// Restart adbd in TCP mode
+    Tcpip {
+        #[arg(default_value_t = DEFAULT_TCP_PORT)]
+        port: u16,
+    },
+    /// List a directory on the device
+    Ls { path: String },
+    /// Run a shell command
+    Shell {
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+}
+
+impl RunCommand {
+    /// The [`AdbCommand`] this subcommand runs
+    pub fn to_adb_command(&self) -> AdbCommand {
+        match self.clone() {
+            RunCommand::Devices => AdbCommand::ListDevices,
+            RunCommand::State => AdbCommand::GetDeviceState,
+            RunCommand::Serial => AdbCommand::GetSerialNumber,
+            RunCommand::Battery => AdbCommand::GetBatteryInfo,
+            RunCommand::Memory => AdbCommand::GetMemoryInfo,
+            RunCommand::Cpu => AdbCommand::GetCpuInfo,
+            RunCommand::Props => AdbCommand::GetDeviceProperties,
+            RunCommand::Network => AdbCommand::GetNetworkInfo,
+            RunCommand::Wifi => AdbCommand::GetWifiStatus,
+            RunCommand::Screen => AdbCommand::GetScreenResolution,
+            RunCommand::Processes => AdbCommand::ListProcesses,
+            RunCommand::Version => AdbCommand::GetAdbVersion,
+            RunCommand::Reboot { target, .. } => AdbCommand::Reboot { target },
+            RunCommand::Screenshot => AdbCommand::TakeScreenshot,
+            RunCommand::Record { time_limit, window } => AdbCommand::RecordScreen {
+                options: record_options(time_limit, window),
+            },
+            RunCommand::Log { lines } => AdbCommand::GetSystemLog { lines },
+            RunCommand::Info { package } => AdbCommand::GetPackageInfo {
+                package_name: package,
+            },
+            RunCommand::Install {
+                mut apks,
+                replace,
+                allow_downgrade,
+                grant,
+                allow_test,
+            } => {
+                let options = InstallOptions {
+                    replace,
+                    allow_downgrade,
+                    grant_permissions: grant,
+                    allow_test,
+                };
+                if apks.len() == 1 {
+                    AdbCommand::InstallPackage {
+                        apk_path: apks.remove(0),
+                        options,
+                    }
+                } else {
+                    AdbCommand::InstallSplitPackage {
+                        apk_paths: apks,
+                        options,
+                    }
+                }
+            }
+            RunCommand::Uninstall { package } => AdbCommand::UninstallPackage {
+                package_name: package,
+            },
+            RunCommand::Clear { package } => AdbCommand::ClearPackageData {
+                package_name: package,
+            },
+            RunCommand::Stop { package } => AdbCommand::ForceStop {
+                package_name: package,
+            },
+            RunCommand::Launch { package } => AdbCommand::LaunchPackage {
+                package_name: package,
+            },
+            RunCommand::Enable { package } => AdbCommand::SetPackageEnabled {
+                package_name: package,
+                enabled: true,
+            },
+            RunCommand::Disable { package } => AdbCommand::SetPackageEnabled {
+                package_name: package,
+                enabled: false,
+            },
+            RunCommand::Connect { address } => AdbCommand::Connect { address },
+            RunCommand::Disconnect { address } => AdbCommand::Disconnect { address },
+            RunCommand::Pair { address, code } => AdbCommand::Pair { address, code },
+            RunCommand::Tcpip { port } => AdbCommand::TcpIp { port },
+            RunCommand::Ls { path } => AdbCommand::ListDirectory { path },
+            RunCommand::Shell { command } => AdbCommand::Shell {
+                command: command.join(" "),
+            },
+        }
+    }
+}
+
+/// Recording settings for `droidtui run record`
+fn record_options(time_limit: u64, show_window: bool) -> RecordOptions {
+    RecordOptions {
+        max_duration: Duration::from_secs(time_limit),
+        show_window,
+        ..RecordOptions::default()
+    }
+}
+
+fn parse_level(value: &str) -> Result<LogLevel, String> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => LogLevel::from_char(c.to_ascii_uppercase()),
+        _ => None,
+    }
+    .ok_or_else(|| format!("expected one of V, D, I, W, E, F; got {}", value))
+}
+
+impl Cli {
+    /// Server address given on the command line, if any
+    fn server_address(&self) -> Result<Option<ServerAddress>, String> {
+        if let Some(server) = &self.server {
+            return ServerAddress::parse(server).map(Some);
+        }
+        if self.host.is_none() && self.port.is_none() {
+            return Ok(None);
+        }
+        let default = ServerAddress::default();
+        Ok(Some(ServerAddress {
+            host: self
+                .host
+                .as_deref()
+                .map(|host| host.trim_matches(['[', ']']).to_string())
+                .unwrap_or(default.host),
+            port: self.port.unwrap_or(default.port),
+        }))
+    }
+
+    /// Pick the ADB server address: command line, then environment, then config file
+    pub fn resolve_server(&self, config: &Config) -> Result<ServerAddress, String> {
+        if let Some(address) = self.server_address()? {
+            return Ok(address);
+        }
+        if let Some(address) = ServerAddress::from_env()? {
+            return Ok(address);
+        }
+        Ok(config.server_address()?.unwrap_or_default())
+    }
+}
+
+/// Run a scripting subcommand and report how it went
+pub fn run(command: Command, manager: &mut AdbManager) -> ExitCode {
+    let mut stdout = io::stdout().lock();
+    let result = match command {
+        Command::Run { command, json } => run_command(manager, &command, json, &mut stdout),
+        Command::Devices { json } => list_devices(manager, json, &mut stdout),
+        Command::Packages {
+            user,
+            system,
+            disabled,
+            enabled,
+            json,
+        } => list_packages(
+            manager,
+            [user, system, disabled, enabled],
+            json,
+            &mut stdout,
+        ),
+        Command::Logcat {
+            level,
+            tag,
+            grep,
+            dump,
+            json,
+        } => {
+            let regex = match grep.as_deref().map(Regex::new).transpose() {
+                Ok(regex) => regex,
+                Err(e) => {
+                    eprintln!("droidtui: invalid --grep: {}", e);
+                    return ExitCode::from(USAGE_ERROR);
+                }
+            };
+            let filter = LogcatFilter {
+                min_level: level,
+                tag,
+                pid: None,
+                regex,
+            };
+            logcat(manager, &filter, dump, json, &mut stdout)
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // The reader went away, e.g. `droidtui logcat | head`
+        Err(AdbError::IoError(e)) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("droidtui: {}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+fn run_command(
+    manager: &mut AdbManager,
+    command: &RunCommand,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), AdbError> {
+    let output = match command {
+        // Going down and coming back each get the reboot timeout
+        RunCommand::Reboot { target, wait: true } => {
+            let timeout = manager.timeouts().get(&command.to_adb_command());
+            let serial = manager.with_timeout(timeout, |m| m.reboot_device(*target))?;
+            AdbOutput::Text(manager.with_timeout(timeout, |m| m.wait_for_device(&serial, *target))?)
+        }
+        // The recorder drives ffmpeg and the window around the device stream
+        RunCommand::Record { time_limit, window } => {
+            let options = record_options(*time_limit, *window);
+            let timeout = manager.timeouts().get(&command.to_adb_command());
+            let summary = manager
+                .with_timeout(timeout, |m| record::record(m, &options, &AtomicU64::new(0)))?;
+            AdbOutput::Text(summary.describe())
+        }
+        command => manager.execute_output(command.to_adb_command())?,
+    };
+    if json {
+        // Parsed commands add their typed value next to the raw text
+        let value = match &output {
+            AdbOutput::Text(raw) => serde_json::json!({ "output": raw }),
+            AdbOutput::Battery { status, raw } => {
+                serde_json::json!({ "output": raw, "battery": status })
+            }
+            AdbOutput::Memory { info, raw } => {
+                serde_json::json!({ "output": raw, "memory": info })
+            }
+            AdbOutput::Connectivity { state, raw } => {
+                serde_json::json!({ "output": raw, "connectivity": state })
+            }
+            AdbOutput::Shell { output, raw } => serde_json::json!({
+                "output": raw,
+                "stdout": output.stdout,
+                "stderr": output.stderr,
+                "exit_code": output.exit_code,
+            }),
+        };
+        writeln!(out, "{}", value)?;
+        return exit_status(&output);
+    }
+
+    // Shell commands keep their stderr on ours
+    let text = match &output {
+        AdbOutput::Shell { output, .. } if !output.stderr.is_empty() => {
+            eprint!("{}", output.stderr);
+            &output.stdout
+        }
+        output => output.text(),
+    };
+    write!(out, "{}", text)?;
+    if !text.is_empty() && !text.ends_with('\n') {
+        writeln!(out)?;
+    }
+    exit_status(&output)
+}
+
+/// Fail when a shell command exited non-zero; its output has been printed already
+fn exit_status(output: &AdbOutput) -> Result<(), AdbError> {
+    match output.exit_code() {
+        Some(code) if code != 0 => Err(AdbError::ExitStatus {
+            code,
+            output: String::new(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn list_devices(
+    manager: &mut AdbManager,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), AdbError> {
+    let devices = manager.devices()?;
+    if json {
+        writeln!(out, "{}", to_json(&devices)?)?;
+        return Ok(());
+    }
+    for device in devices {
+        writeln!(
+            out,
+            "{}\t{}\t{}",
+            device.serial,
+            device.state,
+            device.model.as_deref().unwrap_or("-")
+        )?;
+    }
+    Ok(())
+}
+
+/// `[user, system, disabled, enabled]` restrict the listing; none means everything
+fn list_packages(
+    manager: &mut AdbManager,
+    [user, system, disabled, enabled]: [bool; 4],
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), AdbError> {
+    let packages: Vec<_> = manager
+        .packages()?
+        .into_iter()
+        .filter(|p| (!user || !p.system) && (!system || p.system))
+        .filter(|p| (!disabled || !p.enabled) && (!enabled || p.enabled))
+        .collect();
+
+    if json {
+        writeln!(out, "{}", to_json(&packages)?)?;
+        return Ok(());
+    }
+    for package in packages {
+        writeln!(out, "{}", package.name)?;
+    }
+    Ok(())
+}
+
+fn logcat(
+    manager: &mut AdbManager,
+    filter: &LogcatFilter,
+    dump: bool,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), AdbError> {
+    let command = if dump {
+        "logcat -d -v threadtime"
+    } else {
+        "logcat -v threadtime"
+    };
+    let mut writer = LogcatWriter {
+        filter,
+        json,
+        out,
+        pending: Vec::new(),
+    };
+    manager.shell_stream(command, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Filters logcat output line by line on its way to stdout
+struct LogcatWriter<'a, W: Write> {
+    filter: &'a LogcatFilter,
+    json: bool,
+    out: &'a mut W,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> LogcatWriter<'_, W> {
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        // Banners such as `--------- beginning of main` aren't records
+        let Some(record) = LogRecord::parse(line) else {
+            return Ok(());
+        };
+        if !self.filter.matches(&record) {
+            return Ok(());
+        }
+        if self.json {
+            let value = serde_json::json!({
+                "time": record.time,
+                "pid": record.pid,
+                "tid": record.tid,
+                "level": record.level.as_char().to_string(),
+                "tag": record.tag,
+                "message": record.message,
+            });
+            writeln!(self.out, "{}", value)
+        } else {
+            writeln!(self.out, "{}", line.trim_end_matches('\r'))
+        }
+    }
+}
+
+impl<W: Write> Write for LogcatWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            self.write_line(String::from_utf8_lossy(&line[..end]).as_ref())?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.pending.is_empty() {
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.pending)).to_string();
+            self.write_line(&line)?;
+        }
+        self.out.flush()
+    }
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<String, AdbError> {
+    serde_json::to_string_pretty(value).map_err(|e| AdbError::ParseError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+        assert!(Cli::try_parse_from(["droidtui"]).unwrap().command.is_none());
+    }
+
+    #[test]
+    fn test_run_maps_to_adb_commands() {
+        let cli =
+            Cli::try_parse_from(["droidtui", "-s", "emulator-5554", "run", "battery"]).unwrap();
+        assert_eq!(cli.serial.as_deref(), Some("emulator-5554"));
+        assert!(!cli.read_only);
+        let Some(Command::Run { command, json }) = cli.command else {
+            panic!("expected run");
+        };
+        assert!(!json);
+        assert!(matches!(
+            command.to_adb_command(),
+            AdbCommand::GetBatteryInfo
+        ));
+
+        let cli =
+            Cli::try_parse_from(["droidtui", "run", "shell", "ls", "-la", "/sdcard"]).unwrap();
+        let Some(Command::Run { command, .. }) = cli.command else {
+            panic!("expected run");
+        };
+        assert!(matches!(
+            command.to_adb_command(),
+            AdbCommand::Shell { command } if command == "ls -la /sdcard"
+        ));
+
+        let cli = Cli::try_parse_from(["droidtui", "run", "reboot", "recovery", "--wait"]).unwrap();
+        let Some(Command::Run { command, .. }) = cli.command else {
+            panic!("expected run");
+        };
+        assert!(matches!(command, RunCommand::Reboot { wait: true, .. }));
+        assert!(matches!(
+            command.to_adb_command(),
+            AdbCommand::Reboot {
+                target: RebootTarget::Recovery
+            }
+        ));
+        assert!(Cli::try_parse_from(["droidtui", "run", "reboot", "download"]).is_err());
+    }
+
+    #[test]
+    fn test_record_options() {
+        let cli =
+            Cli::try_parse_from(["droidtui", "run", "record", "-t", "30", "--window"]).unwrap();
+        let Some(Command::Run { command, .. }) = cli.command else {
+            panic!("expected run");
+        };
+        let AdbCommand::RecordScreen { options } = command.to_adb_command() else {
+            panic!("expected record");
+        };
+        assert_eq!(options.max_duration, Duration::from_secs(30));
+        assert!(options.show_window);
+    }
+
+    #[test]
+    fn test_backend_flag() {
+        let cli = Cli::try_parse_from(["droidtui", "--backend", "mock", "run", "battery"]).unwrap();
+        assert_eq!(cli.backend, Some(BackendKind::Mock));
+        assert!(Cli::try_parse_from(["droidtui", "--backend", "usb"]).is_err());
+    }
+
+    #[test]
+    fn test_logcat_level_and_conflicts() {
+        let cli = Cli::try_parse_from(["droidtui", "logcat", "--level", "e"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Logcat {
+                level: LogLevel::Error,
+                ..
+            })
+        ));
+        assert!(Cli::try_parse_from(["droidtui", "logcat", "--level", "X"]).is_err());
+        assert!(Cli::try_parse_from(["droidtui", "packages", "--user", "--system"]).is_err());
+    }
+
+    #[test]
+    fn test_logcat_writer_filters_lines() {
+        let filter = LogcatFilter {
+            min_level: LogLevel::Warn,
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let mut writer = LogcatWriter {
+            filter: &filter,
+            json: false,
+            out: &mut out,
+            pending: Vec::new(),
+        };
+        writer
+            .write_all(
+                b"--------- beginning of main\n\
+                  01-31 14:25:02.123  1234  1240 I Tag     : quiet\n\
+                  01-31 14:25:02.124  1234  1240 E Tag     : loud\n",
+            )
+            .unwrap();
+        writer.flush().unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "01-31 14:25:02.124  1234  1240 E Tag     : loud\n"
+        );
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(AdbError::DeviceNotFound.exit_code(), 4);
+        assert_eq!(AdbError::NoDeviceSelected.exit_code(), 5);
+        assert_eq!(AdbError::ReadOnly.exit_code(), 10);
+        assert_ne!(
+            AdbError::ConnectionError(String::new()).exit_code(),
+            AdbError::CommandFailed(String::new()).exit_code()
+        );
+    }
+}
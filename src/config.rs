@@ -0,0 +1,253 @@
+//! User configuration
+//!
+//! Read from `$XDG_CONFIG_HOME/droidtui/config.toml` (or `~/.config/droidtui/config.toml`).
+//! A missing file is the same as an empty one.
+//!
+//! ```toml
+//! server = "127.0.0.1:5037"
+//! read_only = true
+//! backend = "binary"
+//! screenshot_dir = "/home/me/Pictures/android"
+//!
+//! [dashboard]
+//! interval_ms = 2000
+//!
+//! # Seconds per command, by name; 0 waits forever
+//! [timeouts]
+//! get_memory_info = 60
+//! shell = 0
+//!
+//! # Built-in entries are matched by label, ignoring the leading emoji
+//! [[menu]]
+//! label = "Screen Stream"
+//! hide = true
+//!
+//! [[menu]]
+//! label = "🧪 My Tools"
+//! position = 0
+//! children = [
+//!     { label = "Start app", shell = "am start -n {package}/.MainActivity" },
+//! ]
+//! ```
+
+use crate::adb::screencap::SCREENSHOT_DIR_ENV;
+use crate::adb::{BackendKind, CommandTimeouts, ServerAddress};
+use crate::dashboard::DEFAULT_INTERVAL;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Contents of `config.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// ADB server address, e.g. `"127.0.0.1:5037"` or `"[::1]:5037"`
+    pub server: Option<String>,
+    /// Refuse reboots, uninstalls and other destructive commands
+    pub read_only: bool,
+    /// Transport to use: `client`, `binary` or `mock`
+    pub backend: Option<String>,
+    /// Where screenshots and recordings are saved
+    pub screenshot_dir: Option<PathBuf>,
+    /// Additions and overrides for the main menu, applied in order
+    pub menu: Vec<MenuEntry>,
+    pub dashboard: DashboardConfig,
+    /// Timeout overrides in seconds, keyed by command name
+    pub timeouts: BTreeMap<String, u64>,
+}
+
+/// The `[dashboard]` table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DashboardConfig {
+    /// How often the live dashboard polls the device
+    pub interval_ms: Option<u64>,
+}
+
+/// A `[[menu]]` entry: overrides the built-in item with the same label, or adds one
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MenuEntry {
+    pub label: String,
+    pub description: Option<String>,
+    /// Shell command run on the device; `{name}` placeholders are asked for first
+    pub shell: Option<String>,
+    /// Remove the matching built-in entry
+    pub hide: bool,
+    /// Move the entry to this index
+    pub position: Option<usize>,
+    pub children: Vec<MenuEntry>,
+}
+
+impl Config {
+    /// Load the user's config file
+    pub fn load() -> Result<Self, String> {
+        match config_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load a config file, treating a missing file as empty
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        toml::from_str(content).map_err(|e| e.to_string())
+    }
+
+    /// Live dashboard poll interval
+    pub fn dashboard_interval(&self) -> Duration {
+        self.dashboard
+            .interval_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_INTERVAL)
+    }
+
+    /// Parsed `server` entry, if set
+    pub fn server_address(&self) -> Result<Option<ServerAddress>, String> {
+        self.server
+            .as_deref()
+            .map(|server| ServerAddress::parse(server).map_err(|e| format!("server: {}", e)))
+            .transpose()
+    }
+
+    /// Command timeouts with the `[timeouts]` overrides applied
+    pub fn timeouts(&self) -> Result<CommandTimeouts, String> {
+        let mut timeouts = CommandTimeouts::default();
+        for (name, &secs) in &self.timeouts {
+            let timeout = (secs > 0).then(|| Duration::from_secs(secs));
+            timeouts
+                .set(name, timeout)
+                .map_err(|e| format!("timeouts: {}", e))?;
+        }
+        Ok(timeouts)
+    }
+
+    /// `screenshot_dir` entry, unless `$DROIDTUI_SCREENSHOT_DIR` overrides it
+    pub fn screenshot_dir(&self) -> Option<PathBuf> {
+        let overridden = std::env::var_os(SCREENSHOT_DIR_ENV).is_some_and(|dir| !dir.is_empty());
+        self.screenshot_dir.clone().filter(|_| !overridden)
+    }
+
+    /// Parsed `backend` entry, if set
+    pub fn backend(&self) -> Result<Option<BackendKind>, String> {
+        self.backend
+            .as_deref()
+            .map(|backend| backend.parse().map_err(|e| format!("backend: {}", e)))
+            .transpose()
+    }
+}
+
+/// Location of `config.toml`
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|home| !home.is_empty())
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+    Some(base.join("droidtui").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+        assert_eq!(
+            Config::parse("server = \"[::1]:5038\"\n")
+                .unwrap()
+                .server
+                .as_deref(),
+            Some("[::1]:5038")
+        );
+        assert!(Config::parse("sever = \"typo\"").is_err());
+        assert!(Config::parse("read_only = true\n").unwrap().read_only);
+        assert_eq!(
+            Config::parse("backend = \"mock\"\n").unwrap().backend(),
+            Ok(Some(BackendKind::Mock))
+        );
+        assert!(Config::parse("backend = \"usb\"\n")
+            .unwrap()
+            .backend()
+            .is_err());
+        assert_eq!(
+            Config::parse("screenshot_dir = \"/tmp/shots\"\n")
+                .unwrap()
+                .screenshot_dir,
+            Some(PathBuf::from("/tmp/shots"))
+        );
+    }
+
+    #[test]
+    fn test_dashboard_interval() {
+        assert_eq!(Config::default().dashboard_interval(), DEFAULT_INTERVAL);
+        let config = Config::parse("[dashboard]\ninterval_ms = 2500\n").unwrap();
+        assert_eq!(config.dashboard_interval(), Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn test_timeouts() {
+        use crate::adb::AdbCommand;
+
+        let config = Config::parse("[timeouts]\nget_memory_info = 60\nshell = 0\n").unwrap();
+        let timeouts = config.timeouts().unwrap();
+        assert_eq!(
+            timeouts.get(&AdbCommand::GetMemoryInfo),
+            Some(Duration::from_secs(60))
+        );
+        let shell = AdbCommand::Shell {
+            command: "sleep 100".to_string(),
+        };
+        assert_eq!(timeouts.get(&shell), None);
+
+        let config = Config::parse("[timeouts]\nbattery = 5\n").unwrap();
+        assert_eq!(
+            config.timeouts(),
+            Err("timeouts: unknown command battery".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_menu_entries() {
+        let config = Config::parse(
+            r#"
+            [[menu]]
+            label = "Screen Stream"
+            hide = true
+
+            [[menu]]
+            label = "My Tools"
+            position = 0
+            children = [{ label = "Start", shell = "am start -n {package}/.Main" }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.menu.len(), 2);
+        assert!(config.menu[0].hide);
+        assert_eq!(config.menu[1].position, Some(0));
+        assert_eq!(
+            config.menu[1].children[0].shell.as_deref(),
+            Some("am start -n {package}/.Main")
+        );
+    }
+
+    #[test]
+    fn test_missing_config_is_empty() {
+        let path = std::env::temp_dir().join("droidtui-missing-config.toml");
+        assert_eq!(Config::load_from(&path).unwrap(), Config::default());
+    }
+}
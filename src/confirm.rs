@@ -0,0 +1,63 @@
+//! Confirmation of destructive commands
+//!
+//! Commands classified by [`AdbCommand::is_destructive`] stop at a modal
+//! naming the device before they run.
+
+use crate::adb::{AdbCommand, PackageEntry, ProcessEntry};
+use crate::model::AppState;
+use crate::packages::PackageAction;
+use crate::processes::ProcessAction;
+
+/// What runs once the user confirms
+#[derive(Debug, Clone)]
+pub enum PendingAction {
+    /// A command from the menu
+    Command(AdbCommand),
+    /// An action from the package manager
+    Package {
+        action: PackageAction,
+        package: PackageEntry,
+    },
+    /// An action from the process manager
+    Process {
+        action: ProcessAction,
+        process: ProcessEntry,
+    },
+}
+
+impl PendingAction {
+    /// ADB command the action runs
+    pub fn command(&self) -> AdbCommand {
+        match self {
+            PendingAction::Command(command) => command.clone(),
+            PendingAction::Package { action, package } => action.command(package),
+            PendingAction::Process { action, process } => action.command(process),
+        }
+    }
+}
+
+/// A destructive action waiting for a yes
+#[derive(Debug, Clone)]
+pub struct Confirmation {
+    /// What is about to happen, e.g. "🔄 Normal Reboot"
+    pub title: String,
+    /// Device it runs on, named as in the header
+    pub device: Option<String>,
+    pub action: PendingAction,
+    /// Screen to go back to either way
+    pub previous: AppState,
+}
+
+impl Confirmation {
+    /// Command line or target shown under the title
+    pub fn detail(&self) -> String {
+        match &self.action {
+            PendingAction::Command(AdbCommand::Shell { command }) => command.clone(),
+            PendingAction::Command(command) => format!("{:?}", command),
+            PendingAction::Package { package, .. } => package.name.clone(),
+            PendingAction::Process { process, .. } => {
+                format!("{} (pid {})", process.name, process.pid)
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Live device dashboard
+//!
+//! Every poll reads `/proc` and `dumpsys battery` in a single shell round trip on a
+//! worker thread. Samples are kept in rolling histories for the sparklines. CPU load
+//! and network throughput are rates, so they appear from the second sample on.
+
+use crate::adb::DeviceSample;
+use crate::worker::{PollError, PollJob};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Samples kept per metric
+pub const HISTORY_LEN: usize = 120;
+
+/// Poll interval unless configured otherwise
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Rolling window of the last [`HISTORY_LEN`] values of a metric
+#[derive(Debug, Clone, Default)]
+pub struct History(VecDeque<u64>);
+
+impl History {
+    pub fn push(&mut self, value: u64) {
+        if self.0.len() == HISTORY_LEN {
+            self.0.pop_front();
+        }
+        self.0.push_back(value);
+    }
+
+    pub fn latest(&self) -> Option<u64> {
+        self.0.back().copied()
+    }
+
+    /// The most recent `count` values, oldest first
+    pub fn tail(&self, count: usize) -> Vec<u64> {
+        self.0
+            .iter()
+            .skip(self.0.len().saturating_sub(count))
+            .copied()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Result of a background poll
+#[derive(Debug, Clone)]
+pub struct DashboardEvent {
+    pub id: u64,
+    pub result: Result<DeviceSample, PollError>,
+}
+
+/// Dashboard state: latest sample, histories and polling schedule
+#[derive(Debug)]
+pub struct Dashboard {
+    pub interval: Duration,
+    pub latest: Option<DeviceSample>,
+    /// CPU usage in percent
+    pub cpu: History,
+    /// Used RAM in percent
+    pub memory: History,
+    /// Received bytes per second
+    pub rx: History,
+    /// Sent bytes per second
+    pub tx: History,
+    /// Battery temperature in tenths of a degree
+    pub temperature: History,
+    /// Why the last poll failed
+    pub error: Option<String>,
+    pub poll: Option<PollJob>,
+    last_poll: Option<Instant>,
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self::new(DEFAULT_INTERVAL)
+    }
+}
+
+impl Dashboard {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval: interval.clamp(MIN_INTERVAL, MAX_INTERVAL),
+            latest: None,
+            cpu: History::default(),
+            memory: History::default(),
+            rx: History::default(),
+            tx: History::default(),
+            temperature: History::default(),
+            error: None,
+            poll: None,
+            last_poll: None,
+        }
+    }
+
+    /// Forget all samples and stop polling, keeping the interval
+    pub fn reset(&mut self) {
+        *self = Self::new(self.interval);
+    }
+
+    /// Whether a new sample should be requested at `now`
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.poll.is_none()
+            && self
+                .last_poll
+                .is_none_or(|last| now.duration_since(last) >= self.interval)
+    }
+
+    /// Remember that a poll started at `now`
+    pub fn start_poll(&mut self, poll: PollJob, now: Instant) {
+        self.poll = Some(poll);
+        self.last_poll = Some(now);
+    }
+
+    /// Halve (`faster`) or double the poll interval
+    pub fn adjust_interval(&mut self, faster: bool) {
+        let interval = if faster {
+            self.interval / 2
+        } else {
+            self.interval * 2
+        };
+        self.interval = interval.clamp(MIN_INTERVAL, MAX_INTERVAL);
+    }
+
+    /// Add a sample to the histories
+    pub fn record(&mut self, sample: DeviceSample) {
+        if let Some(previous) = &self.latest {
+            if let (Some(before), Some(after)) = (&previous.cpu, &sample.cpu) {
+                self.cpu.push(after.percent_since(before));
+            }
+            let seconds = sample.taken.duration_since(previous.taken).as_secs_f64();
+            if let (Some(before), Some(after)) = (&previous.network, &sample.network) {
+                if seconds > 0.0 {
+                    // Counters restart when an interface goes away
+                    let rate = |before: u64, after: u64| {
+                        (after.saturating_sub(before) as f64 / seconds) as u64
+                    };
+                    self.rx.push(rate(before.rx_bytes, after.rx_bytes));
+                    self.tx.push(rate(before.tx_bytes, after.tx_bytes));
+                }
+            }
+        }
+        if let Some(memory) = &sample.memory {
+            self.memory.push(memory.used_percent());
+        }
+        if let Some(temperature) = sample.battery.as_ref().and_then(|b| b.temperature_c) {
+            self.temperature.push((temperature * 10.0).max(0.0) as u64);
+        }
+        self.error = None;
+        self.latest = Some(sample);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adb::stats::tests::OUTPUT;
+
+    #[test]
+    fn test_record_rates() {
+        let start = Instant::now();
+        let mut dashboard = Dashboard::default();
+        dashboard.record(DeviceSample::parse(OUTPUT, start));
+        assert!(dashboard.cpu.is_empty() && dashboard.rx.is_empty());
+        assert_eq!(dashboard.memory.latest(), Some(53));
+        assert_eq!(dashboard.temperature.latest(), Some(285));
+
+        let later = OUTPUT
+            .replace("cpu  2255 34 2290 22625563", "cpu  2355 34 2290 22625663")
+            .replace("wlan0: 1000000", "wlan0: 3000000");
+        dashboard.record(DeviceSample::parse(&later, start + Duration::from_secs(2)));
+        assert_eq!(dashboard.cpu.latest(), Some(50));
+        assert_eq!(dashboard.rx.latest(), Some(1_000_000));
+        assert_eq!(dashboard.tx.latest(), Some(0));
+    }
+
+    #[test]
+    fn test_history_and_interval() {
+        let mut history = History::default();
+        for value in 0..(HISTORY_LEN as u64 + 10) {
+            history.push(value);
+        }
+        assert_eq!(history.len(), HISTORY_LEN);
+        assert_eq!(
+            history.tail(2),
+            [HISTORY_LEN as u64 + 8, HISTORY_LEN as u64 + 9]
+        );
+
+        let mut dashboard = Dashboard::new(Duration::from_millis(1));
+        assert_eq!(dashboard.interval, MIN_INTERVAL);
+        dashboard.adjust_interval(false);
+        assert_eq!(dashboard.interval, MIN_INTERVAL * 2);
+
+        let now = Instant::now();
+        assert!(dashboard.is_due(now));
+        dashboard.last_poll = Some(now);
+        assert!(!dashboard.is_due(now));
+        assert!(dashboard.is_due(now + dashboard.interval));
+    }
+}
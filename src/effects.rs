@@ -320,6 +320,19 @@ pub fn get_progress_bar(tick_count: u64, width: usize) -> String {
     format!("[{}{}]", filled, empty)
 }
 
+// Determinate progress bar, animated when the total is unknown
+pub fn get_transfer_progress_bar(fraction: Option<f64>, tick_count: u64, width: usize) -> String {
+    match fraction {
+        Some(fraction) => {
+            let progress = ((fraction.clamp(0.0, 1.0) * width as f64) as usize).min(width);
+            let filled = "█".repeat(progress);
+            let empty = "░".repeat(width - progress);
+            format!("[{}{}]", filled, empty)
+        }
+        None => get_progress_bar(tick_count, width),
+    }
+}
+
 // Enhanced selection effect with consistent green color
 pub fn get_selection_color_with_boost(_tick_count: u64, _position: usize, _boost: u64) -> Color {
     // Always return consistent green color, no boost effects for line selection
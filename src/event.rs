@@ -1,3 +1,13 @@
+use crate::dashboard::DashboardEvent;
+use crate::files::{ListingEvent, TransferEvent};
+use crate::hotplug::{DeviceEvent, DeviceListEvent};
+use crate::logcat::LogcatEvent;
+use crate::mirror::MirrorEvent;
+use crate::processes::ProcessEvent;
+use crate::reboot::RebootEvent;
+use crate::record::RecordingEvent;
+use crate::stream::StreamEvent;
+use crate::worker::{ActionEvent, CommandEvent};
 use color_eyre::eyre::OptionExt;
 use futures::{FutureExt, StreamExt};
 use ratatui::crossterm::event::Event as CrosstermEvent;
@@ -24,6 +34,38 @@ pub enum Event {
     ///
     /// Use this event to emit custom events that are specific to your application.
     App(AppEvent),
+    /// Output from a running logcat stream.
+    Logcat(LogcatEvent),
+    /// Progress of a file browser transfer.
+    Transfer(TransferEvent),
+    /// A device directory was listed for the file browser.
+    RemoteListing(ListingEvent),
+    /// A file browser delete or rename on the device finished.
+    FileAction(ActionEvent),
+    /// Progress of a background command.
+    Command(CommandEvent),
+    /// Sample taken for the live dashboard.
+    Dashboard(DashboardEvent),
+    /// Refreshed process list for the process manager.
+    Processes(ProcessEvent),
+    /// A package manager action finished.
+    PackageAction(ActionEvent),
+    /// A process manager action finished.
+    ProcessAction(ActionEvent),
+    /// A reboot step finished.
+    Reboot(RebootEvent),
+    /// A screen recording finished.
+    Recording(RecordingEvent),
+    /// Frames from the terminal mirror.
+    Mirror(MirrorEvent),
+    /// Lifecycle of the stream window.
+    Stream(StreamEvent),
+    /// A device was attached, detached or changed state.
+    Devices(DeviceEvent),
+    /// The device list was fetched for the picker.
+    DeviceList(DeviceListEvent),
+    /// A network device was disconnected from the picker.
+    Disconnect(ActionEvent),
 }
 
 /// Application events.
@@ -85,6 +127,11 @@ impl EventHandler {
             .ok_or_eyre("Failed to receive event")
     }
 
+    /// Get a sender that background tasks can use to feed events into the loop.
+    pub fn sender(&self) -> mpsc::UnboundedSender<Event> {
+        self.sender.clone()
+    }
+
     /// Queue an app event to be sent to the event receiver.
     ///
     /// This is useful for sending events to the event handler which will be processed by the next
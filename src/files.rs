@@ -0,0 +1,473 @@
+//! Two-pane file browser
+//!
+//! The left pane shows the local file system and the right pane the device storage.
+//! Transfers run on a background thread and report progress back to the event loop.
+
+use crate::adb::{format_size, AdbManager, RemoteEntry};
+use crate::event::Event;
+use crate::worker::{PollError, PollJob};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Directory the device pane opens in
+pub const DEFAULT_REMOTE_DIR: &str = "/sdcard";
+
+/// Which pane has keyboard focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Local,
+    Remote,
+}
+
+/// A row in either pane
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub name: String,
+    pub is_dir: bool,
+    /// Device symlinks may point at a file or a directory; opening one finds out
+    pub is_symlink: bool,
+    pub size: u64,
+}
+
+impl From<RemoteEntry> for FileEntry {
+    fn from(entry: RemoteEntry) -> Self {
+        Self {
+            is_dir: entry.is_dir(),
+            is_symlink: entry.is_symlink(),
+            size: entry.size,
+            name: entry.name,
+        }
+    }
+}
+
+/// Device directory listed by a background poll
+#[derive(Debug, Clone)]
+pub struct ListingEvent {
+    pub id: u64,
+    pub result: Result<Vec<RemoteEntry>, PollError>,
+}
+
+/// Prompt currently shown in the browser footer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileInput {
+    /// New name for the selected entry
+    Rename,
+    /// Confirmation before deleting the selected entry
+    ConfirmDelete,
+}
+
+/// Transfer progress reported by the worker thread
+#[derive(Debug, Clone)]
+pub enum TransferEvent {
+    Progress {
+        transferred: u64,
+        total: Option<u64>,
+    },
+    Finished(Result<String, String>),
+}
+
+/// A running transfer
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    pub label: String,
+    pub transferred: u64,
+    pub total: Option<u64>,
+}
+
+impl Transfer {
+    /// Completed fraction between 0.0 and 1.0, if the size is known
+    pub fn fraction(&self) -> Option<f64> {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| (self.transferred as f64 / total as f64).min(1.0))
+    }
+}
+
+/// One side of the browser
+#[derive(Debug, Clone, Default)]
+pub struct PaneState {
+    pub entries: Vec<FileEntry>,
+    pub selected: usize,
+}
+
+impl PaneState {
+    pub fn selected_entry(&self) -> Option<&FileEntry> {
+        self.entries.get(self.selected)
+    }
+
+    pub fn up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Replace entries, keeping the cursor in range
+    pub fn set_entries(&mut self, entries: Vec<FileEntry>) {
+        self.entries = entries;
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+}
+
+/// File browser state
+#[derive(Debug)]
+pub struct FileBrowser {
+    pub local_dir: PathBuf,
+    pub local: PaneState,
+    pub remote_dir: String,
+    pub remote: PaneState,
+    pub focus: Pane,
+    pub transfer: Option<Transfer>,
+    pub input: Option<(FileInput, String)>,
+    pub status: Option<String>,
+    /// Device pane listing in flight
+    pub listing: Option<PollJob>,
+    /// Device delete or rename in flight
+    pub running: Option<(PollJob, FileInput)>,
+}
+
+impl Default for FileBrowser {
+    fn default() -> Self {
+        Self::new(
+            std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            DEFAULT_REMOTE_DIR.to_string(),
+        )
+    }
+}
+
+impl FileBrowser {
+    pub fn new(local_dir: PathBuf, remote_dir: String) -> Self {
+        Self {
+            local_dir,
+            local: PaneState::default(),
+            remote_dir,
+            remote: PaneState::default(),
+            focus: Pane::Remote,
+            transfer: None,
+            input: None,
+            status: None,
+            listing: None,
+            running: None,
+        }
+    }
+
+    /// State of the focused pane
+    pub fn focused(&mut self) -> &mut PaneState {
+        match self.focus {
+            Pane::Local => &mut self.local,
+            Pane::Remote => &mut self.remote,
+        }
+    }
+
+    /// Name of the selected entry in the focused pane
+    pub fn selected_name(&self) -> Option<&str> {
+        let pane = match self.focus {
+            Pane::Local => &self.local,
+            Pane::Remote => &self.remote,
+        };
+        pane.selected_entry().map(|entry| entry.name.as_str())
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Pane::Local => Pane::Remote,
+            Pane::Remote => Pane::Local,
+        };
+    }
+
+    /// Reload the local pane from disk
+    pub fn refresh_local(&mut self) -> io::Result<()> {
+        let entries = list_local_dir(&self.local_dir)?;
+        self.local.set_entries(entries);
+        Ok(())
+    }
+
+    /// Full device path of the selected remote entry
+    pub fn selected_remote_path(&self) -> Option<String> {
+        self.remote
+            .selected_entry()
+            .map(|entry| join_remote(&self.remote_dir, &entry.name))
+    }
+
+    /// Full local path of the selected local entry
+    pub fn selected_local_path(&self) -> Option<PathBuf> {
+        self.local
+            .selected_entry()
+            .map(|entry| self.local_dir.join(&entry.name))
+    }
+}
+
+/// List a local directory, directories first
+pub fn list_local_dir(dir: &Path) -> io::Result<Vec<FileEntry>> {
+    let mut entries: Vec<FileEntry> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let metadata = entry.metadata().ok();
+            FileEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                is_dir: metadata.as_ref().is_some_and(|m| m.is_dir()),
+                is_symlink: false,
+                size: metadata.map(|m| m.len()).unwrap_or(0),
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(entries)
+}
+
+/// Join a device directory and a file name
+pub fn join_remote(dir: &str, name: &str) -> String {
+    if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// Parent of a device directory, staying at `/`
+pub fn remote_parent(dir: &str) -> String {
+    match dir.trim_end_matches('/').rsplit_once('/') {
+        Some(("", _)) | None => "/".to_string(),
+        Some((parent, _)) => parent.to_string(),
+    }
+}
+
+/// Reports bytes moved through a reader or writer
+struct ProgressReporter {
+    sender: UnboundedSender<Event>,
+    transferred: u64,
+    total: Option<u64>,
+    last_reported: u64,
+}
+
+impl ProgressReporter {
+    /// Report roughly every 64 KiB so large transfers don't flood the event loop
+    fn advance(&mut self, bytes: usize) {
+        self.transferred += bytes as u64;
+        if self.transferred - self.last_reported >= 64 * 1024 {
+            self.last_reported = self.transferred;
+            let _ = self.sender.send(Event::Transfer(TransferEvent::Progress {
+                transferred: self.transferred,
+                total: self.total,
+            }));
+        }
+    }
+}
+
+struct ProgressWriter<W: Write> {
+    inner: W,
+    progress: ProgressReporter,
+}
+
+impl<W: Write> Write for ProgressWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.progress.advance(written);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct ProgressReader<R: Read> {
+    inner: R,
+    progress: ProgressReporter,
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.progress.advance(read);
+        Ok(read)
+    }
+}
+
+/// Pull `remote_path` into `local_path` on a background thread
+pub fn spawn_pull(
+    mut manager: AdbManager,
+    remote_path: String,
+    local_path: PathBuf,
+    sender: UnboundedSender<Event>,
+) {
+    thread::spawn(move || {
+        let result = (|| -> Result<String, String> {
+            let total = manager.file_size(&remote_path).ok();
+            let file = File::options()
+                .write(true)
+                .create_new(true)
+                .open(&local_path)
+                .map_err(|e| match e.kind() {
+                    io::ErrorKind::AlreadyExists => {
+                        format!("{} already exists", local_path.display())
+                    }
+                    _ => e.to_string(),
+                })?;
+            let mut writer = ProgressWriter {
+                inner: file,
+                progress: ProgressReporter {
+                    sender: sender.clone(),
+                    transferred: 0,
+                    total,
+                    last_reported: 0,
+                },
+            };
+            if let Err(e) = manager.pull(&remote_path, &mut writer) {
+                // Don't leave a truncated copy behind
+                drop(writer);
+                let _ = fs::remove_file(&local_path);
+                return Err(e.to_string());
+            }
+            Ok(format!(
+                "Pulled {} → {} ({})",
+                remote_path,
+                local_path.display(),
+                format_size(writer.progress.transferred)
+            ))
+        })();
+        let _ = sender.send(Event::Transfer(TransferEvent::Finished(result)));
+    });
+}
+
+/// Push `local_path` to `remote_path` on a background thread
+pub fn spawn_push(
+    mut manager: AdbManager,
+    local_path: PathBuf,
+    remote_path: String,
+    sender: UnboundedSender<Event>,
+) {
+    thread::spawn(move || {
+        let result = (|| -> Result<String, String> {
+            let file = File::open(&local_path).map_err(|e| e.to_string())?;
+            let total = file.metadata().ok().map(|m| m.len());
+            let mut reader = ProgressReader {
+                inner: file,
+                progress: ProgressReporter {
+                    sender: sender.clone(),
+                    transferred: 0,
+                    total,
+                    last_reported: 0,
+                },
+            };
+            manager
+                .push(&mut reader, &remote_path)
+                .map_err(|e| e.to_string())?;
+            Ok(format!(
+                "Pushed {} → {} ({})",
+                local_path.display(),
+                remote_path,
+                format_size(reader.progress.transferred)
+            ))
+        })();
+        let _ = sender.send(Event::Transfer(TransferEvent::Finished(result)));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_paths() {
+        assert_eq!(join_remote("/sdcard", "DCIM"), "/sdcard/DCIM");
+        assert_eq!(join_remote("/", "sdcard"), "/sdcard");
+        assert_eq!(remote_parent("/sdcard/DCIM/Camera"), "/sdcard/DCIM");
+        assert_eq!(remote_parent("/sdcard"), "/");
+        assert_eq!(remote_parent("/"), "/");
+    }
+
+    #[test]
+    fn test_pane_navigation() {
+        let mut pane = PaneState::default();
+        pane.set_entries(vec![
+            FileEntry {
+                name: "a".to_string(),
+                is_dir: true,
+                is_symlink: false,
+                size: 0,
+            },
+            FileEntry {
+                name: "b".to_string(),
+                is_dir: false,
+                is_symlink: false,
+                size: 1,
+            },
+        ]);
+        pane.up();
+        assert_eq!(pane.selected, 0);
+        pane.down();
+        pane.down();
+        assert_eq!(pane.selected, 1);
+
+        pane.set_entries(Vec::new());
+        assert_eq!(pane.selected, 0);
+        assert!(pane.selected_entry().is_none());
+    }
+
+    #[test]
+    fn test_pull_keeps_existing_files() {
+        use crate::adb::MockBackend;
+
+        let dir = std::env::temp_dir().join(format!("droidtui-pull-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("readme.txt");
+        fs::write(&existing, "local copy").unwrap();
+
+        let mut manager = AdbManager::with_backend(Box::new(MockBackend::demo()));
+        manager.select_device("emulator-5554".to_string());
+        let pull = |remote: &str, local: PathBuf| {
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+            spawn_pull(
+                manager.for_worker(Default::default()),
+                remote.to_string(),
+                local,
+                sender,
+            );
+            loop {
+                match receiver.blocking_recv() {
+                    Some(Event::Transfer(TransferEvent::Finished(result))) => break result,
+                    Some(_) => {}
+                    None => panic!("pull dropped the sender"),
+                }
+            }
+        };
+
+        let error = pull("/sdcard/Download/readme.txt", existing.clone()).unwrap_err();
+        assert!(error.contains("already exists"), "{}", error);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "local copy");
+
+        // A failed pull leaves nothing behind
+        let missing = dir.join("missing.txt");
+        assert!(pull("/sdcard/missing.txt", missing.clone()).is_err());
+        assert!(!missing.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_transfer_fraction() {
+        let transfer = Transfer {
+            label: "test".to_string(),
+            transferred: 50,
+            total: Some(200),
+        };
+        assert_eq!(transfer.fraction(), Some(0.25));
+
+        let unknown = Transfer {
+            total: None,
+            ..transfer
+        };
+        assert_eq!(unknown.fraction(), None);
+    }
+}
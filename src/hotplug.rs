@@ -0,0 +1,221 @@
+//! Live device list
+//!
+//! A background thread follows the server's `track-devices` feed and turns each new
+//! list into connected, disconnected and state-changed events. When the server goes
+//! away the tracker keeps retrying; the first list after it comes back is compared
+//! with the last one seen, so only real changes are reported.
+
+use crate::adb::{AdbError, AdbManager, CancelToken, DeviceInfo};
+use crate::event::Event;
+use crate::worker::PollError;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Wait between attempts to reach a server that went away
+const RETRY_AFTER: Duration = Duration::from_secs(2);
+
+/// How often a retry wait checks whether the tracker was stopped
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+/// Device list fetched for the picker by a background poll
+#[derive(Debug, Clone)]
+pub struct DeviceListEvent {
+    pub id: u64,
+    pub result: Result<Vec<DeviceInfo>, PollError>,
+}
+
+/// Change in the devices attached to the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceEvent {
+    /// Full list, sent whenever the tracker (re)connects
+    Listed(Vec<DeviceInfo>),
+    Connected(DeviceInfo),
+    Disconnected(String),
+    StateChanged {
+        device: DeviceInfo,
+        previous: String,
+    },
+    /// The server stopped answering; the tracker keeps retrying
+    Lost(String),
+}
+
+/// Whether the device list is being kept up to date
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TrackingState {
+    #[default]
+    Starting,
+    Live,
+    /// Why the server can't be reached
+    Lost(String),
+}
+
+/// Events turning the `old` device list into `new`
+pub fn changes(old: &[DeviceInfo], new: &[DeviceInfo]) -> Vec<DeviceEvent> {
+    let mut events: Vec<DeviceEvent> = old
+        .iter()
+        .filter(|device| !new.iter().any(|d| d.serial == device.serial))
+        .map(|device| DeviceEvent::Disconnected(device.serial.clone()))
+        .collect();
+    for device in new {
+        match old.iter().find(|d| d.serial == device.serial) {
+            None => events.push(DeviceEvent::Connected(device.clone())),
+            Some(before) if before.state != device.state => {
+                events.push(DeviceEvent::StateChanged {
+                    device: device.clone(),
+                    previous: before.state.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    events
+}
+
+/// Handle to the tracker thread; dropping it stops tracking
+#[derive(Debug)]
+pub struct DeviceTracker {
+    cancel: CancelToken,
+}
+
+impl DeviceTracker {
+    /// Close the `track-devices` connection, ending the tracker thread
+    pub fn stop(&self) {
+        self.cancel.cancel();
+    }
+}
+
+impl Drop for DeviceTracker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Follow the devices attached to `manager`'s server, reporting changes through `sender`
+pub fn spawn_tracker(manager: &AdbManager, sender: UnboundedSender<Event>) -> DeviceTracker {
+    let cancel = CancelToken::default();
+    let mut manager = manager.for_worker(cancel.clone());
+    let stopped = cancel.clone();
+
+    thread::spawn(move || {
+        let mut known: Option<Vec<DeviceInfo>> = None;
+        let mut lost = false;
+        loop {
+            let mut connected = false;
+            let result = manager.track_devices(&mut |devices| {
+                let mut events = known
+                    .as_deref()
+                    .map(|old| changes(old, &devices))
+                    .unwrap_or_default();
+                if !connected {
+                    connected = true;
+                    events.push(DeviceEvent::Listed(devices.clone()));
+                }
+                for event in events {
+                    let _ = sender.send(Event::Devices(event));
+                }
+                known = Some(devices);
+            });
+            lost &= !connected;
+
+            let error = match result {
+                Err(AdbError::Cancelled) => return,
+                Err(e) => e.to_string(),
+                Ok(()) => "ADB server closed the device list".to_string(),
+            };
+            // Report an outage once, not on every retry
+            if !lost {
+                lost = true;
+                if sender
+                    .send(Event::Devices(DeviceEvent::Lost(error)))
+                    .is_err()
+                {
+                    return;
+                }
+            }
+
+            let retry = Instant::now() + RETRY_AFTER;
+            while Instant::now() < retry {
+                if stopped.is_cancelled() {
+                    return;
+                }
+                thread::sleep(CANCEL_POLL);
+            }
+        }
+    });
+
+    DeviceTracker { cancel }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adb::MockBackend;
+    use tokio::sync::mpsc::UnboundedReceiver;
+
+    fn device(serial: &str, state: &str) -> DeviceInfo {
+        DeviceInfo {
+            serial: serial.to_string(),
+            state: state.to_string(),
+            model: None,
+            device: None,
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let old = [
+            device("emulator-5554", "device"),
+            device("0123456789ABCDEF", "unauthorized"),
+        ];
+        let new = [
+            device("0123456789ABCDEF", "device"),
+            device("192.168.1.20:5555", "device"),
+        ];
+        assert_eq!(
+            changes(&old, &new),
+            [
+                DeviceEvent::Disconnected("emulator-5554".to_string()),
+                DeviceEvent::StateChanged {
+                    device: device("0123456789ABCDEF", "device"),
+                    previous: "unauthorized".to_string(),
+                },
+                DeviceEvent::Connected(device("192.168.1.20:5555", "device")),
+            ]
+        );
+        assert!(changes(&new, &new).is_empty());
+    }
+
+    async fn next(receiver: &mut UnboundedReceiver<Event>) -> DeviceEvent {
+        match receiver.recv().await {
+            Some(Event::Devices(event)) => event,
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tracker_events() {
+        let mock = MockBackend::demo();
+        let manager = AdbManager::with_backend(Box::new(mock.clone()));
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let tracker = spawn_tracker(&manager, sender);
+
+        let DeviceEvent::Listed(devices) = next(&mut receiver).await else {
+            panic!("expected the initial list");
+        };
+        assert_eq!(devices[0].serial, "emulator-5554");
+
+        mock.set_device_state("emulator-5554", "offline");
+        assert!(matches!(
+            next(&mut receiver).await,
+            DeviceEvent::StateChanged { device, previous }
+                if device.state == "offline" && previous == "device"
+        ));
+        mock.unplug("emulator-5554");
+        assert_eq!(
+            next(&mut receiver).await,
+            DeviceEvent::Disconnected("emulator-5554".to_string())
+        );
+        tracker.stop();
+    }
+}
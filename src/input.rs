@@ -0,0 +1,299 @@
+//! Forwarding mouse and keyboard input from the stream window to the device
+//!
+//! Clicks and drags become `input tap`/`input swipe` in device coordinates and keys
+//! become `input keyevent`/`input text`. `input` starts a new process on the device
+//! for every call, so commands run on their own thread to keep the window drawing.
+
+use crate::adb::{AdbManager, CancelToken, DeviceInput, Keycode};
+use minifb::{InputCallback, Key, KeyRepeat, MouseButton, MouseMode, Window};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A press shorter than this that stays put is a tap
+const LONG_PRESS: Duration = Duration::from_millis(500);
+
+/// Movement, in device pixels, still counted as a tap
+const TAP_SLOP: u32 = 12;
+
+/// Key code for a key pressed in the window, `ctrl` telling whether Ctrl is held
+///
+/// Ctrl+B/H/S/M/P are Back, Home, Recents, Menu and Power and Ctrl+Up/Down the
+/// volume; plain editing and arrow keys map to their Android counterparts.
+pub fn key_to_keycode(key: Key, ctrl: bool) -> Option<Keycode> {
+    let keycode = match (key, ctrl) {
+        (Key::B, true) => Keycode::Back,
+        (Key::H, true) => Keycode::Home,
+        (Key::S, true) => Keycode::AppSwitch,
+        (Key::M, true) => Keycode::Menu,
+        (Key::P, true) => Keycode::Power,
+        (Key::Up, true) => Keycode::VolumeUp,
+        (Key::Down, true) => Keycode::VolumeDown,
+        (Key::Enter | Key::NumPadEnter, false) => Keycode::Enter,
+        (Key::Backspace, false) => Keycode::Del,
+        (Key::Delete, false) => Keycode::ForwardDel,
+        (Key::Tab, false) => Keycode::Tab,
+        (Key::Up, false) => Keycode::DpadUp,
+        (Key::Down, false) => Keycode::DpadDown,
+        (Key::Left, false) => Keycode::DpadLeft,
+        (Key::Right, false) => Keycode::DpadRight,
+        (Key::Home, false) => Keycode::MoveHome,
+        (Key::End, false) => Keycode::MoveEnd,
+        (Key::PageUp, false) => Keycode::PageUp,
+        (Key::PageDown, false) => Keycode::PageDown,
+        _ => return None,
+    };
+    Some(keycode)
+}
+
+/// Device coordinates of a point in the window, or `None` outside the picture
+///
+/// The window shows `frame` sized video scaled to fit with its aspect ratio kept and
+/// centered, like minifb's `AspectRatioStretch`; the video covers the whole `device`
+/// screen.
+pub fn window_to_device(
+    pos: (f32, f32),
+    window: (usize, usize),
+    frame: (usize, usize),
+    device: (usize, usize),
+) -> Option<(u32, u32)> {
+    let (frame_w, frame_h) = (frame.0 as f32, frame.1 as f32);
+    if frame_w == 0.0 || frame_h == 0.0 {
+        return None;
+    }
+    let scale = (window.0 as f32 / frame_w).min(window.1 as f32 / frame_h);
+    if scale <= 0.0 {
+        return None;
+    }
+    let offset_x = (window.0 as f32 - frame_w * scale) / 2.0;
+    let offset_y = (window.1 as f32 - frame_h * scale) / 2.0;
+    let x = (pos.0 - offset_x) / scale;
+    let y = (pos.1 - offset_y) / scale;
+    if x < 0.0 || y < 0.0 || x >= frame_w || y >= frame_h {
+        return None;
+    }
+    Some((
+        (x * device.0 as f32 / frame_w) as u32,
+        (y * device.1 as f32 / frame_h) as u32,
+    ))
+}
+
+/// Input for a press from `start` to `end` held for `held`
+pub fn gesture(start: (u32, u32), end: (u32, u32), held: Duration) -> DeviceInput {
+    let moved = start.0.abs_diff(end.0).max(start.1.abs_diff(end.1));
+    if moved <= TAP_SLOP && held < LONG_PRESS {
+        DeviceInput::Tap {
+            x: start.0,
+            y: start.1,
+        }
+    } else {
+        DeviceInput::Swipe {
+            from: start,
+            to: if moved <= TAP_SLOP { start } else { end },
+            duration: held.max(Duration::from_millis(50)),
+        }
+    }
+}
+
+/// Runs input commands in order on a worker thread
+///
+/// The thread ends when the forwarder is dropped.
+pub struct InputForwarder {
+    tx: Sender<DeviceInput>,
+}
+
+impl InputForwarder {
+    /// Forward input through `run`; failures are dropped, input is best effort
+    pub fn spawn(mut run: impl FnMut(&DeviceInput) -> Result<(), String> + Send + 'static) -> Self {
+        let (tx, rx) = channel::<DeviceInput>();
+        thread::spawn(move || {
+            for input in rx {
+                let _ = run(&input);
+            }
+        });
+        Self { tx }
+    }
+
+    pub fn send(&self, input: DeviceInput) {
+        let _ = self.tx.send(input);
+    }
+}
+
+/// Text typed into the window and whether Ctrl is held, fed by minifb
+#[derive(Default)]
+struct Typed {
+    text: String,
+    ctrl: bool,
+}
+
+struct TypedCallback(Arc<Mutex<Typed>>);
+
+impl InputCallback for TypedCallback {
+    fn add_char(&mut self, uni_char: u32) {
+        let mut typed = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        // `input text` only handles printable ASCII; the rest goes through key codes
+        match char::from_u32(uni_char) {
+            Some(c) if !typed.ctrl && (c.is_ascii_graphic() || c == ' ') => typed.text.push(c),
+            _ => {}
+        }
+    }
+
+    fn set_key_state(&mut self, key: Key, state: bool) {
+        if matches!(key, Key::LeftCtrl | Key::RightCtrl) {
+            self.0.lock().unwrap_or_else(|e| e.into_inner()).ctrl = state;
+        }
+    }
+}
+
+/// Turns mouse and keyboard activity in a window into device input
+pub struct WindowInput {
+    /// Size of the video frames shown
+    frame: (usize, usize),
+    /// Size of the device screen
+    device: (usize, usize),
+    forwarder: InputForwarder,
+    typed: Arc<Mutex<Typed>>,
+    /// Where and when the left button went down
+    press: Option<((u32, u32), Instant)>,
+    /// Last point under the pointer while pressed
+    last: (u32, u32),
+    right_down: bool,
+    middle_down: bool,
+}
+
+impl WindowInput {
+    pub fn new(frame: (usize, usize), device: (usize, usize), forwarder: InputForwarder) -> Self {
+        Self {
+            frame,
+            device,
+            forwarder,
+            typed: Arc::default(),
+            press: None,
+            last: (0, 0),
+            right_down: false,
+            middle_down: false,
+        }
+    }
+
+    /// Input for a window showing `frame` sized video of the manager's device
+    ///
+    /// Commands run on a manager of their own, so they don't wait for the stream.
+    pub fn for_device(manager: &mut AdbManager, frame: (usize, usize)) -> Self {
+        let device = manager.screen_size().unwrap_or(frame);
+        let mut input_manager = manager.for_worker(CancelToken::default());
+        let forwarder = InputForwarder::spawn(move |input| {
+            input_manager.send_input(input).map_err(|e| e.to_string())
+        });
+        Self::new(frame, device, forwarder)
+    }
+
+    /// Start collecting typed text from `window`
+    pub fn attach(&self, window: &mut Window) {
+        window.set_input_callback(Box::new(TypedCallback(self.typed.clone())));
+    }
+
+    /// Forward what happened in `window` since the last call
+    ///
+    /// Left click taps, left drag swipes and a long press holds; right click is Back
+    /// and middle click Home.
+    pub fn poll(&mut self, window: &Window) {
+        let text = {
+            let mut typed = self.typed.lock().unwrap_or_else(|e| e.into_inner());
+            std::mem::take(&mut typed.text)
+        };
+        if !text.is_empty() {
+            self.forwarder.send(DeviceInput::Text(text));
+        }
+
+        let ctrl = window.is_key_down(Key::LeftCtrl) || window.is_key_down(Key::RightCtrl);
+        for key in window.get_keys_pressed(KeyRepeat::Yes) {
+            if let Some(keycode) = key_to_keycode(key, ctrl) {
+                self.forwarder.send(DeviceInput::Key(keycode));
+            }
+        }
+
+        let pos = window
+            .get_unscaled_mouse_pos(MouseMode::Pass)
+            .and_then(|pos| window_to_device(pos, window.get_size(), self.frame, self.device));
+        let left = window.get_mouse_down(MouseButton::Left);
+        match (left, self.press, pos) {
+            (true, None, Some(pos)) => {
+                self.press = Some((pos, Instant::now()));
+                self.last = pos;
+            }
+            (true, Some(_), Some(pos)) => self.last = pos,
+            (false, Some((start, at)), _) => {
+                self.press = None;
+                self.forwarder.send(gesture(start, self.last, at.elapsed()));
+            }
+            _ => {}
+        }
+
+        let right = window.get_mouse_down(MouseButton::Right);
+        if right && !self.right_down && pos.is_some() {
+            self.forwarder.send(DeviceInput::Key(Keycode::Back));
+        }
+        self.right_down = right;
+
+        let middle = window.get_mouse_down(MouseButton::Middle);
+        if middle && !self.middle_down && pos.is_some() {
+            self.forwarder.send(DeviceInput::Key(Keycode::Home));
+        }
+        self.middle_down = middle;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_to_device() {
+        // 540x960 video letterboxed in a 1000x960 window, device at 1080x1920
+        let map = |pos| window_to_device(pos, (1000, 960), (540, 960), (1080, 1920));
+        assert_eq!(map((230.0, 0.0)), Some((0, 0)));
+        assert_eq!(map((500.0, 480.0)), Some((540, 960)));
+        assert_eq!(map((100.0, 480.0)), None);
+        assert_eq!(map((800.0, 480.0)), None);
+
+        // Window at half size
+        let map = |pos| window_to_device(pos, (540, 960), (1080, 1920), (1080, 1920));
+        assert_eq!(map((270.0, 480.0)), Some((540, 960)));
+    }
+
+    #[test]
+    fn test_gesture() {
+        let quick = Duration::from_millis(100);
+        assert_eq!(
+            gesture((100, 200), (105, 203), quick),
+            DeviceInput::Tap { x: 100, y: 200 }
+        );
+        assert_eq!(
+            gesture((100, 200), (100, 800), Duration::from_millis(300)),
+            DeviceInput::Swipe {
+                from: (100, 200),
+                to: (100, 800),
+                duration: Duration::from_millis(300),
+            }
+        );
+        // A long press is a swipe that stays put
+        assert_eq!(
+            gesture((100, 200), (104, 200), Duration::from_millis(900)),
+            DeviceInput::Swipe {
+                from: (100, 200),
+                to: (100, 200),
+                duration: Duration::from_millis(900),
+            }
+        );
+    }
+
+    #[test]
+    fn test_key_to_keycode() {
+        assert_eq!(key_to_keycode(Key::H, true), Some(Keycode::Home));
+        assert_eq!(key_to_keycode(Key::H, false), None);
+        assert_eq!(key_to_keycode(Key::Up, true), Some(Keycode::VolumeUp));
+        assert_eq!(key_to_keycode(Key::Up, false), Some(Keycode::DpadUp));
+        assert_eq!(key_to_keycode(Key::Backspace, false), Some(Keycode::Del));
+    }
+}
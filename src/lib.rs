@@ -5,11 +5,30 @@
 
 pub mod adb;
 pub mod app;
+pub mod cli;
+pub mod config;
+pub mod confirm;
+pub mod dashboard;
 pub mod effects;
 pub mod event;
+pub mod files;
+pub mod hotplug;
+pub mod input;
+pub mod logcat;
 pub mod menu;
 pub mod message;
+pub mod mirror;
 pub mod model;
+pub mod packages;
+pub mod preview;
+pub mod processes;
+pub mod reboot;
+pub mod record;
+pub mod screenshot;
 pub mod stream;
+pub mod template;
+pub mod toast;
 pub mod update;
 pub mod view;
+pub mod wireless;
+pub mod worker;
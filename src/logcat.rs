@@ -0,0 +1,562 @@
+//! Live logcat streaming
+//!
+//! Parses `logcat -v threadtime` output into structured records, keeps a rolling
+//! buffer of them and applies level/tag/pid/regex filters at display time so filters
+//! can change without restarting the stream.
+
+use crate::adb::{AdbError, AdbManager, CancelToken};
+use crate::event::Event;
+use crate::worker::next_job_id;
+use ratatui::style::Color;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::thread;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Maximum number of records kept in memory
+const MAX_RECORDS: usize = 10_000;
+
+/// Logcat priority levels, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Verbose,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    /// Parse the single letter priority used by logcat
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'V' => Some(LogLevel::Verbose),
+            'D' => Some(LogLevel::Debug),
+            'I' => Some(LogLevel::Info),
+            'W' => Some(LogLevel::Warn),
+            'E' => Some(LogLevel::Error),
+            'F' | 'A' => Some(LogLevel::Fatal),
+            _ => None,
+        }
+    }
+
+    /// Single letter used in logcat output and filter specs
+    pub fn as_char(&self) -> char {
+        match self {
+            LogLevel::Verbose => 'V',
+            LogLevel::Debug => 'D',
+            LogLevel::Info => 'I',
+            LogLevel::Warn => 'W',
+            LogLevel::Error => 'E',
+            LogLevel::Fatal => 'F',
+        }
+    }
+
+    /// Next level when cycling the minimum level filter
+    pub fn next(&self) -> Self {
+        match self {
+            LogLevel::Verbose => LogLevel::Debug,
+            LogLevel::Debug => LogLevel::Info,
+            LogLevel::Info => LogLevel::Warn,
+            LogLevel::Warn => LogLevel::Error,
+            LogLevel::Error => LogLevel::Fatal,
+            LogLevel::Fatal => LogLevel::Verbose,
+        }
+    }
+
+    /// Display color for records of this level
+    pub fn color(&self) -> Color {
+        match self {
+            LogLevel::Verbose => Color::DarkGray,
+            LogLevel::Debug => Color::Cyan,
+            LogLevel::Info => Color::Green,
+            LogLevel::Warn => Color::Yellow,
+            LogLevel::Error => Color::Red,
+            LogLevel::Fatal => Color::Magenta,
+        }
+    }
+}
+
+/// A single parsed logcat line
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    /// Date and time as printed by logcat (`MM-DD HH:MM:SS.mmm`)
+    pub time: String,
+    pub pid: u32,
+    pub tid: u32,
+    pub level: LogLevel,
+    pub tag: String,
+    pub message: String,
+}
+
+impl LogRecord {
+    /// Parse a `logcat -v threadtime` line
+    ///
+    /// Format: `MM-DD HH:MM:SS.mmm  PID  TID L TAG     : message`
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut rest = line.trim_end_matches(['\r', '\n']);
+        let mut fields = [""; 5];
+        for field in fields.iter_mut() {
+            rest = rest.trim_start();
+            let end = rest.find(char::is_whitespace)?;
+            *field = &rest[..end];
+            rest = &rest[end..];
+        }
+
+        let [date, time, pid, tid, level] = fields;
+        let mut level_chars = level.chars();
+        let level = match (level_chars.next(), level_chars.next()) {
+            (Some(c), None) => LogLevel::from_char(c)?,
+            _ => return None,
+        };
+
+        let (tag, message) = rest.trim_start().split_once(':')?;
+
+        Some(LogRecord {
+            time: format!("{} {}", date, time),
+            pid: pid.parse().ok()?,
+            tid: tid.parse().ok()?,
+            level,
+            tag: tag.trim().to_string(),
+            message: message.strip_prefix(' ').unwrap_or(message).to_string(),
+        })
+    }
+}
+
+/// Which filter field is being edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogcatField {
+    Tag,
+    Package,
+    Regex,
+}
+
+impl LogcatField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogcatField::Tag => "Tag",
+            LogcatField::Package => "Package/PID",
+            LogcatField::Regex => "Regex",
+        }
+    }
+}
+
+/// Process filter, either typed as a PID or resolved from a package name
+#[derive(Debug, Clone)]
+pub struct PidFilter {
+    /// What the user typed
+    pub label: String,
+    /// Matching process ids
+    pub pids: Vec<u32>,
+}
+
+/// Display-time filters for the logcat view
+#[derive(Debug, Clone)]
+pub struct LogcatFilter {
+    pub min_level: LogLevel,
+    pub tag: Option<String>,
+    pub pid: Option<PidFilter>,
+    pub regex: Option<Regex>,
+}
+
+impl Default for LogcatFilter {
+    fn default() -> Self {
+        Self {
+            min_level: LogLevel::Verbose,
+            tag: None,
+            pid: None,
+            regex: None,
+        }
+    }
+}
+
+impl LogcatFilter {
+    /// Check whether a record passes every active filter
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        if record.level < self.min_level {
+            return false;
+        }
+        if let Some(tag) = &self.tag {
+            if !record.tag.to_lowercase().contains(&tag.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(pid) = &self.pid {
+            if !pid.pids.contains(&record.pid) {
+                return false;
+            }
+        }
+        if let Some(regex) = &self.regex {
+            if !regex.is_match(&record.message) && !regex.is_match(&record.tag) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Short description of the active filters for the header
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("Level ≥ {}", self.min_level.as_char())];
+        if let Some(tag) = &self.tag {
+            parts.push(format!("Tag: {}", tag));
+        }
+        if let Some(pid) = &self.pid {
+            parts.push(format!("PID: {}", pid.label));
+        }
+        if let Some(regex) = &self.regex {
+            parts.push(format!("Regex: /{}/", regex.as_str()));
+        }
+        parts.join(" | ")
+    }
+}
+
+/// Stream lifecycle events sent from the reader thread, tagged with its handle's id
+#[derive(Debug, Clone)]
+pub enum LogcatEvent {
+    /// A batch of raw lines read from the device
+    Lines { id: u64, lines: Vec<String> },
+    /// The stream ended, with an error message if it failed
+    Ended { id: u64, error: Option<String> },
+}
+
+/// State of the logcat screen
+#[derive(Debug, Default)]
+pub struct LogcatView {
+    /// Rolling buffer of parsed records
+    records: VecDeque<LogRecord>,
+    /// Active filters
+    filter: LogcatFilter,
+    /// Positions of the records passing `filter`, counted from the first record pushed
+    matching: VecDeque<usize>,
+    /// Records dropped from the front of `records`
+    evicted: usize,
+    /// Lines scrolled up from the tail; 0 follows new output
+    pub scroll_offset: usize,
+    /// Field being edited and its pending text
+    pub input: Option<(LogcatField, String)>,
+    /// Status or error message shown in the footer
+    pub status: Option<String>,
+    /// Whether the reader thread is still running
+    pub streaming: bool,
+}
+
+impl LogcatView {
+    pub fn new() -> Self {
+        Self {
+            streaming: true,
+            ..Self::default()
+        }
+    }
+
+    /// Append raw lines, keeping the view steady while scrolled back
+    pub fn push_lines(&mut self, lines: Vec<String>) {
+        for line in lines {
+            let Some(record) = LogRecord::parse(&line) else {
+                continue;
+            };
+            if self.filter.matches(&record) {
+                self.matching.push_back(self.evicted + self.records.len());
+                if self.scroll_offset > 0 {
+                    self.scroll_offset += 1;
+                }
+            }
+            self.records.push_back(record);
+        }
+
+        while self.records.len() > MAX_RECORDS {
+            self.records.pop_front();
+            if self.matching.front() == Some(&self.evicted) {
+                self.matching.pop_front();
+            }
+            self.evicted += 1;
+        }
+        self.scroll_offset = self.scroll_offset.min(self.max_scroll());
+    }
+
+    pub fn filter(&self) -> &LogcatFilter {
+        &self.filter
+    }
+
+    /// Replace the filters and jump back to the newest matching record
+    pub fn set_filter(&mut self, filter: LogcatFilter) {
+        self.filter = filter;
+        self.matching = self
+            .records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| self.filter.matches(record))
+            .map(|(index, _)| self.evicted + index)
+            .collect();
+        self.scroll_offset = 0;
+    }
+
+    /// Records held, matching or not
+    pub fn record_count(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Records passing the current filters
+    pub fn visible_count(&self) -> usize {
+        self.matching.len()
+    }
+
+    /// The last `height` matching records above the scroll position, oldest first
+    pub fn window(&self, height: usize) -> impl Iterator<Item = &LogRecord> {
+        let end = self.matching.len().saturating_sub(self.scroll_offset);
+        let start = end.saturating_sub(height);
+        self.matching
+            .range(start..end)
+            .map(|position| &self.records[position - self.evicted])
+    }
+
+    /// Scroll by `delta` lines; positive values move towards older records
+    pub fn scroll(&mut self, delta: isize) {
+        self.scroll_offset = self
+            .scroll_offset
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    /// Whether the view is pinned to the newest records
+    pub fn is_following(&self) -> bool {
+        self.scroll_offset == 0
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.matching.len().saturating_sub(1)
+    }
+}
+
+/// Handle to the background reader thread
+#[derive(Debug)]
+pub struct LogcatHandle {
+    pub id: u64,
+    cancel: CancelToken,
+}
+
+impl LogcatHandle {
+    /// Close the logcat connection, ending the reader thread
+    pub fn stop(&self) {
+        self.cancel.cancel();
+    }
+}
+
+impl Drop for LogcatHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Writer that forwards complete lines to the event loop
+struct LineForwarder {
+    id: u64,
+    sender: UnboundedSender<Event>,
+    pending: Vec<u8>,
+}
+
+impl Write for LineForwarder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        let Some(last_newline) = self.pending.iter().rposition(|&b| b == b'\n') else {
+            return Ok(buf.len());
+        };
+
+        let complete: Vec<u8> = self.pending.drain(..=last_newline).collect();
+        let lines = String::from_utf8_lossy(&complete)
+            .lines()
+            .map(|line| line.to_string())
+            .collect();
+
+        self.sender
+            .send(Event::Logcat(LogcatEvent::Lines { id: self.id, lines }))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "event loop closed"))?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Start streaming `logcat -v threadtime` from `manager`'s device on a background thread
+pub fn spawn_logcat(manager: &AdbManager, sender: UnboundedSender<Event>) -> LogcatHandle {
+    let id = next_job_id();
+    let cancel = CancelToken::default();
+    let mut manager = manager.for_worker(cancel.clone());
+    let mut forwarder = LineForwarder {
+        id,
+        sender: sender.clone(),
+        pending: Vec::new(),
+    };
+
+    thread::spawn(move || {
+        let error = match manager.shell_stream("logcat -v threadtime", &mut forwarder) {
+            Ok(()) | Err(AdbError::Cancelled) => None,
+            Err(e) => Some(e.to_string()),
+        };
+        let _ = sender.send(Event::Logcat(LogcatEvent::Ended { id, error }));
+    });
+
+    LogcatHandle { id, cancel }
+}
+
+/// Parse `pidof` output into process ids
+pub fn parse_pids(output: &str) -> Vec<u32> {
+    output
+        .split_whitespace()
+        .filter_map(|pid| pid.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str =
+        "10-17 09:14:02.123  1234  1301 I ActivityManager: Start proc 4242:com.example/u0a123";
+
+    #[test]
+    fn test_parse_threadtime() {
+        let record = LogRecord::parse(SAMPLE).unwrap();
+        assert_eq!(record.time, "10-17 09:14:02.123");
+        assert_eq!(record.pid, 1234);
+        assert_eq!(record.tid, 1301);
+        assert_eq!(record.level, LogLevel::Info);
+        assert_eq!(record.tag, "ActivityManager");
+        assert_eq!(record.message, "Start proc 4242:com.example/u0a123");
+    }
+
+    #[test]
+    fn test_parse_padded_tag() {
+        let line = "10-17 09:14:02.500   880   880 E chatty  : uid=1000 expire 3 lines";
+        let record = LogRecord::parse(line).unwrap();
+        assert_eq!(record.tag, "chatty");
+        assert_eq!(record.level, LogLevel::Error);
+        assert_eq!(record.message, "uid=1000 expire 3 lines");
+    }
+
+    #[test]
+    fn test_parse_rejects_other_lines() {
+        assert!(LogRecord::parse("--------- beginning of main").is_none());
+        assert!(LogRecord::parse("").is_none());
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let record = LogRecord::parse(SAMPLE).unwrap();
+        let mut filter = LogcatFilter::default();
+        assert!(filter.matches(&record));
+
+        filter.min_level = LogLevel::Warn;
+        assert!(!filter.matches(&record));
+
+        filter.min_level = LogLevel::Info;
+        filter.tag = Some("activity".to_string());
+        assert!(filter.matches(&record));
+
+        filter.pid = Some(PidFilter {
+            label: "com.example".to_string(),
+            pids: vec![42],
+        });
+        assert!(!filter.matches(&record));
+
+        filter.pid = None;
+        filter.regex = Some(Regex::new(r"proc \d+").unwrap());
+        assert!(filter.matches(&record));
+    }
+
+    #[test]
+    fn test_scroll_stays_anchored() {
+        let mut view = LogcatView::new();
+        view.push_lines(vec![SAMPLE.to_string(); 5]);
+        assert!(view.is_following());
+
+        view.scroll(2);
+        view.push_lines(vec![SAMPLE.to_string(); 3]);
+        assert_eq!(view.scroll_offset, 5);
+
+        view.scroll(-100);
+        assert!(view.is_following());
+    }
+
+    #[test]
+    fn test_scroll_clamped_after_eviction() {
+        let quiet = SAMPLE.replace(" I ", " V ");
+        let mut view = LogcatView::new();
+        view.set_filter(LogcatFilter {
+            min_level: LogLevel::Info,
+            ..Default::default()
+        });
+        view.push_lines(vec![SAMPLE.to_string(); 10]);
+        view.scroll(9);
+
+        view.push_lines(vec![quiet; MAX_RECORDS]);
+        assert_eq!(view.record_count(), MAX_RECORDS);
+        assert_eq!(view.visible_count(), 0);
+        assert_eq!(view.scroll_offset, 0);
+        assert_eq!(view.window(10).count(), 0);
+    }
+
+    #[test]
+    fn test_window_follows_filter() {
+        let warning = SAMPLE.replace(" I ", " W ");
+        let mut view = LogcatView::new();
+        view.push_lines(vec![
+            SAMPLE.to_string(),
+            warning.clone(),
+            SAMPLE.to_string(),
+        ]);
+        assert_eq!(view.visible_count(), 3);
+
+        view.scroll(1);
+        view.set_filter(LogcatFilter {
+            min_level: LogLevel::Warn,
+            ..Default::default()
+        });
+        assert!(view.is_following());
+        assert_eq!(view.visible_count(), 1);
+        let shown: Vec<_> = view.window(5).map(|record| record.level).collect();
+        assert_eq!(shown, vec![LogLevel::Warn]);
+
+        view.push_lines(vec![warning, SAMPLE.to_string()]);
+        assert_eq!(view.visible_count(), 2);
+        assert_eq!(view.window(1).count(), 1);
+    }
+
+    #[test]
+    fn test_parse_pids() {
+        assert_eq!(parse_pids("4242 4243\n"), vec![4242, 4243]);
+        assert!(parse_pids("").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_events_carry_handle_id() {
+        use crate::adb::MockBackend;
+
+        let manager = AdbManager::with_backend(Box::new(MockBackend::demo()));
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let first = spawn_logcat(&manager, sender.clone());
+        let second = spawn_logcat(&manager, sender);
+        assert_ne!(first.id, second.id);
+
+        let mut ended = Vec::new();
+        while ended.len() < 2 {
+            match receiver.recv().await {
+                Some(Event::Logcat(LogcatEvent::Lines { id, lines })) => {
+                    assert!([first.id, second.id].contains(&id));
+                    assert!(lines[0].contains("ActivityManager"));
+                }
+                Some(Event::Logcat(LogcatEvent::Ended { id, error })) => {
+                    assert_eq!(error, None);
+                    ended.push(id);
+                }
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        ended.sort_unstable();
+        assert_eq!(ended, [first.id, second.id]);
+    }
+}
@@ -1,21 +1,95 @@
+use crate::adb::{AdbError, AdbManager};
 use crate::app::App;
+use crate::cli::{Cli, CONFIG_ERROR};
+use crate::config::Config;
+use crate::menu::Menu;
+use clap::Parser;
+use std::process::ExitCode;
 
 pub mod adb;
 pub mod app;
+pub mod cli;
+pub mod config;
+pub mod confirm;
+pub mod dashboard;
 pub mod effects;
 pub mod event;
+pub mod files;
+pub mod hotplug;
+pub mod input;
+pub mod logcat;
 pub mod menu;
 pub mod message;
+pub mod mirror;
 pub mod model;
+pub mod packages;
+pub mod preview;
+pub mod processes;
+pub mod reboot;
+pub mod record;
+pub mod screenshot;
 pub mod stream;
+pub mod template;
+pub mod toast;
 pub mod update;
 pub mod view;
+pub mod wireless;
+pub mod worker;
 
 #[tokio::main]
-async fn main() -> color_eyre::Result<()> {
+async fn main() -> color_eyre::Result<ExitCode> {
     color_eyre::install()?;
+    let cli = Cli::parse();
+    let (config, mut adb_manager) = match setup(&cli) {
+        Ok(setup) => setup,
+        Err((message, code)) => {
+            eprintln!("droidtui: {}", message);
+            return Ok(ExitCode::from(code));
+        }
+    };
+
+    // Subcommands are for scripts; the TUI only starts without one
+    if let Some(command) = cli.command {
+        return Ok(cli::run(command, &mut adb_manager));
+    }
+
+    let menu = match Menu::with_config(&config.menu) {
+        Ok(menu) => menu,
+        Err(e) => {
+            eprintln!("droidtui: {}", e);
+            return Ok(ExitCode::from(CONFIG_ERROR));
+        }
+    };
     let terminal = ratatui::init();
-    let result = App::new().run(terminal).await;
+    let result = App::with_adb_manager(adb_manager)
+        .with_menu(menu)
+        .with_dashboard_interval(config.dashboard_interval())
+        .run(terminal)
+        .await;
     ratatui::restore();
-    result
+    result.map(|()| ExitCode::SUCCESS)
+}
+
+/// Load the config and build the ADB manager it and the flags describe
+///
+/// Fails with a message and the exit code to report it with.
+fn setup(cli: &Cli) -> Result<(Config, AdbManager), (String, u8)> {
+    let config_error = |e: String| (e, CONFIG_ERROR);
+    let adb_error = |e: AdbError| (e.to_string(), e.exit_code());
+
+    let config = Config::load().map_err(config_error)?;
+    let address = cli.resolve_server(&config).map_err(config_error)?;
+    let mut adb_manager = AdbManager::with_server(address).map_err(adb_error)?;
+    if let Some(kind) = cli.backend.or(config.backend().map_err(config_error)?) {
+        adb_manager.set_backend(kind).map_err(adb_error)?;
+    }
+    adb_manager.set_read_only(cli.read_only || config.read_only);
+    adb_manager.set_timeouts(config.timeouts().map_err(config_error)?);
+    if let Some(dir) = config.screenshot_dir() {
+        adb_manager.set_screenshot_dir(dir);
+    }
+    if let Some(serial) = &cli.serial {
+        adb_manager.select_device(serial.clone());
+    }
+    Ok((config, adb_manager))
 }
@@ -1,5 +1,6 @@
 // Removed unused import: get_menu_border_color
-use crate::adb::{AdbCommand, PackageFilter};
+use crate::adb::{AdbCommand, PackageFilter, RebootTarget, RecordOptions};
+use crate::config::MenuEntry;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Layout, Rect},
@@ -23,6 +24,20 @@ pub struct MenuChild {
     pub command: AdbCommand,
 }
 
+impl MenuItem {
+    /// Whether running the item needs confirmation
+    pub fn is_destructive(&self) -> bool {
+        self.children.is_empty() && self.command.is_destructive()
+    }
+}
+
+impl MenuChild {
+    /// Whether running the entry needs confirmation
+    pub fn is_destructive(&self) -> bool {
+        self.command.is_destructive()
+    }
+}
+
 #[derive(Debug)]
 pub struct Menu {
     pub items: Vec<MenuItem>,
@@ -69,6 +84,11 @@ impl Default for Menu {
                     filter: PackageFilter::All,
                 },
                 children: vec![
+                    MenuChild {
+                        label: "🧰 Manage Packages".to_string(),
+                        description: "Search packages and run per-app actions".to_string(),
+                        command: AdbCommand::ManagePackages,
+                    },
                     MenuChild {
                         label: "📦 All Packages".to_string(),
                         description: "List all installed packages".to_string(),
@@ -103,6 +123,12 @@ impl Default for Menu {
                     },
                 ],
             },
+            MenuItem {
+                label: "📈 Live Dashboard".to_string(),
+                description: "Battery, CPU, memory and network graphs updated live".to_string(),
+                command: AdbCommand::Dashboard,
+                children: vec![],
+            },
             MenuItem {
                 label: "🔋 Battery Info".to_string(),
                 description: "Display detailed battery information".to_string(),
@@ -240,6 +266,12 @@ impl Default for Menu {
                 description: "List all running processes".to_string(),
                 command: AdbCommand::ListProcesses,
                 children: vec![
+                    MenuChild {
+                        label: "🧰 Process Manager".to_string(),
+                        description: "Sort, filter and signal processes, refreshed live"
+                            .to_string(),
+                        command: AdbCommand::ManageProcesses,
+                    },
                     MenuChild {
                         label: "📋 All Processes".to_string(),
                         description: "List all running processes".to_string(),
@@ -260,13 +292,6 @@ impl Default for Menu {
                                 .to_string(),
                         },
                     },
-                    MenuChild {
-                        label: "📊 Process Details".to_string(),
-                        description: "Detailed process information with formatting".to_string(),
-                        command: AdbCommand::Shell {
-                            command: "echo 'PID    USER         COMMAND'; echo '---    ----         -------'; ps -A | awk '{printf \"%-6s %-12s %s\\n\", $2, $1, $9}' | head -20".to_string(),
-                        },
-                    },
                 ],
             },
             MenuItem {
@@ -300,14 +325,79 @@ impl Default for Menu {
                     },
                 ],
             },
+            MenuItem {
+                label: "📂 File Browser".to_string(),
+                description: "Browse device storage, push and pull files".to_string(),
+                command: AdbCommand::ListDirectory {
+                    path: "/sdcard".to_string(),
+                },
+                children: vec![
+                    MenuChild {
+                        label: "📂 Internal Storage".to_string(),
+                        description: "Browse /sdcard".to_string(),
+                        command: AdbCommand::ListDirectory {
+                            path: "/sdcard".to_string(),
+                        },
+                    },
+                    MenuChild {
+                        label: "📥 Downloads".to_string(),
+                        description: "Browse /sdcard/Download".to_string(),
+                        command: AdbCommand::ListDirectory {
+                            path: "/sdcard/Download".to_string(),
+                        },
+                    },
+                    MenuChild {
+                        label: "🗂️ Temp Directory".to_string(),
+                        description: "Browse /data/local/tmp".to_string(),
+                        command: AdbCommand::ListDirectory {
+                            path: "/data/local/tmp".to_string(),
+                        },
+                    },
+                ],
+            },
+            MenuItem {
+                label: "📶 Wireless ADB".to_string(),
+                description: "Pair, connect and disconnect network devices".to_string(),
+                command: AdbCommand::Connect {
+                    address: String::new(),
+                },
+                children: vec![
+                    MenuChild {
+                        label: "🔗 Connect to Device".to_string(),
+                        description: "Connect to host[:port] over the network".to_string(),
+                        command: AdbCommand::Connect {
+                            address: String::new(),
+                        },
+                    },
+                    MenuChild {
+                        label: "🔐 Pair Device".to_string(),
+                        description: "Pair with an Android 11+ wireless debugging code"
+                            .to_string(),
+                        command: AdbCommand::Pair {
+                            address: String::new(),
+                            code: String::new(),
+                        },
+                    },
+                    MenuChild {
+                        label: "📶 Enable TCP/IP Mode".to_string(),
+                        description: "Restart adbd listening on port 5555".to_string(),
+                        command: AdbCommand::TcpIp { port: 5555 },
+                    },
+                    MenuChild {
+                        label: "✂️ Disconnect All".to_string(),
+                        description: "Disconnect every network device".to_string(),
+                        command: AdbCommand::Disconnect { address: None },
+                    },
+                ],
+            },
             MenuItem {
                 label: "📷 Screenshot".to_string(),
-                description: "Take and save device screenshots".to_string(),
+                description: "Save a screenshot locally and preview it".to_string(),
                 command: AdbCommand::TakeScreenshot,
                 children: vec![
                     MenuChild {
                         label: "📸 Take Screenshot".to_string(),
-                        description: "Take screenshot and save to device".to_string(),
+                        description: "Save a timestamped PNG locally and preview it".to_string(),
                         command: AdbCommand::TakeScreenshot,
                     },
                     MenuChild {
@@ -316,10 +406,10 @@ impl Default for Menu {
                         command: AdbCommand::GetScreenResolution,
                     },
                     MenuChild {
-                        label: "🖼️ View Screenshot Path".to_string(),
-                        description: "Show where screenshots are saved".to_string(),
-                        command: AdbCommand::Shell {
-                            command: "ls -la /sdcard/screenshot*.png".to_string(),
+                        label: "🖼️ Device Screenshots".to_string(),
+                        description: "Browse screenshots stored on the device".to_string(),
+                        command: AdbCommand::ListDirectory {
+                            path: "/sdcard/Pictures/Screenshots".to_string(),
                         },
                     },
                 ],
@@ -327,29 +417,43 @@ impl Default for Menu {
             MenuItem {
                 label: "🔄 Reboot Device".to_string(),
                 description: "Reboot the connected device".to_string(),
-                command: AdbCommand::Shell {
-                    command: "reboot".to_string(),
+                command: AdbCommand::Reboot {
+                    target: RebootTarget::System,
                 },
                 children: vec![
                     MenuChild {
                         label: "🔄 Normal Reboot".to_string(),
                         description: "Reboot device normally".to_string(),
-                        command: AdbCommand::Shell {
-                            command: "reboot".to_string(),
+                        command: AdbCommand::Reboot {
+                            target: RebootTarget::System,
                         },
                     },
                     MenuChild {
-                        label: "⚡ Fast Reboot".to_string(),
-                        description: "Fast reboot (bootloader)".to_string(),
-                        command: AdbCommand::Shell {
-                            command: "reboot bootloader".to_string(),
+                        label: "🥾 Bootloader".to_string(),
+                        description: "Reboot to the bootloader (fastboot)".to_string(),
+                        command: AdbCommand::Reboot {
+                            target: RebootTarget::Bootloader,
                         },
                     },
                     MenuChild {
                         label: "🔧 Recovery Mode".to_string(),
                         description: "Reboot to recovery mode".to_string(),
-                        command: AdbCommand::Shell {
-                            command: "reboot recovery".to_string(),
+                        command: AdbCommand::Reboot {
+                            target: RebootTarget::Recovery,
+                        },
+                    },
+                    MenuChild {
+                        label: "📦 Sideload".to_string(),
+                        description: "Reboot to recovery, ready for adb sideload".to_string(),
+                        command: AdbCommand::Reboot {
+                            target: RebootTarget::Sideload,
+                        },
+                    },
+                    MenuChild {
+                        label: "⚡ Fastbootd".to_string(),
+                        description: "Reboot to userspace fastboot".to_string(),
+                        command: AdbCommand::Reboot {
+                            target: RebootTarget::Fastboot,
                         },
                     },
                 ],
@@ -359,6 +463,12 @@ impl Default for Menu {
                 description: "View recent system logs (last 100 lines)".to_string(),
                 command: AdbCommand::GetSystemLog { lines: 100 },
                 children: vec![
+                    MenuChild {
+                        label: "📡 Live Logcat".to_string(),
+                        description: "Stream logcat with level, tag, package and regex filters"
+                            .to_string(),
+                        command: AdbCommand::StreamLogcat,
+                    },
                     MenuChild {
                         label: "📜 Recent Logs".to_string(),
                         description: "Last 100 log entries".to_string(),
@@ -432,6 +542,28 @@ impl Default for Menu {
                             command: "STREAM_FAST".to_string(),
                         },
                     },
+                    MenuChild {
+                        label: "🖥️ Mirror in Terminal".to_string(),
+                        description: "Show the screen inside the terminal (works over SSH and in tmux)".to_string(),
+                        command: AdbCommand::MirrorScreen,
+                    },
+                    MenuChild {
+                        label: "⏺️ Record Screen".to_string(),
+                        description: "Record the screen to an MP4 file (up to 3 minutes)".to_string(),
+                        command: AdbCommand::RecordScreen {
+                            options: RecordOptions::default(),
+                        },
+                    },
+                    MenuChild {
+                        label: "⏺️ Record + Show Window".to_string(),
+                        description: "Record to MP4 while streaming to a window".to_string(),
+                        command: AdbCommand::RecordScreen {
+                            options: RecordOptions {
+                                show_window: true,
+                                ..RecordOptions::default()
+                            },
+                        },
+                    },
                 ],
             },
         ];
@@ -461,6 +593,16 @@ impl Menu {
         Self::default()
     }
 
+    /// Built-in menu with the user's `[[menu]]` entries applied
+    pub fn with_config(entries: &[MenuEntry]) -> Result<Self, String> {
+        let mut menu = Self::default();
+        apply_item_entries(&mut menu.items, entries)?;
+        if menu.items.is_empty() {
+            return Err("menu: every entry is hidden".to_string());
+        }
+        Ok(menu)
+    }
+
     pub fn tick(&mut self) {
         self.tick_count = self.tick_count.wrapping_add(1);
     }
@@ -520,6 +662,15 @@ impl Menu {
         }
     }
 
+    /// Label of the entry [`Menu::get_selected_command`] runs
+    pub fn get_selected_label(&self) -> &str {
+        let item = &self.items[self.selected];
+        match item.children.get(self.child_selected) {
+            Some(child) if self.in_child_mode => &child.label,
+            _ => &item.label,
+        }
+    }
+
     pub fn enter_child_mode(&mut self) {
         if !self.items[self.selected].children.is_empty() {
             self.in_child_mode = true;
@@ -544,6 +695,122 @@ impl Menu {
     }
 }
 
+/// Label without its leading emoji, used to match config entries to built-ins
+fn label_key(label: &str) -> String {
+    label
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .trim()
+        .to_lowercase()
+}
+
+/// Move the element at `from` to `to`, clamped to the end
+fn move_to<T>(items: &mut Vec<T>, from: usize, to: Option<usize>) {
+    if let Some(to) = to {
+        let item = items.remove(from);
+        items.insert(to.min(items.len()), item);
+    }
+}
+
+fn apply_item_entries(items: &mut Vec<MenuItem>, entries: &[MenuEntry]) -> Result<(), String> {
+    for entry in entries {
+        let key = label_key(&entry.label);
+        let index = items.iter().position(|item| label_key(&item.label) == key);
+
+        match index {
+            Some(index) if entry.hide => {
+                items.remove(index);
+            }
+            None if entry.hide => {
+                return Err(format!("menu: no entry named '{}' to hide", entry.label));
+            }
+            Some(index) => {
+                let item = &mut items[index];
+                if let Some(description) = &entry.description {
+                    item.description = description.clone();
+                }
+                if let Some(command) = &entry.shell {
+                    item.command = AdbCommand::Shell {
+                        command: command.clone(),
+                    };
+                }
+                apply_child_entries(&mut item.children, &entry.children)?;
+                move_to(items, index, entry.position);
+            }
+            None => {
+                let mut children = Vec::new();
+                apply_child_entries(&mut children, &entry.children)?;
+                let command = match (&entry.shell, children.first()) {
+                    (Some(command), _) => AdbCommand::Shell {
+                        command: command.clone(),
+                    },
+                    (None, Some(child)) => child.command.clone(),
+                    (None, None) => {
+                        return Err(format!(
+                            "menu: '{}' needs a shell command or children",
+                            entry.label
+                        ))
+                    }
+                };
+                items.push(MenuItem {
+                    label: entry.label.clone(),
+                    description: entry.description.clone().unwrap_or_default(),
+                    command,
+                    children,
+                });
+                move_to(items, items.len() - 1, entry.position);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_child_entries(children: &mut Vec<MenuChild>, entries: &[MenuEntry]) -> Result<(), String> {
+    for entry in entries {
+        if !entry.children.is_empty() {
+            return Err(format!("menu: '{}' is nested too deeply", entry.label));
+        }
+        let key = label_key(&entry.label);
+        let index = children
+            .iter()
+            .position(|child| label_key(&child.label) == key);
+
+        match index {
+            Some(index) if entry.hide => {
+                children.remove(index);
+            }
+            None if entry.hide => {
+                return Err(format!("menu: no entry named '{}' to hide", entry.label));
+            }
+            Some(index) => {
+                let child = &mut children[index];
+                if let Some(description) = &entry.description {
+                    child.description = description.clone();
+                }
+                if let Some(command) = &entry.shell {
+                    child.command = AdbCommand::Shell {
+                        command: command.clone(),
+                    };
+                }
+                move_to(children, index, entry.position);
+            }
+            None => {
+                let Some(command) = &entry.shell else {
+                    return Err(format!("menu: '{}' needs a shell command", entry.label));
+                };
+                children.push(MenuChild {
+                    label: entry.label.clone(),
+                    description: entry.description.clone().unwrap_or_default(),
+                    command: AdbCommand::Shell {
+                        command: command.clone(),
+                    },
+                });
+                move_to(children, children.len() - 1, entry.position);
+            }
+        }
+    }
+    Ok(())
+}
+
 impl Widget for &Menu {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // Create border block
@@ -592,8 +859,11 @@ impl Widget for &Menu {
                         Style::default().fg(Color::White)
                     };
 
-                    let content =
-                        Line::from(vec![Span::raw("  "), Span::styled(&child.label, style)]);
+                    let mut spans = vec![Span::raw("  "), Span::styled(&child.label, style)];
+                    if child.is_destructive() {
+                        spans.push(Span::styled(" ⚠", Style::default().fg(Color::LightRed)));
+                    }
+                    let content = Line::from(spans);
 
                     ListItem::new(content)
                 })
@@ -640,6 +910,8 @@ impl Widget for &Menu {
                         Span::styled(&item.label, style),
                         if !item.children.is_empty() {
                             Span::raw(" ▶")
+                        } else if item.is_destructive() {
+                            Span::styled(" ⚠", Style::default().fg(Color::LightRed))
                         } else {
                             Span::raw("")
                         },
@@ -669,3 +941,88 @@ impl Widget for &Menu {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(label: &str) -> MenuEntry {
+        MenuEntry {
+            label: label.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_menu_config_merge() {
+        let entries = vec![
+            MenuEntry {
+                hide: true,
+                ..entry("Screen Stream")
+            },
+            MenuEntry {
+                position: Some(0),
+                children: vec![MenuEntry {
+                    shell: Some("am start -n {package}/.Main".to_string()),
+                    ..entry("Start app")
+                }],
+                ..entry("🧪 My Tools")
+            },
+            MenuEntry {
+                description: Some("Just the battery".to_string()),
+                children: vec![MenuEntry {
+                    hide: true,
+                    ..entry("Charging Status")
+                }],
+                ..entry("battery info")
+            },
+        ];
+        let menu = Menu::with_config(&entries).unwrap();
+        let default = Menu::default();
+
+        assert_eq!(menu.items.len(), default.items.len());
+        assert_eq!(menu.items[0].label, "🧪 My Tools");
+        assert!(matches!(
+            &menu.items[0].command,
+            AdbCommand::Shell { command } if command.contains("{package}")
+        ));
+        assert!(!menu
+            .items
+            .iter()
+            .any(|item| item.label.contains("Screen Stream")));
+
+        let battery = menu
+            .items
+            .iter()
+            .find(|item| item.label.contains("Battery"))
+            .unwrap();
+        assert_eq!(battery.description, "Just the battery");
+        assert_eq!(battery.children.len(), 2);
+    }
+
+    #[test]
+    fn test_destructive_entries() {
+        let menu = Menu::default();
+        let destructive: Vec<&str> = menu
+            .items
+            .iter()
+            .flat_map(|item| &item.children)
+            .filter(|child| child.is_destructive())
+            .map(|child| child.label.as_str())
+            .collect();
+        assert!(destructive.contains(&"🔄 Normal Reboot"));
+        assert!(destructive.contains(&"🔧 Recovery Mode"));
+        assert!(destructive.contains(&"🔄 Clear Logs"));
+        assert!(!destructive.contains(&"📜 Recent Logs"));
+    }
+
+    #[test]
+    fn test_menu_config_errors() {
+        assert!(Menu::with_config(&[MenuEntry {
+            hide: true,
+            ..entry("No Such Entry")
+        }])
+        .is_err());
+        assert!(Menu::with_config(&[entry("Empty")]).is_err());
+    }
+}
@@ -1,4 +1,9 @@
-use crate::adb::AdbCommand;
+use crate::adb::{AdbCommand, AdbOutput, DeviceInfo, DeviceSample, ProcessSample, RemoteEntry};
+use crate::hotplug::DeviceEvent;
+use crate::logcat::LogcatField;
+use crate::record::RecordingSummary;
+use crate::wireless::WirelessPrompt;
+use crate::worker::PollError;
 
 /// Messages represent all possible actions/events in the application
 /// This follows the Elm architecture pattern for clear state transitions
@@ -14,6 +19,118 @@ pub enum Message {
     ExecuteCommand(AdbCommand),
     CommandStarted,
     CommandCompleted(CommandResult),
+    CancelCommand,
+
+    // Placeholder prompt of templated custom commands
+    PromptInput(char),
+    PromptBackspace,
+    PromptSubmit,
+    PromptCancel,
+
+    // Confirmation of destructive commands
+    ConfirmAccept,
+    ConfirmDecline,
+
+    // Device picker
+    OpenDevicePicker,
+    RefreshDevices,
+    DeviceUp,
+    DeviceDown,
+    SelectDevice,
+    DisconnectDevice,
+    DisconnectDone(Result<String, PollError>),
+    DevicesListed(Result<Vec<DeviceInfo>, PollError>),
+
+    // Live device list from the tracker
+    DevicesChanged(DeviceEvent),
+
+    // Wireless debugging prompts in the device picker
+    OpenWirelessPrompt(WirelessPrompt),
+    WirelessInput(char),
+    WirelessBackspace,
+    WirelessRecent(isize),
+    WirelessSubmit,
+    WirelessCancel,
+
+    // Live logcat
+    LogcatLines(Vec<String>),
+    LogcatEnded(Option<String>),
+    LogcatScroll(isize),
+    LogcatFollow,
+    LogcatCycleLevel,
+    LogcatEditFilter(LogcatField),
+    LogcatInput(char),
+    LogcatBackspace,
+    LogcatSubmitFilter,
+    LogcatCancelInput,
+    LogcatClearFilters,
+    StopLogcat,
+
+    // File browser
+    OpenFileBrowser,
+    FileUp,
+    FileDown,
+    FileOpen,
+    FileParent,
+    FileSwitchPane,
+    FileRefresh,
+    FileCopy,
+    FileDelete,
+    FileRename,
+    FileInput(char),
+    FileBackspace,
+    FileSubmitInput,
+    FileCancelInput,
+    RemoteListed(Result<Vec<RemoteEntry>, PollError>),
+    FileActionDone(Result<String, PollError>),
+    TransferProgress {
+        transferred: u64,
+        total: Option<u64>,
+    },
+    TransferFinished(Result<String, String>),
+
+    // Live dashboard
+    DashboardSample(Result<DeviceSample, PollError>),
+    DashboardFaster,
+    DashboardSlower,
+
+    // Process manager
+    ProcessSample(Result<ProcessSample, PollError>),
+    ProcessMove(isize),
+    ProcessSearch,
+    ProcessInput(char),
+    ProcessBackspace,
+    ProcessSearchDone,
+    ProcessCycleSort,
+    ProcessReverseSort,
+    ProcessOpenActions,
+    ProcessCloseActions,
+    ProcessRunAction,
+    ProcessActionDone(Result<String, PollError>),
+
+    // Reboot progress: the serial once rebooted, then the wait result
+    RebootProgress(Result<String, String>),
+
+    // Screen recording
+    StopRecording,
+    RecordingFinished(Result<RecordingSummary, String>),
+
+    // Terminal mirroring
+    MirrorFrame,
+    MirrorEnded(Result<(), String>),
+
+    // Package manager
+    RefreshPackages,
+    PackageUp,
+    PackageDown,
+    PackageSearch,
+    PackageInput(char),
+    PackageBackspace,
+    PackageSearchDone,
+    PackageOpenActions,
+    PackageCloseActions,
+    PackageRunAction,
+    PackageActionDone(Result<String, PollError>),
 
     // Scroll messages for result view
     ScrollUp,
@@ -26,6 +143,8 @@ pub enum Message {
     // Screen streaming messages
     StartStream,
     StopStream,
+    StreamStarted,
+    StreamEnded(Result<(), String>),
     UpdateFrame(Option<String>),
     TogglePause,
     IncreaseRefreshRate,
@@ -43,7 +162,7 @@ pub enum Message {
 /// Result of command execution
 #[derive(Debug, Clone)]
 pub enum CommandResult {
-    Success(String),
+    Success(AdbOutput),
     Error(String),
 }
 
@@ -55,6 +174,9 @@ impl Message {
             Message::ExecuteCommand(_)
                 | Message::CommandStarted
                 | Message::CommandCompleted(_)
+                | Message::CancelCommand
+                | Message::ConfirmAccept
+                | Message::ConfirmDecline
                 | Message::Quit
                 | Message::ReturnToMenu
                 | Message::EnterChild
@@ -62,6 +184,10 @@ impl Message {
                 | Message::SkipStartup
                 | Message::StartStream
                 | Message::StopStream
+                | Message::OpenDevicePicker
+                | Message::SelectDevice
+                | Message::StopLogcat
+                | Message::OpenFileBrowser
         )
     }
 }
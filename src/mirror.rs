@@ -0,0 +1,195 @@
+//! Screen mirroring inside the terminal
+//!
+//! The `screenrecord` stream is decoded by the same ffmpeg pipeline as the stream
+//! window, but frames land in a single slot that the view draws from instead of a
+//! window. A frame is only announced once the previous one has been shown, so frames
+//! are dropped at the source whenever drawing can't keep up.
+
+use crate::adb::{AdbError, AdbManager, AdbResult, StreamConfig};
+use crate::event::Event;
+use crate::stream::{check_ffmpeg, spawn_decoder, stream_screen};
+use crate::worker::{spawn_poll, PollJob};
+use image::RgbImage;
+use std::io::{BufReader, Read};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Longest side of the decoded video; the terminal shows far fewer pixels
+const MAX_SIDE: usize = 960;
+
+/// Bitrate of the mirrored stream
+const BITRATE: &str = "4M";
+
+/// Progress of a terminal mirror
+#[derive(Debug, Clone)]
+pub enum MirrorEvent {
+    /// A new frame is waiting in the mirror's slot
+    Frame,
+    /// The stream ended
+    Ended { id: u64, result: Result<(), String> },
+}
+
+/// State shared with the decoding thread
+#[derive(Debug, Default)]
+struct Shared {
+    /// Newest decoded frame not yet shown
+    latest: Mutex<Option<RgbImage>>,
+    /// A [`MirrorEvent::Frame`] was sent and not yet handled
+    pending: AtomicBool,
+    /// Frames decoded so far
+    decoded: AtomicU64,
+}
+
+/// A terminal mirror running in the background
+#[derive(Debug)]
+pub struct Mirror {
+    /// Frame on screen
+    pub frame: Option<RgbImage>,
+    /// Frames shown so far
+    pub shown: u64,
+    pub started: Instant,
+    shared: Arc<Shared>,
+    pub poll: PollJob,
+}
+
+impl Mirror {
+    /// Start mirroring the selected device; fails up front when ffmpeg is missing
+    pub fn start(manager: &AdbManager, sender: UnboundedSender<Event>) -> Result<Self, String> {
+        check_ffmpeg()?;
+        let shared = Arc::new(Shared::default());
+        let poll = {
+            let shared = shared.clone();
+            let frames = sender.clone();
+            spawn_poll(
+                manager,
+                sender,
+                None,
+                move |manager| run(manager, &shared, &frames),
+                |id, result| {
+                    Event::Mirror(MirrorEvent::Ended {
+                        id,
+                        result: result.map_err(|e| e.message),
+                    })
+                },
+            )
+        };
+        Ok(Self {
+            frame: None,
+            shown: 0,
+            started: Instant::now(),
+            shared,
+            poll,
+        })
+    }
+
+    /// Move the newest frame on screen; returns whether there was one
+    pub fn take_frame(&mut self) -> bool {
+        let latest = self
+            .shared
+            .latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        self.shared.pending.store(false, Ordering::SeqCst);
+        match latest {
+            Some(frame) => {
+                self.frame = Some(frame);
+                self.shown += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Frames decoded so far, shown or dropped
+    pub fn decoded(&self) -> u64 {
+        self.shared.decoded.load(Ordering::Relaxed)
+    }
+
+    /// Frames shown per second since the start
+    pub fn fps(&self, now: Instant) -> f64 {
+        let secs = now.duration_since(self.started).as_secs_f64();
+        if secs > 0.0 {
+            self.shown as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Video size for a device screen: the same aspect ratio, longest side at most
+/// [`MAX_SIDE`] and both sides multiples of 16 as hardware encoders want
+pub fn video_size(screen: (usize, usize)) -> (usize, usize) {
+    let longest = screen.0.max(screen.1).max(1);
+    let scale = (MAX_SIDE as f64 / longest as f64).min(1.0);
+    let side = |v: usize| (((v as f64 * scale) as usize) / 16 * 16).max(16);
+    (side(screen.0), side(screen.1))
+}
+
+/// Decode the screen into `shared` until cancelled
+fn run(
+    manager: &mut AdbManager,
+    shared: &Arc<Shared>,
+    sender: &UnboundedSender<Event>,
+) -> AdbResult<()> {
+    let (width, height) = video_size(manager.screen_size()?);
+    let config = StreamConfig {
+        width,
+        height,
+        bitrate: BITRATE.to_string(),
+    };
+
+    let mut decoder = spawn_decoder().map_err(AdbError::CommandFailed)?;
+    let (Some(mut input), Some(frames)) = (decoder.stdin.take(), decoder.stdout.take()) else {
+        let _ = decoder.kill();
+        return Err(AdbError::CommandFailed("ffmpeg has no pipes".to_string()));
+    };
+
+    let reader = {
+        let shared = shared.clone();
+        let sender = sender.clone();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(frames);
+            loop {
+                let mut buffer = vec![0u8; width * height * 3];
+                if reader.read_exact(&mut buffer).is_err() {
+                    break;
+                }
+                let Some(frame) = RgbImage::from_raw(width as u32, height as u32, buffer) else {
+                    break;
+                };
+                shared.decoded.fetch_add(1, Ordering::Relaxed);
+                // Replacing an unshown frame drops it
+                *shared.latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(frame);
+                if !shared.pending.swap(true, Ordering::SeqCst)
+                    && sender.send(Event::Mirror(MirrorEvent::Frame)).is_err()
+                {
+                    break;
+                }
+            }
+        })
+    };
+
+    let result = stream_screen(manager, &config, &mut input);
+
+    drop(input);
+    let _ = decoder.kill();
+    let _ = decoder.wait();
+    let _ = reader.join();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_video_size() {
+        assert_eq!(video_size((1080, 2400)), (432, 960));
+        assert_eq!(video_size((2400, 1080)), (960, 432));
+        assert_eq!(video_size((480, 800)), (480, 800));
+    }
+}
@@ -1,8 +1,27 @@
-use crate::adb::{AdbCommand, AdbManager};
+use crate::adb::{AdbCommand, AdbManager, AdbOutput, DeviceInfo, Watchdog};
+use crate::confirm::Confirmation;
+use crate::dashboard::Dashboard;
 use crate::effects::EffectsManager;
+use crate::event::Event;
+use crate::files::FileBrowser;
+use crate::hotplug::{DeviceTracker, TrackingState};
+use crate::logcat::{LogcatHandle, LogcatView};
 use crate::menu::Menu;
+use crate::mirror::Mirror;
+use crate::packages::PackageView;
+use crate::preview::GraphicsProtocol;
+use crate::processes::ProcessView;
+use crate::reboot::RebootWatch;
+use crate::record::Recording;
+use crate::screenshot::Screenshot;
 use crate::stream::StreamState;
+use crate::template::CommandPrompt;
+use crate::toast::Toasts;
+use crate::wireless::WirelessState;
+use crate::worker::{CommandJob, PollJob};
+use ratatui::layout::Rect;
 use std::time::Instant;
+use tokio::sync::mpsc::UnboundedSender;
 
 /// Application state following Elm architecture
 /// All mutable state is contained within this model
@@ -26,6 +45,12 @@ pub struct Model {
     /// Command execution error
     pub command_error: Option<String>,
 
+    /// Parsed output of the last command, drawn as a panel above the raw text
+    pub result_panel: Option<AdbOutput>,
+
+    /// Exit status of the last shell command, when the device reported one
+    pub exit_code: Option<u8>,
+
     /// Loading animation counter
     pub loading_counter: u64,
 
@@ -49,6 +74,84 @@ pub struct Model {
 
     /// ADB client manager
     pub adb_manager: AdbManager,
+
+    /// Values being asked for before a templated custom command runs
+    pub command_prompt: Option<CommandPrompt>,
+
+    /// Destructive action waiting for a yes while `Confirm`
+    pub confirmation: Option<Confirmation>,
+
+    /// Command running in the background while `Loading`
+    pub command_job: Option<CommandJob>,
+
+    /// Commands that timed out in a row, per device
+    pub watchdog: Watchdog,
+
+    /// Devices attached to the server, kept live by the device tracker
+    pub devices: Vec<DeviceInfo>,
+
+    /// Device list requested for the picker
+    pub device_poll: Option<PollJob>,
+
+    /// Network device being disconnected from the picker
+    pub disconnect_job: Option<PollJob>,
+
+    /// Background `track-devices` connection
+    pub device_tracker: Option<DeviceTracker>,
+
+    /// Whether `devices` is being kept up to date
+    pub tracking: TrackingState,
+
+    /// Notifications shown over every screen
+    pub toasts: Toasts,
+
+    /// Highlighted row in the device picker
+    pub device_selected: usize,
+
+    /// Wireless debugging prompts and recent addresses
+    pub wireless: WirelessState,
+
+    /// Live logcat buffer and filters
+    pub logcat: LogcatView,
+
+    /// Background logcat reader, while streaming
+    pub logcat_handle: Option<LogcatHandle>,
+
+    /// Two-pane file browser
+    pub files: FileBrowser,
+
+    /// Package manager list and search
+    pub packages: PackageView,
+
+    /// Live device statistics and their history
+    pub dashboard: Dashboard,
+
+    /// Process manager list, sorting and filter
+    pub processes: ProcessView,
+
+    /// Reboot in progress, while `Rebooting`
+    pub reboot: Option<RebootWatch>,
+
+    /// Screen recording in progress, while `Recording`
+    pub recording: Option<Recording>,
+
+    /// Terminal mirror, while `Mirror`
+    pub mirror: Option<Mirror>,
+
+    /// Cells the mirrored frame occupies, recorded by the view for graphics protocols
+    pub mirror_area: Option<Rect>,
+
+    /// Last screenshot saved to the host, shown in the preview
+    pub screenshot: Option<Screenshot>,
+
+    /// Cells the preview image occupies, recorded by the view for graphics protocols
+    pub screenshot_area: Option<Rect>,
+
+    /// How images are drawn in this terminal
+    pub graphics: GraphicsProtocol,
+
+    /// Channel for background tasks to report back into the event loop
+    pub event_sender: Option<UnboundedSender<Event>>,
 }
 
 /// Application states
@@ -68,6 +171,42 @@ pub enum AppState {
 
     /// Showing command results
     ShowResult,
+
+    /// Choosing which attached device commands run against
+    DevicePicker,
+
+    /// Live logcat viewer
+    Logcat,
+
+    /// Local / device file browser
+    FileBrowser,
+
+    /// Interactive package manager
+    Packages,
+
+    /// Preview of the last screenshot
+    Screenshot,
+
+    /// Live device statistics
+    Dashboard,
+
+    /// Interactive process list
+    Processes,
+
+    /// Asking before a destructive command, over the screen it came from
+    Confirm,
+
+    /// Waiting for a rebooted device to come back
+    Rebooting,
+
+    /// Recording the screen to a file
+    Recording,
+
+    /// Mirroring the screen inside the terminal
+    Mirror,
+
+    /// Streaming the screen into a separate window
+    Streaming,
 }
 
 impl Default for Model {
@@ -86,6 +225,8 @@ impl Model {
             last_tick: Instant::now(),
             command_result: None,
             command_error: None,
+            result_panel: None,
+            exit_code: None,
             loading_counter: 0,
             scroll_position: 0,
             result_lines: Vec::new(),
@@ -94,6 +235,32 @@ impl Model {
             running: true,
             stream_state: None,
             adb_manager: AdbManager::new(),
+            command_prompt: None,
+            confirmation: None,
+            command_job: None,
+            watchdog: Watchdog::default(),
+            devices: Vec::new(),
+            device_poll: None,
+            disconnect_job: None,
+            device_tracker: None,
+            tracking: TrackingState::default(),
+            toasts: Toasts::default(),
+            device_selected: 0,
+            wireless: WirelessState::new(),
+            logcat: LogcatView::default(),
+            logcat_handle: None,
+            files: FileBrowser::default(),
+            packages: PackageView::default(),
+            dashboard: Dashboard::default(),
+            processes: ProcessView::default(),
+            reboot: None,
+            recording: None,
+            mirror: None,
+            mirror_area: None,
+            screenshot: None,
+            screenshot_area: None,
+            graphics: GraphicsProtocol::detect(),
+            event_sender: None,
         }
     }
 
@@ -122,10 +289,40 @@ impl Model {
         self.menu.get_selected_command()
     }
 
+    /// Serial of the device commands are sent to
+    pub fn active_device(&self) -> Option<&str> {
+        self.adb_manager.selected_device()
+    }
+
+    /// Device commands run on: the selected one, or the only one attached
+    pub fn target_device(&self) -> Option<&str> {
+        match (self.active_device(), self.devices.as_slice()) {
+            (Some(serial), _) => Some(serial),
+            (None, [device]) => Some(&device.serial),
+            _ => None,
+        }
+    }
+
+    /// Name of an attached device, falling back to its serial
+    pub fn device_name(&self, serial: &str) -> String {
+        self.devices
+            .iter()
+            .find(|d| d.serial == serial)
+            .map(|d| d.display_name())
+            .unwrap_or_else(|| serial.to_string())
+    }
+
+    /// Get the device currently highlighted in the picker
+    pub fn highlighted_device(&self) -> Option<&DeviceInfo> {
+        self.devices.get(self.device_selected)
+    }
+
     /// Clear result state
     pub fn clear_results(&mut self) {
         self.command_result = None;
         self.command_error = None;
+        self.result_panel = None;
+        self.exit_code = None;
         self.scroll_position = 0;
         self.result_lines.clear();
         self.wrapped_lines.clear();
@@ -134,14 +331,25 @@ impl Model {
     /// Set command result (success)
     pub fn set_result(&mut self, output: String) {
         self.command_result = Some(output.clone());
+        self.result_panel = None;
+        self.exit_code = None;
         self.result_lines = output.lines().map(|s| s.to_string()).collect();
         self.scroll_position = 0;
         self.reveal_counter = 0;
     }
 
+    /// Set command output, keeping parsed values for the result panel
+    pub fn set_output(&mut self, output: AdbOutput) {
+        self.set_result(output.text().to_string());
+        self.exit_code = output.exit_code();
+        self.result_panel = output.is_structured().then_some(output);
+    }
+
     /// Set command error
     pub fn set_error(&mut self, error: String) {
         self.command_error = Some(error.clone());
+        self.result_panel = None;
+        self.exit_code = None;
         self.result_lines = error.lines().map(|s| s.to_string()).collect();
         self.scroll_position = 0;
         self.reveal_counter = 0;
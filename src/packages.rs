@@ -0,0 +1,214 @@
+//! Package manager view
+//!
+//! Holds the state of the interactive package list: fuzzy search, selection and
+//! the per-package action panel.
+
+use crate::adb::{AdbCommand, PackageEntry};
+use crate::worker::PollJob;
+
+/// Score `candidate` against a fuzzy `query`; `None` when it doesn't match
+///
+/// Every query character must appear in order. Consecutive matches and matches at
+/// the start of a dotted segment score higher, so `gm` prefers `com.google.maps`.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.to_lowercase().chars() {
+        let offset = candidate[position..].iter().position(|&c| c == wanted)?;
+        let index = position + offset;
+
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || matches!(candidate[index - 1], '.' | '_') {
+            score += 3;
+        }
+
+        previous = Some(index);
+        position = index + 1;
+    }
+
+    // Prefer shorter names when scores tie
+    Some(score * 1000 - candidate.len() as i64)
+}
+
+/// Actions available from a package's action panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageAction {
+    Info,
+    Launch,
+    ForceStop,
+    ClearData,
+    Toggle,
+    Uninstall,
+    Logcat,
+}
+
+impl PackageAction {
+    pub const ALL: [PackageAction; 7] = [
+        PackageAction::Info,
+        PackageAction::Launch,
+        PackageAction::ForceStop,
+        PackageAction::ClearData,
+        PackageAction::Toggle,
+        PackageAction::Uninstall,
+        PackageAction::Logcat,
+    ];
+
+    /// Menu label for `package`
+    pub fn label(&self, package: &PackageEntry) -> &'static str {
+        match self {
+            PackageAction::Info => "ℹ️ Package Info",
+            PackageAction::Launch => "🚀 Launch",
+            PackageAction::ForceStop => "⏹️ Force Stop",
+            PackageAction::ClearData => "🧹 Clear Data",
+            PackageAction::Toggle if package.enabled => "🚫 Disable",
+            PackageAction::Toggle => "✅ Enable",
+            PackageAction::Uninstall => "🗑️ Uninstall",
+            PackageAction::Logcat => "📡 Open Logcat",
+        }
+    }
+
+    /// ADB command implementing the action
+    pub fn command(&self, package: &PackageEntry) -> AdbCommand {
+        let package_name = package.name.clone();
+        match self {
+            PackageAction::Info => AdbCommand::GetPackageInfo { package_name },
+            PackageAction::Launch => AdbCommand::LaunchPackage { package_name },
+            PackageAction::ForceStop => AdbCommand::ForceStop { package_name },
+            PackageAction::ClearData => AdbCommand::ClearPackageData { package_name },
+            PackageAction::Toggle => AdbCommand::SetPackageEnabled {
+                package_name,
+                enabled: !package.enabled,
+            },
+            PackageAction::Uninstall => AdbCommand::UninstallPackage { package_name },
+            PackageAction::Logcat => AdbCommand::StreamLogcat,
+        }
+    }
+}
+
+/// State of the package manager screen
+#[derive(Debug, Default)]
+pub struct PackageView {
+    pub entries: Vec<PackageEntry>,
+    pub query: String,
+    /// Whether keystrokes go to the search box
+    pub searching: bool,
+    /// Index into [`PackageView::filtered`]
+    pub selected: usize,
+    /// Highlighted action while the action panel is open
+    pub action: Option<usize>,
+    pub status: Option<String>,
+    /// Action running in the background, with the package it acts on
+    pub running: Option<(PollJob, PackageAction, PackageEntry)>,
+}
+
+impl PackageView {
+    /// Entries matching the query, best match first
+    pub fn filtered(&self) -> Vec<&PackageEntry> {
+        if self.query.is_empty() {
+            return self.entries.iter().collect();
+        }
+
+        let mut matches: Vec<(i64, &PackageEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| fuzzy_score(&self.query, &entry.name).map(|score| (score, entry)))
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        matches.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    pub fn selected_package(&self) -> Option<&PackageEntry> {
+        self.filtered().get(self.selected).copied()
+    }
+
+    pub fn up(&mut self) {
+        match self.action.as_mut() {
+            Some(action) => *action = action.saturating_sub(1),
+            None => self.selected = self.selected.saturating_sub(1),
+        }
+    }
+
+    pub fn down(&mut self) {
+        match self.action.as_mut() {
+            Some(action) => {
+                if *action + 1 < PackageAction::ALL.len() {
+                    *action += 1;
+                }
+            }
+            None => {
+                if self.selected + 1 < self.filtered().len() {
+                    self.selected += 1;
+                }
+            }
+        }
+    }
+
+    /// Replace the entries, keeping the same package selected when possible
+    pub fn set_entries(&mut self, entries: Vec<PackageEntry>) {
+        let current = self.selected_package().map(|p| p.name.clone());
+        self.entries = entries;
+        self.selected = current
+            .and_then(|name| self.filtered().iter().position(|p| p.name == name))
+            .unwrap_or(0);
+    }
+
+    /// Update the search query, resetting the selection to the best match
+    pub fn set_query(&mut self, query: String) {
+        self.query = query;
+        self.selected = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: &str = "package:/data/app/~~a1==/com.example.app-b2==/base.apk=com.example.app\n\
+                       package:/system/priv-app/Settings/Settings.apk=com.android.settings\n\
+                       package:/system/app/Calendar/Calendar.apk=com.android.calendar\n";
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("settings", "com.android.settings").is_some());
+        assert!(fuzzy_score("cas", "com.android.settings").is_some());
+        assert!(fuzzy_score("xyz", "com.android.settings").is_none());
+        assert!(
+            fuzzy_score("set", "com.android.settings") > fuzzy_score("set", "com.example.sxext")
+        );
+    }
+
+    #[test]
+    fn test_view_filter_and_actions() {
+        let mut view = PackageView::default();
+        view.set_entries(crate::adb::packages::build_package_entries(ALL, "", ""));
+        view.set_query("calendar".to_string());
+        assert_eq!(view.filtered().len(), 1);
+        assert_eq!(
+            view.selected_package().unwrap().name,
+            "com.android.calendar"
+        );
+
+        view.action = Some(0);
+        view.down();
+        view.down();
+        assert_eq!(view.action, Some(2));
+        assert_eq!(view.selected, 0);
+
+        let package = view.selected_package().unwrap();
+        assert!(matches!(
+            PackageAction::Toggle.command(package),
+            AdbCommand::SetPackageEnabled { enabled: false, .. }
+        ));
+        assert_eq!(PackageAction::Toggle.label(package), "🚫 Disable");
+    }
+}
@@ -0,0 +1,381 @@
+//! Image rendering inside the terminal
+//!
+//! Half-block characters work everywhere: each cell shows two vertically stacked
+//! pixels using `▀` with separate foreground and background colours. Terminals that
+//! speak a graphics protocol (Kitty, iTerm2, Sixel) get the real image instead,
+//! written as an escape sequence over an area the view leaves blank.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use image::{imageops::FilterType, RgbImage};
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Environment variable forcing a protocol: `halfblocks`, `kitty`, `iterm2` or `sixel`
+pub const GRAPHICS_ENV: &str = "DROIDTUI_GRAPHICS";
+
+/// Cell size assumed when the terminal doesn't report its pixel size
+const DEFAULT_CELL_SIZE: (u32, u32) = (8, 16);
+
+/// How images are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    HalfBlocks,
+    Kitty,
+    Iterm2,
+    Sixel,
+}
+
+impl GraphicsProtocol {
+    /// Pick a protocol from the environment
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        Self::from_env(
+            &var(GRAPHICS_ENV),
+            &var("TERM"),
+            &var("TERM_PROGRAM"),
+            std::env::var_os("KITTY_WINDOW_ID").is_some(),
+        )
+    }
+
+    fn from_env(forced: &str, term: &str, term_program: &str, kitty_window: bool) -> Self {
+        match forced.to_lowercase().as_str() {
+            "halfblocks" | "blocks" => return GraphicsProtocol::HalfBlocks,
+            "kitty" => return GraphicsProtocol::Kitty,
+            "iterm2" | "iterm" => return GraphicsProtocol::Iterm2,
+            "sixel" => return GraphicsProtocol::Sixel,
+            _ => {}
+        }
+
+        if kitty_window || term == "xterm-kitty" || term_program == "ghostty" {
+            GraphicsProtocol::Kitty
+        } else if matches!(term_program, "iTerm.app" | "WezTerm") {
+            GraphicsProtocol::Iterm2
+        } else if term.contains("sixel") || matches!(term, "foot" | "mlterm") {
+            GraphicsProtocol::Sixel
+        } else {
+            GraphicsProtocol::HalfBlocks
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GraphicsProtocol::HalfBlocks => "half-blocks",
+            GraphicsProtocol::Kitty => "Kitty",
+            GraphicsProtocol::Iterm2 => "iTerm2",
+            GraphicsProtocol::Sixel => "Sixel",
+        }
+    }
+}
+
+/// Pixel size of a terminal cell
+pub fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns).max(1) as u32,
+            (size.height / size.rows).max(1) as u32,
+        ),
+        _ => DEFAULT_CELL_SIZE,
+    }
+}
+
+/// Largest cell area inside `area` that keeps the image's aspect ratio, centred
+pub fn fit_area(image: (u32, u32), cell: (u32, u32), area: Rect) -> Rect {
+    let (img_w, img_h) = (image.0.max(1) as f64, image.1.max(1) as f64);
+    let area_w = area.width as f64 * cell.0 as f64;
+    let area_h = area.height as f64 * cell.1 as f64;
+    let scale = (area_w / img_w).min(area_h / img_h);
+
+    let width = ((img_w * scale / cell.0 as f64) as u16).clamp(1, area.width.max(1));
+    let height = ((img_h * scale / cell.1 as f64) as u16).clamp(1, area.height.max(1));
+    Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    }
+}
+
+/// Draw `image` into `area` using half-block characters
+pub fn render_half_blocks(image: &RgbImage, area: Rect, buf: &mut Buffer) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+
+    // Half-block pixels are twice as tall as they are wide
+    let target = fit_area(image.dimensions(), (1, 2), area);
+    let scaled = image::imageops::resize(
+        image,
+        target.width as u32,
+        target.height as u32 * 2,
+        FilterType::Triangle,
+    );
+
+    for row in 0..target.height {
+        for col in 0..target.width {
+            let top = scaled.get_pixel(col as u32, row as u32 * 2);
+            let bottom = scaled.get_pixel(col as u32, row as u32 * 2 + 1);
+            if let Some(cell) = buf.cell_mut((target.x + col, target.y + row)) {
+                cell.set_char('▀')
+                    .set_fg(Color::Rgb(top[0], top[1], top[2]))
+                    .set_bg(Color::Rgb(bottom[0], bottom[1], bottom[2]));
+            }
+        }
+    }
+}
+
+/// Escape sequence drawing the image over `area` for graphics-capable terminals
+///
+/// `png` is the encoded image, passed through untouched where the protocol accepts it.
+/// Returns `None` for [`GraphicsProtocol::HalfBlocks`].
+pub fn encode(
+    protocol: GraphicsProtocol,
+    image: &RgbImage,
+    png: &[u8],
+    area: Rect,
+    cell: (u32, u32),
+) -> Option<String> {
+    match protocol {
+        GraphicsProtocol::HalfBlocks => None,
+        GraphicsProtocol::Kitty => Some(encode_kitty(png, area)),
+        GraphicsProtocol::Iterm2 => Some(encode_iterm2(png, area)),
+        GraphicsProtocol::Sixel => {
+            let scaled = image::imageops::resize(
+                image,
+                area.width as u32 * cell.0,
+                area.height as u32 * cell.1,
+                FilterType::Triangle,
+            );
+            Some(encode_sixel(&scaled))
+        }
+    }
+}
+
+/// Escape sequence drawing a video frame over `area`
+///
+/// The frame is scaled to the area's pixels first, so each frame stays small.
+/// Returns `None` for [`GraphicsProtocol::HalfBlocks`].
+pub fn encode_frame(
+    protocol: GraphicsProtocol,
+    frame: &RgbImage,
+    area: Rect,
+    cell: (u32, u32),
+) -> Option<String> {
+    if protocol == GraphicsProtocol::HalfBlocks {
+        return None;
+    }
+    let scaled = image::imageops::resize(
+        frame,
+        (area.width as u32 * cell.0).max(1),
+        (area.height as u32 * cell.1).max(1),
+        FilterType::Triangle,
+    );
+    let mut png = Vec::new();
+    if protocol != GraphicsProtocol::Sixel {
+        scaled
+            .write_to(&mut io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .ok()?;
+    }
+    encode(protocol, &scaled, &png, area, cell)
+}
+
+/// Escape sequence removing images drawn with `protocol`
+///
+/// Kitty keeps images on a separate layer; the others are cleared by redrawing cells.
+pub fn clear_sequence(protocol: GraphicsProtocol) -> Option<&'static str> {
+    match protocol {
+        GraphicsProtocol::Kitty => Some("\x1b_Ga=d,q=2\x1b\\"),
+        _ => None,
+    }
+}
+
+/// Write an escape sequence with the cursor at the top-left corner of `area`
+pub fn write_at(area: Rect, sequence: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    crossterm::queue!(
+        stdout,
+        crossterm::cursor::SavePosition,
+        crossterm::cursor::MoveTo(area.x, area.y)
+    )?;
+    stdout.write_all(sequence.as_bytes())?;
+    crossterm::queue!(stdout, crossterm::cursor::RestorePosition)?;
+    stdout.flush()
+}
+
+/// Kitty graphics protocol, PNG payload chunked into 4096 byte pieces
+fn encode_kitty(png: &[u8], area: Rect) -> String {
+    let payload = BASE64.encode(png);
+    let chunks: Vec<&str> = payload
+        .as_bytes()
+        .chunks(4096)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+
+    let mut out = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        if index == 0 {
+            out.push_str(&format!(
+                "\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={};{}\x1b\\",
+                area.width, area.height, more, chunk
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+/// iTerm2 inline image protocol
+fn encode_iterm2(png: &[u8], area: Rect) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1;doNotMoveCursor=1:{}\x07",
+        png.len(),
+        area.width,
+        area.height,
+        BASE64.encode(png)
+    )
+}
+
+/// Sixel encoding using a 6×6×6 colour cube
+fn encode_sixel(image: &RgbImage) -> String {
+    let (width, height) = image.dimensions();
+    let level = |v: u8| (v as u32 * 5 + 127) / 255;
+    let index_of = |pixel: &image::Rgb<u8>| {
+        (level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2])) as usize
+    };
+
+    let mut out = format!("\x1bPq\"1;1;{};{}", width, height);
+    for index in 0..216 {
+        let (r, g, b) = (index / 36, (index / 6) % 6, index % 6);
+        out.push_str(&format!("#{};2;{};{};{}", index, r * 20, g * 20, b * 20));
+    }
+
+    for band in (0..height).step_by(6) {
+        // Bit masks of each colour present in this band, one column at a time
+        let mut colours: HashMap<usize, Vec<u8>> = HashMap::new();
+        for x in 0..width {
+            for bit in 0..6 {
+                let y = band + bit;
+                if y >= height {
+                    break;
+                }
+                let mask = colours
+                    .entry(index_of(image.get_pixel(x, y)))
+                    .or_insert_with(|| vec![0; width as usize]);
+                mask[x as usize] |= 1 << bit;
+            }
+        }
+
+        let mut ordered: Vec<_> = colours.into_iter().collect();
+        ordered.sort_by_key(|(index, _)| *index);
+        for (position, (index, mask)) in ordered.iter().enumerate() {
+            if position > 0 {
+                out.push('$');
+            }
+            out.push_str(&format!("#{}", index));
+            push_sixel_run(&mut out, mask);
+        }
+        out.push('-');
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Append sixel characters for `mask`, run-length encoding repeats
+fn push_sixel_run(out: &mut String, mask: &[u8]) {
+    let mut iter = mask.iter().peekable();
+    while let Some(&bits) = iter.next() {
+        let mut count = 1;
+        while iter.peek() == Some(&&bits) {
+            iter.next();
+            count += 1;
+        }
+        let c = (b'?' + bits) as char;
+        if count > 3 {
+            out.push_str(&format!("!{}{}", count, c));
+        } else {
+            out.extend(std::iter::repeat_n(c, count));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_detection() {
+        let detect = GraphicsProtocol::from_env;
+        assert_eq!(
+            detect("", "xterm-kitty", "", false),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            detect("", "xterm-256color", "iTerm.app", false),
+            GraphicsProtocol::Iterm2
+        );
+        assert_eq!(detect("", "foot", "", false), GraphicsProtocol::Sixel);
+        assert_eq!(
+            detect("", "xterm-256color", "", false),
+            GraphicsProtocol::HalfBlocks
+        );
+        assert_eq!(
+            detect("halfblocks", "xterm-kitty", "", true),
+            GraphicsProtocol::HalfBlocks
+        );
+    }
+
+    #[test]
+    fn test_fit_area_keeps_aspect() {
+        let area = Rect::new(0, 0, 100, 50);
+        // Portrait phone screen in 8×16 cells
+        let fitted = fit_area((1080, 2400), (8, 16), area);
+        assert_eq!(fitted.height, 50);
+        assert_eq!(fitted.width, 45);
+        assert_eq!(fitted.x, 27);
+    }
+
+    #[test]
+    fn test_render_half_blocks() {
+        let mut image = RgbImage::new(2, 4);
+        for x in 0..2 {
+            image.put_pixel(x, 0, image::Rgb([255, 0, 0]));
+            image.put_pixel(x, 1, image::Rgb([255, 0, 0]));
+        }
+        let area = Rect::new(0, 0, 2, 2);
+        let mut buf = Buffer::empty(area);
+        render_half_blocks(&image, area, &mut buf);
+
+        let cell = &buf[(0, 0)];
+        assert_eq!(cell.symbol(), "▀");
+        assert_eq!(cell.fg, Color::Rgb(255, 0, 0));
+    }
+
+    #[test]
+    fn test_sixel_run_length() {
+        let mut out = String::new();
+        push_sixel_run(&mut out, &[1, 1, 1, 1, 1, 0]);
+        assert_eq!(out, "!5@?");
+
+        let image = RgbImage::new(2, 2);
+        let sixel = encode_sixel(&image);
+        assert!(sixel.starts_with("\x1bPq\"1;1;2;2"));
+        assert!(sixel.ends_with("-\x1b\\"));
+    }
+
+    #[test]
+    fn test_encode_frame() {
+        let frame = RgbImage::new(48, 96);
+        let area = Rect::new(0, 0, 4, 3);
+        assert!(encode_frame(GraphicsProtocol::HalfBlocks, &frame, area, (8, 16)).is_none());
+
+        let kitty = encode_frame(GraphicsProtocol::Kitty, &frame, area, (8, 16)).unwrap();
+        assert!(kitty.starts_with("\x1b_Ga=T,f=100,q=2,C=1,c=4,r=3,"));
+
+        // Scaled to the area's 32x48 pixels
+        let sixel = encode_frame(GraphicsProtocol::Sixel, &frame, area, (8, 16)).unwrap();
+        assert!(sixel.starts_with("\x1bPq\"1;1;32;48"));
+    }
+}
@@ -0,0 +1,352 @@
+//! Process manager view
+//!
+//! Lists processes from `ps` like `top` does. CPU usage is the share of total CPU
+//! time each process used since the previous refresh, from `/proc/<pid>/stat`.
+//! Processes can be signalled, and app processes force-stopped by package.
+
+use crate::adb::stats::CpuTimes;
+use crate::adb::{AdbCommand, ProcessEntry, ProcessSample, Signal};
+use crate::worker::{PollError, PollJob};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How often the process list refreshes
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Result of a background refresh
+#[derive(Debug, Clone)]
+pub struct ProcessEvent {
+    pub id: u64,
+    pub result: Result<ProcessSample, PollError>,
+}
+
+/// Column the process list is ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSort {
+    Cpu,
+    Memory,
+    Pid,
+    Name,
+    User,
+}
+
+impl ProcessSort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProcessSort::Cpu => "CPU",
+            ProcessSort::Memory => "RSS",
+            ProcessSort::Pid => "PID",
+            ProcessSort::Name => "Name",
+            ProcessSort::User => "User",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ProcessSort::Cpu => ProcessSort::Memory,
+            ProcessSort::Memory => ProcessSort::Pid,
+            ProcessSort::Pid => ProcessSort::Name,
+            ProcessSort::Name => ProcessSort::User,
+            ProcessSort::User => ProcessSort::Cpu,
+        }
+    }
+
+    /// Busiest first for usage columns, alphabetical or ascending otherwise
+    fn descending_by_default(&self) -> bool {
+        matches!(self, ProcessSort::Cpu | ProcessSort::Memory)
+    }
+}
+
+/// Actions available from a process's action panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAction {
+    Signal(Signal),
+    /// `am force-stop` the package owning the process
+    ForceStop,
+}
+
+impl ProcessAction {
+    /// Actions that apply to `process`
+    pub fn available(process: &ProcessEntry) -> Vec<ProcessAction> {
+        let mut actions = vec![
+            ProcessAction::Signal(Signal::Term),
+            ProcessAction::Signal(Signal::Kill),
+            ProcessAction::Signal(Signal::Stop),
+            ProcessAction::Signal(Signal::Cont),
+        ];
+        if process.package().is_some() {
+            actions.push(ProcessAction::ForceStop);
+        }
+        actions
+    }
+
+    pub fn label(&self, process: &ProcessEntry) -> String {
+        match self {
+            ProcessAction::Signal(Signal::Term) => "⏹️ Terminate (SIGTERM)".to_string(),
+            ProcessAction::Signal(Signal::Kill) => "💀 Kill (SIGKILL)".to_string(),
+            ProcessAction::Signal(Signal::Stop) => "⏸️ Suspend (SIGSTOP)".to_string(),
+            ProcessAction::Signal(Signal::Cont) => "▶️ Resume (SIGCONT)".to_string(),
+            ProcessAction::ForceStop => {
+                format!(
+                    "🛑 Force Stop {}",
+                    process.package().unwrap_or(&process.name)
+                )
+            }
+        }
+    }
+
+    /// ADB command implementing the action
+    pub fn command(&self, process: &ProcessEntry) -> AdbCommand {
+        match self {
+            ProcessAction::Signal(signal) => AdbCommand::KillProcess {
+                pid: process.pid,
+                signal: *signal,
+            },
+            ProcessAction::ForceStop => AdbCommand::ForceStop {
+                package_name: process.package().unwrap_or(&process.name).to_string(),
+            },
+        }
+    }
+}
+
+/// State of the process manager screen
+#[derive(Debug)]
+pub struct ProcessView {
+    pub entries: Vec<ProcessEntry>,
+    pub sort: ProcessSort,
+    pub descending: bool,
+    /// Case-insensitive filter on name and user
+    pub query: String,
+    /// Whether keystrokes go to the filter box
+    pub searching: bool,
+    /// Selection follows the process across refreshes
+    pub selected_pid: Option<u32>,
+    /// Highlighted action while the action panel is open
+    pub action: Option<usize>,
+    pub status: Option<String>,
+    pub poll: Option<PollJob>,
+    /// Action running in the background, with the process it acts on
+    pub running: Option<(PollJob, ProcessEntry)>,
+    last_poll: Option<Instant>,
+    /// CPU counters of the previous refresh, for usage deltas
+    previous: Option<(CpuTimes, HashMap<u32, u64>)>,
+}
+
+impl Default for ProcessView {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            sort: ProcessSort::Cpu,
+            descending: true,
+            query: String::new(),
+            searching: false,
+            selected_pid: None,
+            action: None,
+            status: None,
+            poll: None,
+            running: None,
+            last_poll: None,
+            previous: None,
+        }
+    }
+}
+
+impl ProcessView {
+    /// Whether a refresh should be requested at `now`
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.poll.is_none()
+            && self
+                .last_poll
+                .is_none_or(|last| now.duration_since(last) >= REFRESH_INTERVAL)
+    }
+
+    /// Remember that a refresh started at `now`
+    pub fn start_poll(&mut self, poll: PollJob, now: Instant) {
+        self.poll = Some(poll);
+        self.last_poll = Some(now);
+    }
+
+    /// Refresh on the next tick, e.g. after killing a process
+    pub fn refresh_soon(&mut self) {
+        self.last_poll = None;
+    }
+
+    /// Replace the list with a new sample, working out CPU usage since the last one
+    pub fn apply(&mut self, sample: ProcessSample) {
+        let mut entries = sample.processes;
+        if let (Some((cpu_before, ticks_before)), Some(cpu)) = (&self.previous, &sample.cpu) {
+            let elapsed = cpu.total.saturating_sub(cpu_before.total);
+            if elapsed > 0 {
+                for entry in &mut entries {
+                    if let (Some(before), Some(after)) =
+                        (ticks_before.get(&entry.pid), sample.ticks.get(&entry.pid))
+                    {
+                        let used = after.saturating_sub(*before);
+                        entry.cpu = Some(used as f32 * 100.0 / elapsed as f32);
+                    }
+                }
+            }
+        }
+        self.previous = sample.cpu.map(|cpu| (cpu, sample.ticks));
+        self.entries = entries;
+
+        if self.action.is_some() && self.selected_process().is_none() {
+            self.action = None;
+            self.status = Some("Process exited".to_string());
+        }
+    }
+
+    /// Processes matching the filter, in sort order
+    pub fn visible(&self) -> Vec<&ProcessEntry> {
+        let query = self.query.to_lowercase();
+        let mut visible: Vec<&ProcessEntry> = self
+            .entries
+            .iter()
+            .filter(|p| {
+                query.is_empty()
+                    || p.name.to_lowercase().contains(&query)
+                    || p.user.to_lowercase().contains(&query)
+            })
+            .collect();
+
+        visible.sort_by(|a, b| {
+            let order = match self.sort {
+                ProcessSort::Cpu => a.cpu.unwrap_or(0.0).total_cmp(&b.cpu.unwrap_or(0.0)),
+                ProcessSort::Memory => a.rss_kb.cmp(&b.rss_kb),
+                ProcessSort::Pid => a.pid.cmp(&b.pid),
+                ProcessSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                ProcessSort::User => a.user.cmp(&b.user),
+            };
+            let order = if self.descending {
+                order.reverse()
+            } else {
+                order
+            };
+            order.then(a.pid.cmp(&b.pid))
+        });
+        visible
+    }
+
+    /// Row of the selected process in [`ProcessView::visible`], defaulting to the top
+    pub fn selected_index(&self) -> usize {
+        let visible = self.visible();
+        self.selected_pid
+            .and_then(|pid| visible.iter().position(|p| p.pid == pid))
+            .unwrap_or(0)
+    }
+
+    pub fn selected_process(&self) -> Option<&ProcessEntry> {
+        let visible = self.visible();
+        match self.selected_pid {
+            Some(pid) => visible.into_iter().find(|p| p.pid == pid),
+            None => visible.first().copied(),
+        }
+    }
+
+    /// Move the selection, or the highlighted action while the panel is open
+    pub fn move_by(&mut self, delta: isize) {
+        if let Some(action) = self.action {
+            let count = self
+                .selected_process()
+                .map(|p| ProcessAction::available(p).len())
+                .unwrap_or(0);
+            self.action = Some(
+                action
+                    .saturating_add_signed(delta)
+                    .min(count.saturating_sub(1)),
+            );
+            return;
+        }
+
+        let visible = self.visible();
+        if visible.is_empty() {
+            return;
+        }
+        let index = self
+            .selected_index()
+            .saturating_add_signed(delta)
+            .min(visible.len() - 1);
+        self.selected_pid = Some(visible[index].pid);
+    }
+
+    /// Switch to the next sort column
+    pub fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.descending = self.sort.descending_by_default();
+    }
+
+    /// Highlighted action, if the panel is open
+    pub fn selected_action(&self) -> Option<ProcessAction> {
+        let process = self.selected_process()?;
+        ProcessAction::available(process).get(self.action?).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adb::ps::parse_ps;
+    use crate::adb::ps::tests::{PS, STAT};
+
+    fn sample(cpu_total: u64, launcher_ticks: u64) -> ProcessSample {
+        let stat = STAT.replace("300 100", &format!("{} 0", launcher_ticks));
+        ProcessSample::parse(&format!(
+            "{}--droidtui--\ncpu  {} 0 0 0 0 0 0 0\n--droidtui--\n{}",
+            PS, cpu_total, stat
+        ))
+    }
+
+    #[test]
+    fn test_package() {
+        let processes = parse_ps(PS);
+        assert_eq!(processes[0].package(), None);
+        assert_eq!(processes[1].package(), None);
+        assert_eq!(processes[4].package(), Some("com.google.android.gms"));
+        assert_eq!(ProcessAction::available(&processes[4]).len(), 5);
+        assert_eq!(
+            ProcessAction::ForceStop.label(&processes[4]),
+            "🛑 Force Stop com.google.android.gms"
+        );
+    }
+
+    #[test]
+    fn test_cpu_usage_and_sorting() {
+        let mut view = ProcessView::default();
+        view.apply(sample(1000, 400));
+        assert!(view.entries.iter().all(|p| p.cpu.is_none()));
+
+        view.apply(sample(1200, 450));
+        let launcher = view.visible()[0];
+        assert_eq!(launcher.pid, 2590);
+        assert_eq!(launcher.cpu, Some(25.0));
+
+        view.cycle_sort();
+        assert_eq!(view.sort, ProcessSort::Memory);
+        assert_eq!(view.visible()[0].pid, 2590);
+        view.cycle_sort();
+        assert_eq!(view.visible()[0].pid, 1);
+
+        view.query = "u0_a145".to_string();
+        assert_eq!(view.visible().len(), 2);
+    }
+
+    #[test]
+    fn test_selection_follows_pid() {
+        let mut view = ProcessView {
+            sort: ProcessSort::Pid,
+            descending: false,
+            ..Default::default()
+        };
+        view.apply(sample(1000, 400));
+        view.move_by(2);
+        assert_eq!(view.selected_process().map(|p| p.pid), Some(2467));
+
+        view.descending = true;
+        assert_eq!(view.selected_process().map(|p| p.pid), Some(2467));
+        assert_eq!(view.selected_index(), 2);
+
+        view.action = Some(0);
+        view.move_by(10);
+        assert_eq!(view.selected_action(), Some(ProcessAction::ForceStop));
+    }
+}
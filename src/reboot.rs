@@ -0,0 +1,89 @@
+//! Rebooting a device and waiting for it to come back
+//!
+//! The reboot itself and the `wait-for` request run as two background polls;
+//! [`RebootWatch`] tracks which one is in flight for the status screen.
+
+use crate::adb::RebootTarget;
+use crate::worker::PollJob;
+use std::time::{Duration, Instant};
+
+/// Result of a reboot step, tagged with the poll that produced it
+///
+/// The reboot step reports the device's serial, the wait step a status line.
+#[derive(Debug, Clone)]
+pub struct RebootEvent {
+    pub id: u64,
+    pub result: Result<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebootPhase {
+    /// The `reboot:` service is open until the device goes down
+    Rebooting,
+    /// Waiting for the device to show up again
+    Waiting,
+}
+
+/// A reboot in progress
+#[derive(Debug)]
+pub struct RebootWatch {
+    pub target: RebootTarget,
+    /// Device being rebooted, once known
+    pub serial: Option<String>,
+    pub phase: RebootPhase,
+    pub started: Instant,
+    /// Step in flight; dropping it stops waiting
+    pub poll: Option<PollJob>,
+}
+
+impl RebootWatch {
+    pub fn new(target: RebootTarget, serial: Option<String>) -> Self {
+        Self {
+            target,
+            serial,
+            phase: RebootPhase::Rebooting,
+            started: Instant::now(),
+            poll: None,
+        }
+    }
+
+    /// Time since the reboot was sent, in whole seconds
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        Duration::from_secs(now.duration_since(self.started).as_secs())
+    }
+
+    /// One line describing the current step
+    pub fn status(&self) -> String {
+        let device = self.serial.as_deref().unwrap_or("device");
+        match (self.phase, self.target.wait_state()) {
+            (RebootPhase::Rebooting, _) => {
+                format!("Rebooting {} into {}", device, self.target.name())
+            }
+            (RebootPhase::Waiting, Some(state)) => {
+                format!("Waiting for {} to come back as '{}'", device, state)
+            }
+            (RebootPhase::Waiting, None) => format!("{} left adb", device),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reboot_status() {
+        let mut watch = RebootWatch::new(RebootTarget::Recovery, None);
+        assert_eq!(watch.status(), "Rebooting device into recovery");
+
+        watch.serial = Some("emulator-5554".to_string());
+        watch.phase = RebootPhase::Waiting;
+        assert_eq!(
+            watch.status(),
+            "Waiting for emulator-5554 to come back as 'recovery'"
+        );
+
+        let later = watch.started + Duration::from_millis(2500);
+        assert_eq!(watch.elapsed(later), Duration::from_secs(2));
+    }
+}
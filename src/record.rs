@@ -0,0 +1,334 @@
+//! Screen recording to a local file
+//!
+//! `screenrecord --output-format=h264` streams through adbd's `exec:` service into
+//! ffmpeg, which remuxes it into MP4 without re-encoding. Without ffmpeg the raw
+//! H.264 stream is saved instead. The same stream can be decoded into the minifb
+//! window while it records.
+
+use crate::adb::format_size;
+use crate::adb::screencap::unused_path;
+use crate::adb::{AdbError, AdbManager, AdbResult, RecordOptions, MAX_DURATION};
+use crate::event::Event;
+use crate::input::WindowInput;
+use crate::stream::{show_frames, spawn_decoder};
+use crate::worker::{spawn_poll, PollJob};
+use chrono::{DateTime, Local};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// A finished recording
+#[derive(Debug, Clone)]
+pub struct RecordingSummary {
+    pub path: PathBuf,
+    /// Size of the H.264 stream received
+    pub bytes: u64,
+    pub duration: Duration,
+    /// Whether ffmpeg remuxed it into MP4; otherwise the file is raw H.264
+    pub mp4: bool,
+}
+
+impl RecordingSummary {
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "Recording saved to {}\n{} in {}s",
+            self.path.display(),
+            format_size(self.bytes),
+            self.duration.as_secs()
+        );
+        if !self.mp4 {
+            text.push_str("\n\nffmpeg was not found, so this is a raw H.264 stream. Install ffmpeg to get MP4 files.");
+        }
+        text
+    }
+}
+
+/// Result of a background recording
+#[derive(Debug, Clone)]
+pub struct RecordingEvent {
+    pub id: u64,
+    pub result: Result<RecordingSummary, String>,
+}
+
+/// A recording running in the background
+#[derive(Debug)]
+pub struct Recording {
+    pub options: RecordOptions,
+    /// Directory the file is written to
+    pub dir: PathBuf,
+    pub started: Instant,
+    /// Bytes of video received so far
+    written: Arc<AtomicU64>,
+    /// Stop was requested and the file is being finished
+    pub stopping: bool,
+    pub poll: PollJob,
+}
+
+impl Recording {
+    /// Record on a worker thread, reporting through `sender` when done
+    pub fn start(
+        manager: &AdbManager,
+        options: RecordOptions,
+        sender: UnboundedSender<Event>,
+    ) -> Self {
+        let written = Arc::new(AtomicU64::new(0));
+        let poll = {
+            let options = options.clone();
+            let written = written.clone();
+            spawn_poll(
+                manager,
+                sender,
+                None,
+                move |manager| record(manager, &options, &written),
+                |id, result| {
+                    Event::Recording(RecordingEvent {
+                        id,
+                        result: result.map_err(|e| e.message),
+                    })
+                },
+            )
+        };
+        Self {
+            options,
+            dir: manager.screenshot_dir().to_path_buf(),
+            started: Instant::now(),
+            written,
+            stopping: false,
+            poll,
+        }
+    }
+
+    /// Stop recording; the summary arrives once the file is finished
+    pub fn stop(&mut self) {
+        self.stopping = true;
+        self.poll.cancel();
+    }
+
+    pub fn written(&self) -> u64 {
+        self.written.load(Ordering::Relaxed)
+    }
+}
+
+/// File name for a recording started at `time`, e.g. `recording-20240131-142502.mp4`
+pub fn recording_file_name(time: DateTime<Local>, extension: &str) -> String {
+    format!("recording-{}.{}", time.format("%Y%m%d-%H%M%S"), extension)
+}
+
+/// Record into the screenshot directory until the time limit, cancellation or the window closing
+///
+/// `written` counts the bytes received, for progress displays.
+pub fn record(
+    manager: &mut AdbManager,
+    options: &RecordOptions,
+    written: &AtomicU64,
+) -> AdbResult<RecordingSummary> {
+    let dir = manager.screenshot_dir().to_path_buf();
+    fs::create_dir_all(&dir)?;
+    let (mut sink, path) = Sink::open(&dir, Local::now())?;
+    let started = Instant::now();
+
+    let mut decoder = match options.show_window {
+        true => Some(spawn_decoder().map_err(AdbError::CommandFailed)?),
+        false => None,
+    };
+    let finished = Arc::new(AtomicBool::new(false));
+    let window = decoder.as_mut().and_then(|decoder| {
+        let frames = decoder.stdout.take()?;
+        let (width, height) = (options.config.width, options.config.height);
+        let input = WindowInput::for_device(manager, (width, height));
+        let cancel = manager.cancel_token();
+        let finished = finished.clone();
+        Some(thread::spawn(move || {
+            let result = show_frames(width, height, frames, Some(input));
+            // Closing the window stops the recording
+            if result.is_err() || !finished.load(Ordering::SeqCst) {
+                cancel.cancel();
+            }
+            result
+        }))
+    });
+
+    let mut tee = Tee {
+        sink: sink.writer(),
+        decoder: decoder.as_mut().and_then(|decoder| decoder.stdin.take()),
+        written,
+    };
+    let result = manager.record_screen(
+        &options.config,
+        options.max_duration.min(MAX_DURATION),
+        &mut tee,
+    );
+    drop(tee);
+    finished.store(true, Ordering::SeqCst);
+
+    let window_result = window.map(|window| window.join().unwrap_or(Ok(())));
+    if let Some(mut decoder) = decoder {
+        let _ = decoder.kill();
+        let _ = decoder.wait();
+    }
+
+    let bytes = written.load(Ordering::Relaxed);
+    if bytes == 0 {
+        drop(sink);
+        let _ = fs::remove_file(&path);
+        return Err(match result {
+            Err(e) => e,
+            Ok(()) => AdbError::CommandFailed("screenrecord sent no video".to_string()),
+        });
+    }
+    let mp4 = sink.finish()?;
+
+    match (result, window_result) {
+        // Stopping is how most recordings end
+        (Ok(()) | Err(AdbError::Cancelled), None | Some(Ok(()))) => Ok(RecordingSummary {
+            path,
+            bytes,
+            duration: started.elapsed(),
+            mp4,
+        }),
+        (_, Some(Err(e))) => Err(AdbError::CommandFailed(format!(
+            "{} (recorded so far: {})",
+            e,
+            path.display()
+        ))),
+        (Err(e), _) => Err(e),
+    }
+}
+
+/// Where the H.264 stream is written
+enum Sink {
+    /// ffmpeg remuxing its stdin into an MP4 file
+    Mp4 { ffmpeg: Child, stdin: ChildStdin },
+    /// Raw H.264 when ffmpeg is not installed
+    Raw(File),
+}
+
+impl Sink {
+    fn open(dir: &Path, time: DateTime<Local>) -> io::Result<(Self, PathBuf)> {
+        let path = unused_path(dir, &recording_file_name(time, "mp4"));
+        // screenrecord only sends frames when the screen changes, so arrival time is the clock
+        let spawned = Command::new("ffmpeg")
+            .args([
+                "-loglevel",
+                "error",
+                "-use_wallclock_as_timestamps",
+                "1",
+                "-f",
+                "h264",
+                "-i",
+                "pipe:0",
+                "-c",
+                "copy",
+                "-movflags",
+                "+faststart",
+                "-y",
+            ])
+            .arg(&path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        match spawned {
+            Ok(mut ffmpeg) => {
+                let stdin = ffmpeg
+                    .stdin
+                    .take()
+                    .ok_or_else(|| io::Error::other("ffmpeg has no stdin"))?;
+                Ok((Sink::Mp4 { ffmpeg, stdin }, path))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let path = unused_path(dir, &recording_file_name(time, "h264"));
+                Ok((Sink::Raw(File::create(&path)?), path))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Sink::Mp4 { stdin, .. } => stdin,
+            Sink::Raw(file) => file,
+        }
+    }
+
+    /// Close the stream and wait for the file to be complete; returns whether it is MP4
+    fn finish(self) -> io::Result<bool> {
+        match self {
+            Sink::Mp4 { mut ffmpeg, stdin } => {
+                drop(stdin);
+                let status = ffmpeg.wait()?;
+                if status.success() {
+                    Ok(true)
+                } else {
+                    Err(io::Error::other(format!("ffmpeg exited with {}", status)))
+                }
+            }
+            Sink::Raw(mut file) => {
+                file.flush()?;
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// Copies the stream to the sink and, while it lasts, the window's decoder
+struct Tee<'a> {
+    sink: &'a mut dyn Write,
+    decoder: Option<ChildStdin>,
+    written: &'a AtomicU64,
+}
+
+impl Write for Tee<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.sink.write_all(buf)?;
+        // The decoder going away is not a recording error
+        if let Some(decoder) = &mut self.decoder {
+            if decoder.write_all(buf).is_err() {
+                self.decoder = None;
+            }
+        }
+        self.written.fetch_add(buf.len() as u64, Ordering::Relaxed);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.sink.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_recording_file_name() {
+        let time = Local.with_ymd_and_hms(2024, 1, 31, 14, 25, 2).unwrap();
+        assert_eq!(
+            recording_file_name(time, "mp4"),
+            "recording-20240131-142502.mp4"
+        );
+    }
+
+    #[test]
+    fn test_tee_counts_bytes() {
+        let written = AtomicU64::new(0);
+        let mut out = Vec::new();
+        let mut tee = Tee {
+            sink: &mut out,
+            decoder: None,
+            written: &written,
+        };
+        tee.write_all(b"\0\0\0\x01h264").unwrap();
+        drop(tee);
+        assert_eq!(out, b"\0\0\0\x01h264");
+        assert_eq!(written.load(Ordering::Relaxed), 8);
+    }
+}
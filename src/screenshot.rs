@@ -0,0 +1,25 @@
+//! Screenshot preview
+//!
+//! Captures saved by the adb layer are kept decoded in memory for the in-TUI preview.
+
+use image::RgbImage;
+use std::path::PathBuf;
+
+/// A saved screenshot ready to preview
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    pub path: PathBuf,
+    /// Encoded PNG, sent as-is to terminals that accept PNG (Kitty, iTerm2)
+    pub png: Vec<u8>,
+    pub image: RgbImage,
+}
+
+impl Screenshot {
+    /// Decode a PNG saved at `path`
+    pub fn decode(path: PathBuf, png: Vec<u8>) -> Result<Self, String> {
+        let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to decode screenshot: {}", e))?
+            .to_rgb8();
+        Ok(Self { path, png, image })
+    }
+}
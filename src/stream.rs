@@ -1,115 +1,179 @@
+//! Screen streaming into a separate window
+//!
+//! `screenrecord` runs through adbd's `exec:` service on the selected device, so
+//! no `adb` binary is needed; ffmpeg decodes the H.264 stream for the minifb window.
+
+use crate::adb::{AdbError, AdbManager, AdbResult, StreamConfig, MAX_DURATION};
+use crate::event::Event;
+use crate::input::WindowInput;
+use crate::worker::{spawn_poll, PollJob};
 use minifb::{Key, Window, WindowOptions};
-use std::io::{BufReader, Read};
+use std::io::{self, BufReader, Read, Write};
 use std::process::{Child, Command, Stdio};
-use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::mpsc::{sync_channel, TryRecvError};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
 
-/// Configuration for screen streaming
-#[derive(Debug, Clone)]
-pub struct StreamConfig {
-    /// Window width
-    pub width: usize,
-    /// Window height
-    pub height: usize,
-    /// Video bitrate
-    pub bitrate: String,
-}
-
-impl Default for StreamConfig {
-    fn default() -> Self {
-        Self {
-            width: 1080,
-            height: 1920,
-            bitrate: "8M".to_string(),
-        }
-    }
-}
+/// Shown when ffmpeg can't be found
+const FFMPEG_MISSING: &str = "ffmpeg was not found in PATH. Install it to decode the screen, e.g. `sudo apt install ffmpeg` or `brew install ffmpeg`.";
 
-/// Stream control messages
-pub enum StreamControl {
-    Stop,
+/// Lifecycle of a stream window
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    /// The decoder is running and video is on its way; the window opens on its own thread
+    Started,
+    /// The window closed or the stream failed
+    Ended { id: u64, result: Result<(), String> },
 }
 
-/// Stream state
+/// A stream window running in the background; dropping it stops the stream
 #[derive(Debug)]
 pub struct StreamState {
-    adb_process: Option<Child>,
-    ffmpeg_process: Option<Child>,
-    control_tx: Option<Sender<StreamControl>>,
-    stream_thread: Option<thread::JoinHandle<()>>,
+    pub config: StreamConfig,
+    /// Device streamed, when one is selected
+    pub serial: Option<String>,
+    /// The window is up
+    pub started: bool,
+    pub poll: PollJob,
 }
 
-impl Default for StreamState {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Start streaming the selected device into a window
+///
+/// Fails up front when ffmpeg is missing; everything after that is reported
+/// through `sender`.
+pub fn start_stream(
+    manager: &AdbManager,
+    config: StreamConfig,
+    sender: UnboundedSender<Event>,
+) -> Result<StreamState, String> {
+    check_ffmpeg()?;
+    let poll = {
+        let config = config.clone();
+        let started = sender.clone();
+        spawn_poll(
+            manager,
+            sender,
+            None,
+            move |manager| run_stream(manager, &config, &started),
+            |id, result| {
+                Event::Stream(StreamEvent::Ended {
+                    id,
+                    result: result.map_err(|e| e.message),
+                })
+            },
+        )
+    };
+    Ok(StreamState {
+        config,
+        serial: manager.selected_device().map(str::to_string),
+        started: false,
+        poll,
+    })
 }
 
-impl StreamState {
-    pub fn new() -> Self {
-        Self {
-            adb_process: None,
-            ffmpeg_process: None,
-            control_tx: None,
-            stream_thread: None,
-        }
-    }
-
-    pub fn stop(&mut self) {
-        // Send stop signal
-        if let Some(tx) = self.control_tx.take() {
-            let _ = tx.send(StreamControl::Stop);
-        }
-
-        // Wait for thread to finish
-        if let Some(handle) = self.stream_thread.take() {
-            let _ = handle.join();
-        }
-
-        // Kill processes
-        if let Some(mut proc) = self.adb_process.take() {
-            let _ = proc.kill();
-        }
-        if let Some(mut proc) = self.ffmpeg_process.take() {
-            let _ = proc.kill();
-        }
+/// Check that ffmpeg can be run
+pub fn check_ffmpeg() -> Result<(), String> {
+    match Command::new("ffmpeg")
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+    {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(FFMPEG_MISSING.to_string()),
+        Err(e) => Err(format!("Failed to run ffmpeg: {}", e)),
     }
 }
 
-impl Drop for StreamState {
-    fn drop(&mut self) {
-        self.stop();
+/// Stream into the window until it is closed or the stream is cancelled
+fn run_stream(
+    manager: &mut AdbManager,
+    config: &StreamConfig,
+    sender: &UnboundedSender<Event>,
+) -> AdbResult<()> {
+    let (width, height) = (config.width, config.height);
+    let input = WindowInput::for_device(manager, (width, height));
+    let mut decoder = spawn_decoder().map_err(AdbError::CommandFailed)?;
+    let (Some(mut video), Some(frames)) = (decoder.stdin.take(), decoder.stdout.take()) else {
+        let _ = decoder.kill();
+        return Err(AdbError::CommandFailed("ffmpeg has no pipes".to_string()));
+    };
+
+    // Closing the window stops the stream
+    let cancel = manager.cancel_token();
+    let window = thread::spawn(move || {
+        let result = show_frames(width, height, frames, Some(input));
+        cancel.cancel();
+        result
+    });
+    let _ = sender.send(Event::Stream(StreamEvent::Started));
+
+    let result = stream_screen(manager, config, &mut video);
+    drop(video);
+    let _ = decoder.kill();
+    let _ = decoder.wait();
+    let window_result = window.join().unwrap_or(Ok(()));
+
+    match (result, window_result) {
+        (_, Err(e)) => Err(AdbError::CommandFailed(format!(
+            "{}. Without a display, use Mirror in Terminal instead.",
+            e
+        ))),
+        (result, Ok(())) => result,
     }
 }
 
-/// Start screen streaming in a separate window
-pub fn start_stream(config: StreamConfig) -> Result<StreamState, String> {
-    let (control_tx, control_rx) = channel();
-
-    // Spawn streaming thread
-    let stream_thread = thread::spawn(move || {
-        if let Err(e) = run_stream(config, control_rx) {
-            eprintln!("Stream error: {}", e);
+/// Send the `screenrecord` stream to `out` until cancelled
+///
+/// `screenrecord` stops at its time limit, so it is restarted into the same output.
+pub(crate) fn stream_screen(
+    manager: &mut AdbManager,
+    config: &StreamConfig,
+    out: &mut impl Write,
+) -> AdbResult<()> {
+    loop {
+        let started = Instant::now();
+        match manager.record_screen(config, MAX_DURATION, out) {
+            // Ending well before the time limit means screenrecord refused to run
+            Ok(()) if started.elapsed() < Duration::from_secs(1) => {
+                return Err(AdbError::CommandFailed(
+                    "screenrecord stopped right away".to_string(),
+                ))
+            }
+            Ok(()) => continue,
+            Err(AdbError::Cancelled) => return Ok(()),
+            Err(e) => return Err(e),
         }
-    });
-
-    Ok(StreamState {
-        adb_process: None,
-        ffmpeg_process: None,
-        control_tx: Some(control_tx),
-        stream_thread: Some(stream_thread),
-    })
+    }
 }
 
-/// Main streaming loop
-fn run_stream(config: StreamConfig, control_rx: Receiver<StreamControl>) -> Result<(), String> {
-    // Get device resolution
-    let resolution = get_device_resolution()?;
-
-    // Use device resolution or config
-    let (width, height) = resolution;
+/// Start ffmpeg decoding H.264 from stdin into RGB24 frames on stdout
+pub(crate) fn spawn_decoder() -> Result<Child, String> {
+    Command::new("ffmpeg")
+        .args([
+            "-f", "h264", "-i", "pipe:0", "-f", "rawvideo", "-pix_fmt", "rgb24", "pipe:1",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => FFMPEG_MISSING.to_string(),
+            _ => format!("Failed to start ffmpeg: {}", e),
+        })
+}
 
+/// Show RGB24 frames of `width`x`height` in a window
+///
+/// Returns when the window is closed, Esc is pressed or the frames run out. With
+/// `input`, mouse and keys go to the device; without it Q closes too.
+pub(crate) fn show_frames(
+    width: usize,
+    height: usize,
+    frames: impl Read + Send + 'static,
+    mut input: Option<WindowInput>,
+) -> Result<(), String> {
     // Create window
     let mut window = Window::new(
         "DroidTUI - Screen Stream",
@@ -125,90 +189,41 @@ fn run_stream(config: StreamConfig, control_rx: Receiver<StreamControl>) -> Resu
 
     // Limit update rate
     window.set_target_fps(60);
+    if let Some(input) = &input {
+        input.attach(&mut window);
+    }
 
-    // Start ADB screen recording
-    let mut adb_child = Command::new("adb")
-        .args([
-            "exec-out",
-            "screenrecord",
-            "--output-format=h264",
-            "--size",
-            &format!("{}x{}", width, height),
-            "--bit-rate",
-            &config.bitrate,
-            "-",
-        ])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .spawn()
-        .map_err(|e| format!("Failed to start screenrecord: {}", e))?;
-
-    let stdout = adb_child.stdout.take().ok_or("Failed to get stdout")?;
-
-    // Start FFmpeg to decode H.264
-    let mut ffmpeg_child = Command::new("ffmpeg")
-        .args([
-            "-f", "h264", "-i", "pipe:0", "-f", "rawvideo", "-pix_fmt", "rgb24", "pipe:1",
-        ])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .spawn()
-        .map_err(|e| format!("Failed to start ffmpeg: {}", e))?;
-
-    let mut ffmpeg_stdin = ffmpeg_child
-        .stdin
-        .take()
-        .ok_or("Failed to get ffmpeg stdin")?;
-
-    let ffmpeg_stdout = ffmpeg_child
-        .stdout
-        .take()
-        .ok_or("Failed to get ffmpeg stdout")?;
-
-    // Thread to pipe ADB output to FFmpeg
-    let pipe_thread = thread::spawn(move || {
-        let mut reader = BufReader::new(stdout);
-        let mut buffer = vec![0u8; 8192];
+    // Frames only arrive when the screen changes, so they are read on their own
+    // thread and the window keeps handling input in between
+    let frame_size = width * height * 3; // RGB24
+    let (frame_tx, frame_rx) = sync_channel::<Vec<u8>>(2);
+    thread::spawn(move || {
+        let mut reader = BufReader::new(frames);
         loop {
-            match reader.read(&mut buffer) {
-                Ok(0) => break,
-                Ok(n) => {
-                    if std::io::Write::write_all(&mut ffmpeg_stdin, &buffer[..n]).is_err() {
-                        break;
-                    }
-                }
-                Err(_) => break,
+            let mut frame = vec![0u8; frame_size];
+            if reader.read_exact(&mut frame).is_err() || frame_tx.send(frame).is_err() {
+                break;
             }
         }
     });
 
     // Main display loop
-    let frame_size = width * height * 3; // RGB24
-    let mut frame_buffer = vec![0u8; frame_size];
     let mut display_buffer: Vec<u32> = vec![0; width * height];
-    let mut reader = BufReader::new(ffmpeg_stdout);
 
     loop {
-        // Check for control messages
-        if let Ok(StreamControl::Stop) = control_rx.try_recv() {
-            break;
-        }
-
         // Check if window is still open
-        if !window.is_open() || window.is_key_down(Key::Escape) || window.is_key_down(Key::Q) {
+        if !window.is_open()
+            || window.is_key_down(Key::Escape)
+            || (input.is_none() && window.is_key_down(Key::Q))
+        {
             break;
         }
 
-        // Read frame from FFmpeg
-        match reader.read_exact(&mut frame_buffer) {
-            Ok(_) => {
+        match frame_rx.try_recv() {
+            Ok(frame) => {
                 // Convert RGB24 to ARGB32 for minifb
-                for i in 0..(width * height) {
-                    let r = frame_buffer[i * 3] as u32;
-                    let g = frame_buffer[i * 3 + 1] as u32;
-                    let b = frame_buffer[i * 3 + 2] as u32;
-                    display_buffer[i] = (r << 16) | (g << 8) | b;
+                for (pixel, rgb) in display_buffer.iter_mut().zip(frame.chunks_exact(3)) {
+                    *pixel = (rgb[0] as u32) << 16 | (rgb[1] as u32) << 8 | rgb[2] as u32;
                 }
 
                 // Update window
@@ -216,65 +231,16 @@ fn run_stream(config: StreamConfig, control_rx: Receiver<StreamControl>) -> Resu
                     .update_with_buffer(&display_buffer, width, height)
                     .map_err(|e| format!("Failed to update window: {}", e))?;
             }
-            Err(_) => {
-                // No frame available, just update window
-                window.update();
-                thread::sleep(Duration::from_millis(10));
-            }
+            // No new frame; keep the window responsive (paced by the target fps)
+            Err(TryRecvError::Empty) => window.update(),
+            // The decoder exited: the stream is over
+            Err(TryRecvError::Disconnected) => break,
         }
-    }
-
-    // Cleanup
-    let _ = adb_child.kill();
-    let _ = ffmpeg_child.kill();
-    let _ = pipe_thread.join();
-
-    Ok(())
-}
-
-/// Get device screen resolution
-fn get_device_resolution() -> Result<(usize, usize), String> {
-    let output = Command::new("adb")
-        .args(["shell", "wm", "size"])
-        .output()
-        .map_err(|e| format!("Failed to get device resolution: {}", e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
 
-    // Parse output like "Physical size: 1080x1920"
-    for line in stdout.lines() {
-        if line.contains("Physical size:") || line.contains("Override size:") {
-            if let Some(size_part) = line.split(':').nth(1) {
-                let size_str = size_part.trim();
-                if let Some((w, h)) = size_str.split_once('x') {
-                    if let (Ok(width), Ok(height)) = (w.parse(), h.parse()) {
-                        return Ok((width, height));
-                    }
-                }
-            }
+        if let Some(input) = &mut input {
+            input.poll(&window);
         }
     }
 
-    // Default fallback
-    Ok((1080, 1920))
-}
-
-/// Quick screenshot using existing method (for fallback)
-pub async fn capture_screenshot() -> Result<Vec<u8>, String> {
-    let output = tokio::process::Command::new("adb")
-        .args(["exec-out", "screencap", "-p"])
-        .output()
-        .await
-        .map_err(|e| format!("Failed to capture screenshot: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("screencap failed: {}", stderr));
-    }
-
-    if output.stdout.is_empty() {
-        return Err("No screenshot data received".to_string());
-    }
-
-    Ok(output.stdout)
+    Ok(())
 }
@@ -0,0 +1,177 @@
+//! Templated custom commands
+//!
+//! Shell commands from the config may contain `{name}` placeholders such as
+//! `{package}` or `{serial}`. The user is asked for each value before the command
+//! runs; values are shell-quoted when substituted. `${var}` is left to the shell.
+
+use crate::adb::shell_quote;
+
+/// Piece of a command template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn segments(template: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    let mut text_start = 0;
+    let mut offset = 0;
+
+    while let Some(open) = rest.find('{') {
+        let start = offset + open;
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            break;
+        };
+        let name = &after[..close];
+        let end = start + 1 + close + 1;
+
+        if is_name(name) && !template[..start].ends_with('$') {
+            if text_start < start {
+                segments.push(Segment::Text(&template[text_start..start]));
+            }
+            segments.push(Segment::Placeholder(name));
+            text_start = end;
+            offset = end;
+        } else {
+            offset = start + 1;
+        }
+        rest = &template[offset..];
+    }
+
+    if text_start < template.len() {
+        segments.push(Segment::Text(&template[text_start..]));
+    }
+    segments
+}
+
+/// Placeholder names in order of first appearance
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for segment in segments(template) {
+        if let Segment::Placeholder(name) = segment {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Substitute placeholders with shell-quoted values; unknown ones are left as-is
+pub fn render(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    segments(template)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text.to_string(),
+            Segment::Placeholder(name) => match value(name) {
+                Some(value) => shell_quote(&value),
+                None => format!("{{{}}}", name),
+            },
+        })
+        .collect()
+}
+
+/// Asks for a template's placeholder values one at a time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandPrompt {
+    pub template: String,
+    pub names: Vec<String>,
+    /// Values entered so far, one per name
+    pub values: Vec<String>,
+    /// Suggested value per name, e.g. the active device for `{serial}`
+    defaults: Vec<Option<String>>,
+    pub input: String,
+}
+
+impl CommandPrompt {
+    /// Prompt for `template`, or `None` when it has no placeholders
+    pub fn new(template: &str, default: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let names = placeholders(template);
+        if names.is_empty() {
+            return None;
+        }
+        let defaults: Vec<Option<String>> = names.iter().map(|name| default(name)).collect();
+        Some(Self {
+            template: template.to_string(),
+            input: defaults[0].clone().unwrap_or_default(),
+            names,
+            values: Vec::new(),
+            defaults,
+        })
+    }
+
+    /// Placeholder currently being asked for
+    pub fn current(&self) -> Option<&str> {
+        self.names.get(self.values.len()).map(String::as_str)
+    }
+
+    /// Accept the current input; returns the finished command after the last value
+    pub fn submit(&mut self) -> Option<String> {
+        self.values.push(std::mem::take(&mut self.input));
+        match self.defaults.get(self.values.len()) {
+            Some(default) => {
+                self.input = default.clone().unwrap_or_default();
+                None
+            }
+            None => Some(render(&self.template, |name| {
+                self.names
+                    .iter()
+                    .position(|n| n == name)
+                    .map(|index| self.values[index].clone())
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(
+            placeholders("am start -n {package}/.Main && echo {serial} {package}"),
+            ["package", "serial"]
+        );
+        assert!(placeholders("echo ${HOME} | awk '{print $1}'").is_empty());
+        assert!(placeholders("no braces { here").is_empty());
+    }
+
+    #[test]
+    fn test_render_quotes_values() {
+        let command = render("pm clear {package} {other}", |name| {
+            (name == "package").then(|| "com.example; reboot".to_string())
+        });
+        assert_eq!(command, "pm clear 'com.example; reboot' {other}");
+    }
+
+    #[test]
+    fn test_command_prompt() {
+        let mut prompt = CommandPrompt::new("echo {serial} {name}", |name| {
+            (name == "serial").then(|| "emulator-5554".to_string())
+        })
+        .unwrap();
+        assert_eq!(prompt.current(), Some("serial"));
+        assert_eq!(prompt.input, "emulator-5554");
+        assert_eq!(prompt.submit(), None);
+
+        assert_eq!(prompt.current(), Some("name"));
+        prompt.input = "world".to_string();
+        assert_eq!(
+            prompt.submit().as_deref(),
+            Some("echo 'emulator-5554' 'world'")
+        );
+        assert!(CommandPrompt::new("ls", |_| None).is_none());
+    }
+}
@@ -0,0 +1,79 @@
+//! Short-lived notifications
+//!
+//! Toasts stack in the corner of every screen and expire on their own, so
+//! background news such as a device being plugged in never takes over the view.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How long a toast stays up
+pub const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Toasts shown at once; older ones make way
+const MAX_TOASTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub text: String,
+    pub level: ToastLevel,
+    pub shown: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct Toasts {
+    items: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, text: String, level: ToastLevel) {
+        if self.items.len() == MAX_TOASTS {
+            self.items.pop_front();
+        }
+        self.items.push_back(Toast {
+            text,
+            level,
+            shown: Instant::now(),
+        });
+    }
+
+    /// Drop toasts shown longer than [`TOAST_DURATION`] before `now`
+    pub fn expire(&mut self, now: Instant) {
+        self.items
+            .retain(|toast| now.duration_since(toast.shown) < TOAST_DURATION);
+    }
+
+    /// Toasts on screen, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.items.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toasts() {
+        let mut toasts = Toasts::default();
+        for n in 0..4 {
+            toasts.push(format!("toast {}", n), ToastLevel::Info);
+        }
+        let texts: Vec<&str> = toasts.iter().map(|toast| toast.text.as_str()).collect();
+        assert_eq!(texts, ["toast 1", "toast 2", "toast 3"]);
+
+        toasts.expire(Instant::now());
+        assert_eq!(toasts.iter().count(), 3);
+        toasts.expire(Instant::now() + TOAST_DURATION);
+        assert!(toasts.is_empty());
+    }
+}
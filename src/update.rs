@@ -1,7 +1,32 @@
-use crate::adb::AdbCommand;
+use crate::adb::screencap::save_screenshot;
+use crate::adb::{
+    AdbCommand, AdbError, AdbManager, AdbResult, CancelToken, DeviceInfo, PackageEntry,
+    ProcessEntry, RebootTarget, RemoteEntry, StreamConfig, Transport,
+};
+use crate::confirm::{Confirmation, PendingAction};
+use crate::dashboard::DashboardEvent;
+use crate::event::Event;
+use crate::files::{
+    join_remote, remote_parent, spawn_pull, spawn_push, FileEntry, FileInput, ListingEvent, Pane,
+    Transfer,
+};
+use crate::hotplug::{DeviceEvent, DeviceListEvent, TrackingState};
+use crate::logcat::{parse_pids, spawn_logcat, LogcatField, LogcatView, PidFilter};
 use crate::message::{CommandResult, Message};
+use crate::mirror::Mirror;
 use crate::model::{AppState, Model};
-use crate::stream::{start_stream, StreamConfig};
+use crate::packages::PackageAction;
+use crate::processes::{ProcessAction, ProcessEvent, ProcessView};
+use crate::reboot::{RebootEvent, RebootPhase, RebootWatch};
+use crate::record::Recording;
+use crate::screenshot::Screenshot;
+use crate::stream::start_stream;
+use crate::template::CommandPrompt;
+use crate::toast::ToastLevel;
+use crate::wireless::{normalize_address, parse_pair_input, WirelessPrompt, DEFAULT_TCP_PORT};
+use crate::worker::{
+    describe_error, spawn_command, spawn_poll, ActionEvent, PollError, POLL_TIMEOUT,
+};
 
 /// Update function - the heart of Elm architecture
 /// Takes the current model and a message, returns updated model
@@ -48,14 +73,67 @@ pub async fn update(model: &mut Model, message: Message) {
                     };
 
                     // Launch streaming in separate window (non-blocking)
-                    match start_stream(config) {
-                        Ok(stream_state) => {
-                            model.stream_state = Some(stream_state);
-                            model.set_result("Screen streaming started in separate window.\nClose the window or press Q in it to stop.".to_string());
-                            model.state = AppState::ShowResult;
+                    open_stream(model, config);
+                    return;
+                }
+            }
+
+            // Custom commands ask for their `{placeholder}` values first
+            if let AdbCommand::Shell { command: template } = &command {
+                let prompt = CommandPrompt::new(template, |name| placeholder_default(model, name));
+                if prompt.is_some() {
+                    model.command_prompt = prompt;
+                    return;
+                }
+            }
+
+            if let AdbCommand::StreamLogcat = command {
+                start_logcat(model);
+                return;
+            }
+
+            // Wireless commands without an address ask for one in the device picker
+            match &command {
+                AdbCommand::Connect { address } if address.is_empty() => {
+                    open_device_picker(model);
+                    model.wireless.open_prompt(WirelessPrompt::Connect);
+                    return;
+                }
+                AdbCommand::Pair { address, .. } if address.is_empty() => {
+                    open_device_picker(model);
+                    model.wireless.open_prompt(WirelessPrompt::Pair);
+                    return;
+                }
+                _ => {}
+            }
+
+            if let AdbCommand::TakeScreenshot = command {
+                take_screenshot(model);
+                return;
+            }
+
+            if let AdbCommand::RecordScreen { options } = &command {
+                if let Some(sender) = model.event_sender.clone() {
+                    model.recording = Some(Recording::start(
+                        &model.adb_manager,
+                        options.clone(),
+                        sender,
+                    ));
+                    model.state = AppState::Recording;
+                    return;
+                }
+            }
+
+            if let AdbCommand::MirrorScreen = command {
+                if let Some(sender) = model.event_sender.clone() {
+                    match Mirror::start(&model.adb_manager, sender) {
+                        Ok(mirror) => {
+                            model.mirror = Some(mirror);
+                            model.mirror_area = None;
+                            model.state = AppState::Mirror;
                         }
                         Err(e) => {
-                            model.set_error(format!("Failed to start streaming: {}\n\nMake sure:\n- ADB is installed and in PATH\n- Device is connected\n- FFmpeg is installed", e));
+                            model.set_error(format!("Failed to start mirroring: {}", e));
                             model.state = AppState::ShowResult;
                         }
                     }
@@ -64,24 +142,90 @@ pub async fn update(model: &mut Model, message: Message) {
                 }
             }
 
-            model.state = AppState::Loading;
-            model.clear_results();
-            model.loading_counter = 0;
-            model.effects.reset_slide();
+            if let AdbCommand::ManagePackages = command {
+                open_packages(model);
+                return;
+            }
+
+            if let AdbCommand::ManageProcesses = command {
+                model.processes = ProcessView::default();
+                model.state = AppState::Processes;
+                model.effects.start_slide_in();
+                return;
+            }
 
-            let result = execute_adb_command(model, command).await;
+            if let AdbCommand::Dashboard = command {
+                model.dashboard.reset();
+                model.state = AppState::Dashboard;
+                model.effects.start_slide_in();
+                return;
+            }
 
-            // Handle result directly to avoid recursion
-            match result {
-                CommandResult::Success(output) => {
-                    model.set_result(output);
+            if let AdbCommand::ListDirectory { path } = &command {
+                model.files.remote_dir = path.clone();
+                open_file_browser(model);
+                return;
+            }
+
+            let title = model.menu.get_selected_label().to_string();
+            if ask_confirmation(model, title, PendingAction::Command(command.clone())) {
+                return;
+            }
+            run_command(model, command).await;
+        }
+
+        Message::PromptInput(c) => {
+            if let Some(prompt) = model.command_prompt.as_mut() {
+                prompt.input.push(c);
+            }
+        }
+
+        Message::PromptBackspace => {
+            if let Some(prompt) = model.command_prompt.as_mut() {
+                prompt.input.pop();
+            }
+        }
+
+        Message::PromptSubmit => {
+            let Some(prompt) = model.command_prompt.as_mut() else {
+                return;
+            };
+            if let Some(command) = prompt.submit() {
+                model.command_prompt = None;
+                // Run directly: substituted values must not be expanded again
+                let command = AdbCommand::Shell { command };
+                let title = model.menu.get_selected_label().to_string();
+                if ask_confirmation(model, title, PendingAction::Command(command.clone())) {
+                    return;
+                }
+                run_command(model, command).await;
+            }
+        }
+
+        Message::PromptCancel => {
+            model.command_prompt = None;
+        }
+
+        Message::ConfirmAccept => {
+            let Some(confirmation) = model.confirmation.take() else {
+                return;
+            };
+            model.state = confirmation.previous;
+            match confirmation.action {
+                PendingAction::Command(command) => run_command(model, command).await,
+                PendingAction::Package { action, package } => {
+                    perform_package_action(model, action, package).await
                 }
-                CommandResult::Error(error) => {
-                    model.set_error(error);
+                PendingAction::Process { action, process } => {
+                    perform_process_action(model, action, process)
                 }
             }
-            model.state = AppState::ShowResult;
-            model.effects.start_slide_in();
+        }
+
+        Message::ConfirmDecline => {
+            if let Some(confirmation) = model.confirmation.take() {
+                model.state = confirmation.previous;
+            }
         }
 
         Message::CommandStarted => {
@@ -90,224 +234,1834 @@ pub async fn update(model: &mut Model, message: Message) {
         }
 
         Message::CommandCompleted(result) => {
+            let mut unresponsive = None;
+            if let Some(job) = model.command_job.take() {
+                if let Some(serial) = model.target_device().map(str::to_string) {
+                    if !job.timed_out() {
+                        model.watchdog.record_response(&serial);
+                    } else if model.watchdog.record_timeout(&serial) {
+                        unresponsive = Some(serial);
+                    }
+                }
+            }
             match result {
                 CommandResult::Success(output) => {
-                    model.set_result(output);
-                }
-                CommandResult::Error(error) => {
-                    model.set_error(error);
+                    model.set_output(output);
                 }
+                CommandResult::Error(error) => match unresponsive {
+                    Some(serial) => {
+                        model.set_error(format!("{}\n\n{}", error, reconnect_hint(&serial)))
+                    }
+                    None => model.set_error(error),
+                },
             }
             model.state = AppState::ShowResult;
             model.effects.start_slide_in();
         }
 
-        // Scroll messages
-        Message::ScrollUp => {
-            if model.scroll_position > 0 {
-                model.scroll_position -= 1;
+        Message::CancelCommand => {
+            if let Some(job) = model.command_job.take() {
+                job.cancel();
             }
+            model.state = AppState::Menu;
+            model.clear_results();
         }
 
-        Message::ScrollDown => {
-            if model.scroll_position + 1 < model.total_result_lines() {
-                model.scroll_position += 1;
-            }
+        // Device picker
+        Message::OpenDevicePicker => {
+            open_device_picker(model);
         }
 
-        Message::ScrollPageUp => {
-            model.scroll_position = model.scroll_position.saturating_sub(10);
+        Message::RefreshDevices => {
+            refresh_devices(model);
         }
 
-        Message::ScrollPageDown => {
-            let max_scroll = model.total_result_lines().saturating_sub(1);
-            model.scroll_position = (model.scroll_position + 10).min(max_scroll);
+        Message::DevicesListed(result) => {
+            devices_listed(model, result);
         }
 
-        Message::ScrollToTop => {
-            model.scroll_position = 0;
+        Message::DeviceUp => {
+            model.device_selected = model.device_selected.saturating_sub(1);
         }
 
-        Message::ScrollToBottom => {
-            model.scroll_position = model.total_result_lines().saturating_sub(1);
+        Message::DeviceDown => {
+            if model.device_selected + 1 < model.devices.len() {
+                model.device_selected += 1;
+            }
         }
 
-        // Screen streaming messages
-        Message::StartStream => {
-            let config = StreamConfig::default();
-            match start_stream(config) {
-                Ok(stream_state) => {
-                    model.stream_state = Some(stream_state);
-                    model.set_result("Screen streaming started in separate window.".to_string());
-                    model.state = AppState::ShowResult;
-                }
-                Err(e) => {
-                    model.set_error(format!("Failed to start streaming: {}", e));
-                    model.state = AppState::ShowResult;
-                }
+        Message::SelectDevice => {
+            if let Some(device) = model.highlighted_device() {
+                let serial = device.serial.clone();
+                model.adb_manager.select_device(serial);
             }
+            model.state = AppState::Menu;
             model.effects.start_slide_in();
         }
 
-        Message::StopStream => {
-            if let Some(mut stream) = model.stream_state.take() {
-                stream.stop();
+        Message::DisconnectDevice => {
+            let Some(device) = model.highlighted_device() else {
+                return;
+            };
+            if device.transport() != Transport::Tcp {
+                model.wireless.status =
+                    Some("Only network devices can be disconnected".to_string());
+                return;
             }
-            model.state = AppState::Menu;
+            let serial = device.serial.clone();
+            if model.disconnect_job.is_some() {
+                return;
+            }
+            let Some(sender) = model.event_sender.clone() else {
+                // Without an event loop (tests) the disconnect runs inline
+                let result = model
+                    .adb_manager
+                    .disconnect_device(Some(&serial))
+                    .map_err(PollError::from);
+                disconnect_done(model, result);
+                return;
+            };
+            model.wireless.status = Some(format!("Disconnecting {}…", serial));
+            let poll = spawn_poll(
+                &model.adb_manager,
+                sender,
+                None,
+                move |manager| manager.disconnect_device(Some(&serial)),
+                |id, result| Event::Disconnect(ActionEvent { id, result }),
+            );
+            model.disconnect_job = Some(poll);
         }
 
-        Message::UpdateFrame(_frame) => {
-            // No longer used with window-based streaming
+        Message::DisconnectDone(result) => {
+            disconnect_done(model, result);
         }
 
-        Message::TogglePause => {
-            // No longer used with window-based streaming
+        Message::DevicesChanged(event) => {
+            apply_device_event(model, event);
+            clamp_device_selection(model);
         }
 
-        Message::IncreaseRefreshRate => {
-            // No longer used with window-based streaming
+        // Wireless debugging prompts
+        Message::OpenWirelessPrompt(prompt) => {
+            model.wireless.open_prompt(prompt);
         }
 
-        Message::DecreaseRefreshRate => {
-            // No longer used with window-based streaming
+        Message::WirelessInput(c) => {
+            if let Some((_, text)) = model.wireless.prompt.as_mut() {
+                text.push(c);
+            }
         }
 
-        // Application lifecycle
-        Message::Tick => {
-            tick(model).await;
+        Message::WirelessBackspace => {
+            if let Some((_, text)) = model.wireless.prompt.as_mut() {
+                text.pop();
+            }
         }
 
-        Message::Quit => {
-            model.running = false;
+        Message::WirelessRecent(delta) => {
+            model.wireless.cycle_recent(delta);
         }
 
-        Message::ReturnToMenu => {
-            model.state = AppState::Menu;
-            model.clear_results();
+        Message::WirelessSubmit => {
+            if let Some((prompt, text)) = model.wireless.prompt.take() {
+                submit_wireless_prompt(model, prompt, text);
+            }
         }
 
-        Message::SkipStartup => {
+        Message::WirelessCancel => {
+            model.wireless.prompt = None;
+        }
+
+        // Live logcat
+        Message::LogcatLines(lines) => {
+            model.logcat.push_lines(lines);
+        }
+
+        Message::LogcatEnded(error) => {
+            model.logcat.streaming = false;
+            model.logcat_handle = None;
+            model.logcat.status = Some(error.unwrap_or_else(|| "Logcat stream ended".to_string()));
+        }
+
+        Message::LogcatScroll(delta) => {
+            model.logcat.scroll(delta);
+        }
+
+        Message::LogcatFollow => {
+            model.logcat.scroll_offset = 0;
+        }
+
+        Message::LogcatCycleLevel => {
+            let mut filter = model.logcat.filter().clone();
+            filter.min_level = filter.min_level.next();
+            model.logcat.set_filter(filter);
+        }
+
+        Message::LogcatEditFilter(field) => {
+            let filter = model.logcat.filter();
+            let current = match field {
+                LogcatField::Tag => filter.tag.clone(),
+                LogcatField::Package => filter.pid.as_ref().map(|p| p.label.clone()),
+                LogcatField::Regex => filter.regex.as_ref().map(|r| r.as_str().to_string()),
+            };
+            model.logcat.input = Some((field, current.unwrap_or_default()));
+        }
+
+        Message::LogcatInput(c) => {
+            if let Some((_, text)) = model.logcat.input.as_mut() {
+                text.push(c);
+            }
+        }
+
+        Message::LogcatBackspace => {
+            if let Some((_, text)) = model.logcat.input.as_mut() {
+                text.pop();
+            }
+        }
+
+        Message::LogcatSubmitFilter => {
+            if let Some((field, text)) = model.logcat.input.take() {
+                apply_logcat_filter(model, field, text.trim().to_string());
+            }
+        }
+
+        Message::LogcatCancelInput => {
+            model.logcat.input = None;
+        }
+
+        Message::LogcatClearFilters => {
+            model.logcat.set_filter(Default::default());
+            model.logcat.status = None;
+        }
+
+        Message::StopLogcat => {
+            if let Some(handle) = model.logcat_handle.take() {
+                handle.stop();
+            }
             model.state = AppState::Menu;
             model.effects.start_slide_in();
         }
-    }
-}
 
-/// Handle tick updates (animations, timers, etc.)
-async fn tick(model: &mut Model) {
-    let now = std::time::Instant::now();
-    let elapsed = now.duration_since(model.last_tick);
-    model.last_tick = now;
+        // File browser
+        Message::OpenFileBrowser => {
+            open_file_browser(model);
+        }
 
-    // Update effects
-    model.effects.tick(elapsed);
+        Message::FileUp => {
+            model.files.focused().up();
+        }
 
-    // Update menu animations
-    model.menu.tick();
+        Message::FileDown => {
+            model.files.focused().down();
+        }
 
-    // Update loading animation
-    if model.state == AppState::Loading {
-        model.loading_counter += 1;
-    }
+        Message::FileOpen => {
+            let files = &mut model.files;
+            match files.focus {
+                Pane::Local => {
+                    if let Some(path) = files.selected_local_path().filter(|p| p.is_dir()) {
+                        files.local_dir = path;
+                        files.local.selected = 0;
+                        refresh_local_pane(model);
+                    }
+                }
+                Pane::Remote => {
+                    let (Some(entry), Some(path)) = (
+                        files.remote.selected_entry().cloned(),
+                        files.selected_remote_path(),
+                    ) else {
+                        return;
+                    };
+                    let enter = entry.is_dir
+                        || (entry.is_symlink
+                            && match model.adb_manager.is_remote_dir(&path) {
+                                Ok(is_dir) => is_dir,
+                                Err(e) => {
+                                    model.files.status = Some(e.to_string());
+                                    return;
+                                }
+                            });
+                    let files = &mut model.files;
+                    if enter {
+                        files.remote_dir = path;
+                        files.remote.selected = 0;
+                        refresh_remote_pane(model);
+                    } else if entry.is_symlink {
+                        files.status = Some(format!(
+                            "{} links to a file; press c to copy it",
+                            entry.name
+                        ));
+                    }
+                }
+            }
+        }
 
-    // Update reveal animation for results
-    if model.state == AppState::ShowResult {
-        model.reveal_counter += 1;
-    }
+        Message::FileParent => {
+            let files = &mut model.files;
+            match files.focus {
+                Pane::Local => {
+                    if let Some(parent) = files.local_dir.parent() {
+                        files.local_dir = parent.to_path_buf();
+                        files.local.selected = 0;
+                        refresh_local_pane(model);
+                    }
+                }
+                Pane::Remote => {
+                    files.remote_dir = remote_parent(&files.remote_dir);
+                    files.remote.selected = 0;
+                    refresh_remote_pane(model);
+                }
+            }
+        }
 
-    // Check if startup is complete
-    if model.state == AppState::Startup && model.effects.is_startup_complete() {
-        model.state = AppState::Menu;
-    }
-}
+        Message::FileSwitchPane => {
+            model.files.toggle_focus();
+        }
 
-/// Execute an ADB command using the ADB manager
-async fn execute_adb_command(model: &mut Model, command: AdbCommand) -> CommandResult {
-    // Execute the command using the ADB manager
-    match model.adb_manager.execute(command) {
-        Ok(output) => CommandResult::Success(output),
-        Err(e) => {
-            let error_msg = format!("{}", e);
+        Message::FileRefresh => {
+            model.files.status = None;
+            refresh_local_pane(model);
+            refresh_remote_pane(model);
+        }
 
-            // Add helpful context for common errors
-            let enhanced_error = if error_msg.contains("Connection")
-                || error_msg.contains("connection")
-            {
-                format!(
-                    "{}\n\nTroubleshooting:\n• Make sure ADB server is running (adb start-server)\n• Check that device is connected (adb devices)\n• Verify USB debugging is enabled on device",
-                    error_msg
-                )
-            } else if error_msg.contains("No device selected") {
-                format!(
-                    "{}\n\nPlease:\n• Connect an Android device via USB\n• Enable USB debugging on the device\n• Run 'List Devices' to detect your device",
-                    error_msg
-                )
-            } else {
-                error_msg
-            };
+        Message::FileCopy => {
+            start_transfer(model);
+        }
 
-            CommandResult::Error(enhanced_error)
+        Message::FileDelete => {
+            if model.files.focused().selected_entry().is_some() {
+                model.files.input = Some((FileInput::ConfirmDelete, String::new()));
+            }
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        Message::FileRename => {
+            if let Some(entry) = model.files.focused().selected_entry() {
+                let name = entry.name.clone();
+                model.files.input = Some((FileInput::Rename, name));
+            }
+        }
 
-    #[tokio::test]
-    async fn test_menu_navigation() {
-        let mut model = Model::new();
-        model.state = AppState::Menu;
+        Message::FileInput(c) => {
+            if let Some((_, text)) = model.files.input.as_mut() {
+                text.push(c);
+            }
+        }
 
-        update(&mut model, Message::MenuDown).await;
-        assert_eq!(model.menu.selected, 1);
+        Message::FileBackspace => {
+            if let Some((_, text)) = model.files.input.as_mut() {
+                text.pop();
+            }
+        }
 
-        update(&mut model, Message::MenuUp).await;
-        assert_eq!(model.menu.selected, 0);
-    }
+        Message::FileSubmitInput => {
+            if let Some((input, text)) = model.files.input.take() {
+                match input {
+                    FileInput::ConfirmDelete => delete_selected(model),
+                    FileInput::Rename => rename_selected(model, text.trim()),
+                }
+            }
+        }
 
-    #[tokio::test]
-    async fn test_quit() {
-        let mut model = Model::new();
-        assert!(model.running);
+        Message::FileCancelInput => {
+            model.files.input = None;
+        }
 
-        update(&mut model, Message::Quit).await;
-        assert!(!model.running);
-    }
+        Message::RemoteListed(result) => {
+            remote_listed(model, result);
+        }
 
-    #[tokio::test]
-    async fn test_scroll_boundaries() {
-        let mut model = Model::new();
-        model.wrapped_lines = vec!["Line 1".to_string(), "Line 2".to_string()];
-        model.scroll_position = 0;
+        Message::FileActionDone(result) => {
+            if let Some((_, input)) = model.files.running.take() {
+                file_action_done(model, input, result);
+            }
+        }
 
-        // Can't scroll up from position 0
-        update(&mut model, Message::ScrollUp).await;
-        assert_eq!(model.scroll_position, 0);
+        Message::TransferProgress { transferred, total } => {
+            if let Some(transfer) = model.files.transfer.as_mut() {
+                transfer.transferred = transferred;
+                transfer.total = total;
+            }
+        }
 
-        // Can scroll down
-        update(&mut model, Message::ScrollDown).await;
-        assert_eq!(model.scroll_position, 1);
+        Message::TransferFinished(result) => {
+            model.files.transfer = None;
+            model.files.status = Some(match result {
+                Ok(summary) => summary,
+                Err(e) => format!("Transfer failed: {}", e),
+            });
+            refresh_local_pane(model);
+            refresh_remote_pane(model);
+        }
 
-        // Can't scroll past end
-        update(&mut model, Message::ScrollDown).await;
-        assert_eq!(model.scroll_position, 1);
-    }
+        // Package manager
+        Message::RefreshPackages => {
+            refresh_packages(model);
+        }
 
-    #[tokio::test]
-    async fn test_enter_exit_child_mode() {
-        let mut model = Model::new();
-        model.state = AppState::Menu;
+        Message::PackageUp => {
+            model.packages.up();
+        }
 
-        update(&mut model, Message::EnterChild).await;
-        assert!(model.menu.is_in_child_mode());
+        Message::PackageDown => {
+            model.packages.down();
+        }
 
-        update(&mut model, Message::ExitChild).await;
-        assert!(!model.menu.is_in_child_mode());
-    }
+        Message::PackageSearch => {
+            model.packages.searching = true;
+        }
 
-    #[tokio::test]
+        Message::PackageInput(c) => {
+            let mut query = model.packages.query.clone();
+            query.push(c);
+            model.packages.set_query(query);
+        }
+
+        Message::PackageBackspace => {
+            let mut query = model.packages.query.clone();
+            query.pop();
+            model.packages.set_query(query);
+        }
+
+        Message::PackageSearchDone => {
+            model.packages.searching = false;
+        }
+
+        Message::PackageOpenActions => {
+            if model.packages.selected_package().is_some() {
+                model.packages.action = Some(0);
+            }
+        }
+
+        Message::PackageCloseActions => {
+            model.packages.action = None;
+        }
+
+        Message::PackageRunAction => {
+            run_package_action(model).await;
+        }
+
+        Message::PackageActionDone(result) => {
+            record_poll(model, &result);
+            if let Some((_, action, package)) = model.packages.running.take() {
+                package_action_done(model, action, &package, result);
+            }
+        }
+
+        // Process manager
+        Message::ProcessSample(result) => {
+            model.processes.poll = None;
+            record_poll(model, &result);
+            match result {
+                Ok(sample) => model.processes.apply(sample),
+                Err(e) => model.processes.status = Some(e.message),
+            }
+        }
+
+        Message::ProcessActionDone(result) => {
+            record_poll(model, &result);
+            if let Some((_, process)) = model.processes.running.take() {
+                process_action_done(model, &process, result);
+            }
+        }
+
+        Message::ProcessMove(delta) => {
+            model.processes.move_by(delta);
+        }
+
+        Message::ProcessSearch => {
+            model.processes.searching = true;
+        }
+
+        Message::ProcessInput(c) => {
+            model.processes.query.push(c);
+        }
+
+        Message::ProcessBackspace => {
+            model.processes.query.pop();
+        }
+
+        Message::ProcessSearchDone => {
+            model.processes.searching = false;
+        }
+
+        Message::ProcessCycleSort => {
+            model.processes.cycle_sort();
+        }
+
+        Message::ProcessReverseSort => {
+            model.processes.descending = !model.processes.descending;
+        }
+
+        Message::ProcessOpenActions => {
+            if let Some(process) = model.processes.selected_process() {
+                // Pin the selection so a re-sort can't move the action to another process
+                model.processes.selected_pid = Some(process.pid);
+                model.processes.action = Some(0);
+            }
+        }
+
+        Message::ProcessCloseActions => {
+            model.processes.action = None;
+        }
+
+        Message::ProcessRunAction => {
+            run_process_action(model);
+        }
+
+        Message::RebootProgress(result) => {
+            reboot_progress(model, result);
+        }
+
+        Message::MirrorFrame => {
+            if let Some(mirror) = model.mirror.as_mut() {
+                mirror.take_frame();
+            }
+        }
+
+        Message::MirrorEnded(result) => {
+            model.mirror = None;
+            model.mirror_area = None;
+            match result {
+                Ok(()) => model.set_result("Mirroring stopped".to_string()),
+                Err(e) => model.set_error(format!("Mirroring failed: {}", e)),
+            }
+            model.state = AppState::ShowResult;
+            model.effects.start_slide_in();
+        }
+
+        Message::StopRecording => {
+            if let Some(recording) = model.recording.as_mut() {
+                recording.stop();
+            }
+        }
+
+        Message::RecordingFinished(result) => {
+            model.recording = None;
+            match result {
+                Ok(summary) => model.set_result(summary.describe()),
+                Err(e) => model.set_error(format!("Recording failed: {}", e)),
+            }
+            model.state = AppState::ShowResult;
+            model.effects.start_slide_in();
+        }
+
+        // Scroll messages
+        Message::ScrollUp => {
+            if model.scroll_position > 0 {
+                model.scroll_position -= 1;
+            }
+        }
+
+        Message::ScrollDown => {
+            if model.scroll_position + 1 < model.total_result_lines() {
+                model.scroll_position += 1;
+            }
+        }
+
+        Message::ScrollPageUp => {
+            model.scroll_position = model.scroll_position.saturating_sub(10);
+        }
+
+        Message::ScrollPageDown => {
+            let max_scroll = model.total_result_lines().saturating_sub(1);
+            model.scroll_position = (model.scroll_position + 10).min(max_scroll);
+        }
+
+        Message::ScrollToTop => {
+            model.scroll_position = 0;
+        }
+
+        Message::ScrollToBottom => {
+            model.scroll_position = model.total_result_lines().saturating_sub(1);
+        }
+
+        // Screen streaming messages
+        Message::StartStream => {
+            open_stream(model, StreamConfig::default());
+        }
+
+        Message::StopStream => {
+            // Dropping the stream cancels it, which closes the window
+            model.stream_state = None;
+            model.state = AppState::Menu;
+        }
+
+        Message::StreamStarted => {
+            if let Some(stream) = model.stream_state.as_mut() {
+                stream.started = true;
+            }
+        }
+
+        Message::StreamEnded(result) => {
+            model.stream_state = None;
+            match result {
+                Ok(()) => model.set_result("Screen stream closed".to_string()),
+                Err(e) => model.set_error(format!("Screen stream failed: {}", e)),
+            }
+            model.state = AppState::ShowResult;
+            model.effects.start_slide_in();
+        }
+
+        Message::UpdateFrame(_frame) => {
+            // No longer used with window-based streaming
+        }
+
+        Message::TogglePause => {
+            // No longer used with window-based streaming
+        }
+
+        Message::IncreaseRefreshRate => {
+            // No longer used with window-based streaming
+        }
+
+        Message::DecreaseRefreshRate => {
+            // No longer used with window-based streaming
+        }
+
+        // Live dashboard
+        Message::DashboardSample(result) => {
+            model.dashboard.poll = None;
+            record_poll(model, &result);
+            match result {
+                Ok(sample) => model.dashboard.record(sample),
+                Err(e) => model.dashboard.error = Some(e.message),
+            }
+        }
+
+        Message::DashboardFaster => {
+            model.dashboard.adjust_interval(true);
+        }
+
+        Message::DashboardSlower => {
+            model.dashboard.adjust_interval(false);
+        }
+
+        // Application lifecycle
+        Message::Tick => {
+            tick(model).await;
+        }
+
+        Message::Quit => {
+            model.running = false;
+        }
+
+        Message::ReturnToMenu => {
+            model.command_job = None;
+            model.dashboard.poll = None;
+            model.processes.poll = None;
+            model.reboot = None;
+            model.mirror = None;
+            model.mirror_area = None;
+            model.state = AppState::Menu;
+            model.clear_results();
+        }
+
+        Message::SkipStartup => {
+            model.state = AppState::Menu;
+            model.effects.start_slide_in();
+        }
+    }
+}
+
+/// Open the stream window for the selected device
+fn open_stream(model: &mut Model, config: StreamConfig) {
+    let result = match model.event_sender.clone() {
+        Some(sender) => start_stream(&model.adb_manager, config, sender),
+        None => Err("Screen streaming needs the event loop".to_string()),
+    };
+    match result {
+        Ok(stream) => {
+            model.stream_state = Some(stream);
+            model.state = AppState::Streaming;
+        }
+        Err(e) => {
+            model.set_error(format!("Failed to start streaming: {}", e));
+            model.state = AppState::ShowResult;
+        }
+    }
+    model.effects.start_slide_in();
+}
+
+/// Handle tick updates (animations, timers, etc.)
+async fn tick(model: &mut Model) {
+    let now = std::time::Instant::now();
+    let elapsed = now.duration_since(model.last_tick);
+    model.last_tick = now;
+
+    // Update effects
+    model.effects.tick(elapsed);
+
+    // Update menu animations
+    model.menu.tick();
+
+    model.toasts.expire(now);
+
+    // Update loading animation (also drives transfer progress bars)
+    if matches!(
+        model.state,
+        AppState::Loading
+            | AppState::Rebooting
+            | AppState::Recording
+            | AppState::Mirror
+            | AppState::Streaming
+    ) || model.files.transfer.is_some()
+    {
+        model.loading_counter += 1;
+    }
+
+    // Update reveal animation for results
+    if model.state == AppState::ShowResult {
+        model.reveal_counter += 1;
+    }
+
+    // Poll the device for the live dashboard
+    if model.state == AppState::Dashboard && model.dashboard.is_due(now) {
+        if let Some(sender) = model.event_sender.clone() {
+            let poll = spawn_poll(
+                &model.adb_manager,
+                sender,
+                Some(POLL_TIMEOUT),
+                AdbManager::sample_device,
+                |id, result| Event::Dashboard(DashboardEvent { id, result }),
+            );
+            model.dashboard.start_poll(poll, now);
+        }
+    }
+
+    // Refresh the process manager
+    if model.state == AppState::Processes && model.processes.is_due(now) {
+        if let Some(sender) = model.event_sender.clone() {
+            let poll = spawn_poll(
+                &model.adb_manager,
+                sender,
+                Some(POLL_TIMEOUT),
+                AdbManager::sample_processes,
+                |id, result| Event::Processes(ProcessEvent { id, result }),
+            );
+            model.processes.start_poll(poll, now);
+        }
+    }
+
+    // Check if startup is complete
+    if model.state == AppState::Startup && model.effects.is_startup_complete() {
+        model.state = AppState::Menu;
+    }
+}
+
+/// Run a regular command, in the background when the event loop is available
+///
+/// Reboots then wait for the device to come back.
+async fn run_command(model: &mut Model, command: AdbCommand) {
+    if model.event_sender.is_some() {
+        match command {
+            AdbCommand::Reboot { target } => start_reboot(model, target),
+            command => start_command(model, command),
+        }
+        return;
+    }
+
+    // Without an event loop (tests) the command runs inline
+    model.state = AppState::Loading;
+    model.clear_results();
+    model.loading_counter = 0;
+    model.effects.reset_slide();
+
+    let result = execute_adb_command(model, command).await;
+    update_result(model, result);
+}
+
+/// Reboot in the background, then wait for the device to come back
+fn start_reboot(model: &mut Model, target: RebootTarget) {
+    let Some(sender) = model.event_sender.clone() else {
+        return;
+    };
+    let mut watch = RebootWatch::new(target, model.active_device().map(str::to_string));
+    watch.poll = Some(spawn_poll(
+        &model.adb_manager,
+        sender,
+        reboot_timeout(&model.adb_manager, target),
+        move |manager| manager.reboot_device(target),
+        |id, result| {
+            Event::Reboot(RebootEvent {
+                id,
+                result: result.map_err(|e| e.message),
+            })
+        },
+    ));
+    model.clear_results();
+    model.loading_counter = 0;
+    model.reboot = Some(watch);
+    model.state = AppState::Rebooting;
+}
+
+/// Configured deadline for each reboot step: going down, then coming back
+fn reboot_timeout(manager: &AdbManager, target: RebootTarget) -> Option<std::time::Duration> {
+    manager.timeouts().get(&AdbCommand::Reboot { target })
+}
+
+/// Move a reboot on to waiting, or show how it ended
+fn reboot_progress(model: &mut Model, result: Result<String, String>) {
+    let Some(watch) = model.reboot.as_mut() else {
+        return;
+    };
+    watch.poll = None;
+    let target = watch.target;
+
+    match (watch.phase, result) {
+        (RebootPhase::Rebooting, Ok(serial)) => {
+            watch.serial = Some(serial.clone());
+            watch.phase = RebootPhase::Waiting;
+            if let Some(sender) = model.event_sender.clone() {
+                let timeout = reboot_timeout(&model.adb_manager, target);
+                watch.poll = Some(spawn_poll(
+                    &model.adb_manager,
+                    sender,
+                    timeout,
+                    move |manager| manager.wait_for_device(&serial, target),
+                    |id, result| {
+                        Event::Reboot(RebootEvent {
+                            id,
+                            result: result.map_err(|e| e.message),
+                        })
+                    },
+                ));
+            }
+        }
+        (RebootPhase::Waiting, Ok(status)) => {
+            let elapsed = watch.elapsed(std::time::Instant::now());
+            model.reboot = None;
+            model.set_result(format!("{} after {}s", status, elapsed.as_secs()));
+            model.state = AppState::ShowResult;
+            model.effects.start_slide_in();
+        }
+        (phase, Err(e)) => {
+            model.reboot = None;
+            model.set_error(match phase {
+                RebootPhase::Rebooting => format!("Reboot failed: {}", e),
+                RebootPhase::Waiting => format!("Stopped waiting for the device: {}", e),
+            });
+            model.state = AppState::ShowResult;
+            model.effects.start_slide_in();
+        }
+    }
+}
+
+/// Stop at the confirmation modal if `action` is destructive
+///
+/// Returns whether the modal was opened. Read-only mode refuses destructive
+/// commands outright, so they skip the modal and fail when run.
+fn ask_confirmation(model: &mut Model, title: String, action: PendingAction) -> bool {
+    if model.adb_manager.is_read_only() || !action.command().is_destructive() {
+        return false;
+    }
+    let device = model.active_device().map(|serial| {
+        model
+            .devices
+            .iter()
+            .find(|d| d.serial == serial)
+            .map(|d| d.display_name())
+            .unwrap_or_else(|| serial.to_string())
+    });
+    model.confirmation = Some(Confirmation {
+        title,
+        device,
+        action,
+        previous: model.state,
+    });
+    model.state = AppState::Confirm;
+    true
+}
+
+/// Suggested value for a custom command placeholder
+fn placeholder_default(model: &Model, name: &str) -> Option<String> {
+    match name {
+        "serial" => model.active_device().map(str::to_string),
+        "package" => model
+            .packages
+            .selected_package()
+            .map(|package| package.name.clone()),
+        _ => None,
+    }
+}
+
+/// Run a command on a worker thread and show the loading screen until it reports back
+fn start_command(model: &mut Model, command: AdbCommand) {
+    let Some(sender) = model.event_sender.clone() else {
+        return;
+    };
+    model.state = AppState::Loading;
+    model.clear_results();
+    model.loading_counter = 0;
+    model.effects.reset_slide();
+    // Replacing a job drops it, which cancels it
+    model.command_job = Some(spawn_command(&model.adb_manager, command, sender));
+}
+
+/// Execute an ADB command using the ADB manager
+async fn execute_adb_command(model: &mut Model, command: AdbCommand) -> CommandResult {
+    // Execute the command using the ADB manager
+    match model.adb_manager.execute_output(command) {
+        Ok(output) => CommandResult::Success(output),
+        Err(e) => CommandResult::Error(describe_error(e)),
+    }
+}
+
+/// Open the live logcat view and start streaming from the active device
+fn start_logcat(model: &mut Model) {
+    let Some(sender) = model.event_sender.clone() else {
+        model.set_error("Live logcat requires the interactive event loop".to_string());
+        model.state = AppState::ShowResult;
+        return;
+    };
+
+    model.logcat = LogcatView::new();
+    model.logcat_handle = Some(spawn_logcat(&model.adb_manager, sender));
+    model.state = AppState::Logcat;
+    model.effects.start_slide_in();
+}
+
+/// Apply an edited logcat filter field; an empty value clears it
+fn apply_logcat_filter(model: &mut Model, field: LogcatField, text: String) {
+    model.logcat.status = None;
+    let mut filter = model.logcat.filter().clone();
+
+    match field {
+        LogcatField::Tag => {
+            filter.tag = (!text.is_empty()).then_some(text);
+        }
+        LogcatField::Regex if text.is_empty() => {
+            filter.regex = None;
+        }
+        LogcatField::Regex => match regex::Regex::new(&text) {
+            Ok(regex) => filter.regex = Some(regex),
+            Err(e) => {
+                model.logcat.status = Some(format!("Invalid regex: {}", e));
+                model.logcat.input = Some((field, text));
+            }
+        },
+        LogcatField::Package if text.is_empty() => {
+            filter.pid = None;
+        }
+        LogcatField::Package => {
+            let pids = match text.parse::<u32>() {
+                Ok(pid) => vec![pid],
+                Err(_) => model
+                    .adb_manager
+                    .execute(AdbCommand::Shell {
+                        command: format!("pidof {}", text),
+                    })
+                    .map(|output| parse_pids(&output))
+                    .unwrap_or_default(),
+            };
+            if pids.is_empty() {
+                model.logcat.status = Some(format!("{} is not running", text));
+            }
+            filter.pid = Some(PidFilter { label: text, pids });
+        }
+    }
+    model.logcat.set_filter(filter);
+}
+
+/// Capture a screenshot, save it on the host and show the preview
+fn take_screenshot(model: &mut Model) {
+    let result = model
+        .adb_manager
+        .capture_screenshot()
+        .map_err(describe_error)
+        .and_then(|png| {
+            let dir = model.adb_manager.screenshot_dir().to_path_buf();
+            save_screenshot(&dir, &png)
+                .map_err(|e| format!("Failed to save screenshot to {}: {}", dir.display(), e))
+                .map(|path| (path, png))
+        })
+        .and_then(|(path, png)| Screenshot::decode(path, png));
+
+    match result {
+        Ok(screenshot) => {
+            model.screenshot = Some(screenshot);
+            model.screenshot_area = None;
+            model.state = AppState::Screenshot;
+        }
+        Err(e) => {
+            model.set_error(e);
+            model.state = AppState::ShowResult;
+        }
+    }
+    model.effects.start_slide_in();
+}
+
+/// Open the package manager and load the package list
+fn open_packages(model: &mut Model) {
+    match model.adb_manager.packages() {
+        Ok(entries) => {
+            model.packages.set_entries(entries);
+            model.packages.action = None;
+            model.packages.status = None;
+            model.state = AppState::Packages;
+        }
+        Err(e) => {
+            model.set_error(describe_error(e));
+            model.state = AppState::ShowResult;
+        }
+    }
+    model.effects.start_slide_in();
+}
+
+/// Reload the package list, keeping the selection
+fn refresh_packages(model: &mut Model) {
+    match model.adb_manager.packages() {
+        Ok(entries) => model.packages.set_entries(entries),
+        Err(e) => model.packages.status = Some(e.to_string()),
+    }
+}
+
+/// Run the highlighted action on the selected package
+async fn run_package_action(model: &mut Model) {
+    let Some(index) = model.packages.action.take() else {
+        return;
+    };
+    let Some(package) = model.packages.selected_package().cloned() else {
+        return;
+    };
+    let action = PackageAction::ALL[index];
+
+    let title = action.label(&package).to_string();
+    let pending = PendingAction::Package {
+        action,
+        package: package.clone(),
+    };
+    if !ask_confirmation(model, title, pending) {
+        perform_package_action(model, action, package).await;
+    }
+}
+
+/// Run a package action, once confirmed if it needed to be
+async fn perform_package_action(model: &mut Model, action: PackageAction, package: PackageEntry) {
+    match action {
+        PackageAction::Info if model.event_sender.is_some() => {
+            start_command(model, action.command(&package));
+        }
+        PackageAction::Info => {
+            let result = execute_adb_command(model, action.command(&package)).await;
+            update_result(model, result);
+        }
+        PackageAction::Logcat => {
+            start_logcat(model);
+            if model.state == AppState::Logcat {
+                apply_logcat_filter(model, LogcatField::Package, package.name);
+            }
+        }
+        _ if model.packages.running.is_some() => {}
+        _ => {
+            let command = action.command(&package);
+            let Some(sender) = model.event_sender.clone() else {
+                // Without an event loop (tests) the action runs inline
+                let result = model.adb_manager.execute(command).map_err(PollError::from);
+                package_action_done(model, action, &package, result);
+                return;
+            };
+            model.packages.status = Some(format!("{}: {}…", package.name, action.label(&package)));
+            let poll = spawn_poll(
+                &model.adb_manager,
+                sender,
+                None,
+                move |manager| manager.execute(command),
+                |id, result| Event::PackageAction(ActionEvent { id, result }),
+            );
+            model.packages.running = Some((poll, action, package));
+        }
+    }
+}
+
+/// Show how a package action went, reloading the list when it changed
+fn package_action_done(
+    model: &mut Model,
+    action: PackageAction,
+    package: &PackageEntry,
+    result: Result<String, PollError>,
+) {
+    model.packages.status = Some(match result {
+        Ok(output) => format!("{}: {}", package.name, output.trim()),
+        Err(e) => format!("{}: {}", package.name, e),
+    });
+    if matches!(action, PackageAction::Toggle | PackageAction::Uninstall) {
+        refresh_packages(model);
+    }
+}
+
+/// Run the highlighted action on the selected process
+fn run_process_action(model: &mut Model) {
+    let view = &mut model.processes;
+    let Some(action) = view.selected_action() else {
+        view.action = None;
+        return;
+    };
+    let Some(process) = view.selected_process().cloned() else {
+        return;
+    };
+    view.action = None;
+
+    let title = action.label(&process);
+    let pending = PendingAction::Process {
+        action,
+        process: process.clone(),
+    };
+    if !ask_confirmation(model, title, pending) {
+        perform_process_action(model, action, process);
+    }
+}
+
+/// Run a process action, once confirmed if it needed to be
+fn perform_process_action(model: &mut Model, action: ProcessAction, process: ProcessEntry) {
+    if model.processes.running.is_some() {
+        return;
+    }
+    let command = action.command(&process);
+    let Some(sender) = model.event_sender.clone() else {
+        // Without an event loop (tests) the action runs inline
+        let result = model.adb_manager.execute(command).map_err(PollError::from);
+        process_action_done(model, &process, result);
+        return;
+    };
+    model.processes.status = Some(format!(
+        "{} ({}): {}…",
+        process.name,
+        process.pid,
+        action.label(&process)
+    ));
+    let poll = spawn_poll(
+        &model.adb_manager,
+        sender,
+        None,
+        move |manager| manager.execute(command),
+        |id, result| Event::ProcessAction(ActionEvent { id, result }),
+    );
+    model.processes.running = Some((poll, process));
+}
+
+/// Show how a process action went and refresh the list
+fn process_action_done(
+    model: &mut Model,
+    process: &ProcessEntry,
+    result: Result<String, PollError>,
+) {
+    model.processes.status = Some(match result {
+        Ok(output) => format!("{} ({}): {}", process.name, process.pid, output.trim()),
+        Err(e) => format!("{} ({}): {}", process.name, process.pid, e),
+    });
+    model.processes.refresh_soon();
+}
+
+/// Show a command result in the result view
+fn update_result(model: &mut Model, result: CommandResult) {
+    model.clear_results();
+    match result {
+        CommandResult::Success(output) => model.set_output(output),
+        CommandResult::Error(error) => model.set_error(error),
+    }
+    model.state = AppState::ShowResult;
+    model.effects.start_slide_in();
+}
+
+/// Show the file browser at its current directories
+fn open_file_browser(model: &mut Model) {
+    model.files.status = None;
+    model.files.input = None;
+    refresh_local_pane(model);
+    refresh_remote_pane(model);
+    model.state = AppState::FileBrowser;
+    model.effects.start_slide_in();
+}
+
+/// Reload the local pane, reporting failures in the status line
+fn refresh_local_pane(model: &mut Model) {
+    if let Err(e) = model.files.refresh_local() {
+        model.files.local.set_entries(Vec::new());
+        model.files.status = Some(format!(
+            "Cannot read {}: {}",
+            model.files.local_dir.display(),
+            e
+        ));
+    }
+}
+
+/// List the device pane in the background
+fn refresh_remote_pane(model: &mut Model) {
+    let dir = model.files.remote_dir.clone();
+    let Some(sender) = model.event_sender.clone() else {
+        // Without an event loop (tests) the listing runs inline
+        let result = model
+            .adb_manager
+            .list_remote_dir(&dir)
+            .map_err(PollError::from);
+        remote_listed(model, result);
+        return;
+    };
+    let poll = spawn_poll(
+        &model.adb_manager,
+        sender,
+        Some(POLL_TIMEOUT),
+        move |manager| manager.list_remote_dir(&dir),
+        |id, result| Event::RemoteListing(ListingEvent { id, result }),
+    );
+    model.files.listing = Some(poll);
+}
+
+/// Show a device pane listing, reporting failures in the status line
+fn remote_listed(model: &mut Model, result: Result<Vec<RemoteEntry>, PollError>) {
+    model.files.listing = None;
+    record_poll(model, &result);
+    match result {
+        Ok(entries) => {
+            let entries = entries.into_iter().map(FileEntry::from).collect();
+            model.files.remote.set_entries(entries);
+        }
+        Err(e) => {
+            model.files.remote.set_entries(Vec::new());
+            model.files.status = Some(format!("Cannot read {}: {}", model.files.remote_dir, e));
+        }
+    }
+}
+
+/// Pull the selected device file or push the selected local file
+fn start_transfer(model: &mut Model) {
+    if model.files.transfer.is_some() {
+        model.files.status = Some("A transfer is already running".to_string());
+        return;
+    }
+    let Some(sender) = model.event_sender.clone() else {
+        model.files.status = Some("Transfers require the interactive event loop".to_string());
+        return;
+    };
+    let files = &mut model.files;
+    let Some(entry) = files.focused().selected_entry().cloned() else {
+        return;
+    };
+    if entry.is_dir {
+        files.status = Some("Only files can be copied".to_string());
+        return;
+    }
+
+    let worker = model.adb_manager.for_worker(CancelToken::default());
+    let files = &mut model.files;
+    let label = match files.focus {
+        Pane::Remote => {
+            let remote_path = join_remote(&files.remote_dir, &entry.name);
+            let local_path = files.local_dir.join(&entry.name);
+            spawn_pull(worker, remote_path, local_path, sender);
+            format!("Pulling {}", entry.name)
+        }
+        Pane::Local if model.adb_manager.is_read_only() => {
+            files.status = Some(AdbError::ReadOnly.to_string());
+            return;
+        }
+        Pane::Local => {
+            let local_path = files.local_dir.join(&entry.name);
+            let remote_path = join_remote(&files.remote_dir, &entry.name);
+            spawn_push(worker, local_path, remote_path, sender);
+            format!("Pushing {}", entry.name)
+        }
+    };
+    files.status = None;
+    files.transfer = Some(Transfer {
+        label,
+        transferred: 0,
+        total: Some(entry.size),
+    });
+}
+
+/// Delete the selected entry in the focused pane
+fn delete_selected(model: &mut Model) {
+    let files = &mut model.files;
+    let Some(entry) = files.focused().selected_entry().cloned() else {
+        return;
+    };
+
+    match files.focus {
+        Pane::Local => {
+            let path = files.local_dir.join(&entry.name);
+            let result = if entry.is_dir {
+                std::fs::remove_dir_all(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+            files.status = Some(match result {
+                Ok(()) => format!("Deleted {}", entry.name),
+                Err(e) => format!("Delete failed: {}", e),
+            });
+            refresh_local_pane(model);
+        }
+        Pane::Remote => {
+            let path = join_remote(&files.remote_dir, &entry.name);
+            start_file_action(model, FileInput::ConfirmDelete, move |manager| {
+                manager
+                    .delete_remote(&path)
+                    .map(|_| format!("Deleted {}", entry.name))
+            });
+        }
+    }
+}
+
+/// Rename the selected entry in the focused pane
+fn rename_selected(model: &mut Model, new_name: &str) {
+    let files = &mut model.files;
+    let Some(entry) = files.focused().selected_entry().cloned() else {
+        return;
+    };
+    if new_name.is_empty() || new_name == entry.name || new_name.contains('/') {
+        files.status = Some("Rename cancelled".to_string());
+        return;
+    }
+
+    let renamed = format!("Renamed {} → {}", entry.name, new_name);
+    match files.focus {
+        Pane::Local => {
+            let result = std::fs::rename(
+                files.local_dir.join(&entry.name),
+                files.local_dir.join(new_name),
+            );
+            files.status = Some(match result {
+                Ok(()) => renamed,
+                Err(e) => format!("Rename failed: {}", e),
+            });
+            refresh_local_pane(model);
+        }
+        Pane::Remote => {
+            let from = join_remote(&files.remote_dir, &entry.name);
+            let to = join_remote(&files.remote_dir, new_name);
+            start_file_action(model, FileInput::Rename, move |manager| {
+                manager.rename_remote(&from, &to).map(|_| renamed)
+            });
+        }
+    }
+}
+
+/// Run a device pane delete or rename in the background
+fn start_file_action(
+    model: &mut Model,
+    input: FileInput,
+    action: impl FnOnce(&mut AdbManager) -> AdbResult<String> + Send + 'static,
+) {
+    if model.files.running.is_some() {
+        model.files.status = Some("Another file operation is running".to_string());
+        return;
+    }
+    let Some(sender) = model.event_sender.clone() else {
+        // Without an event loop (tests) the action runs inline
+        let result = action(&mut model.adb_manager).map_err(PollError::from);
+        file_action_done(model, input, result);
+        return;
+    };
+    let poll = spawn_poll(&model.adb_manager, sender, None, action, |id, result| {
+        Event::FileAction(ActionEvent { id, result })
+    });
+    model.files.running = Some((poll, input));
+}
+
+/// Show how a device pane delete or rename went and reload the pane
+fn file_action_done(model: &mut Model, input: FileInput, result: Result<String, PollError>) {
+    model.files.status = Some(match (input, result) {
+        (_, Ok(status)) => status,
+        (FileInput::ConfirmDelete, Err(e)) => format!("Delete failed: {}", e),
+        (FileInput::Rename, Err(e)) => format!("Rename failed: {}", e),
+    });
+    refresh_remote_pane(model);
+}
+
+/// Show the device picker with the active device highlighted
+fn open_device_picker(model: &mut Model) {
+    model.device_selected = model
+        .active_device()
+        .and_then(|serial| model.devices.iter().position(|d| d.serial == serial))
+        .unwrap_or(0);
+    model.wireless.status = None;
+    model.state = AppState::DevicePicker;
+    refresh_devices(model);
+    model.effects.start_slide_in();
+}
+
+/// Keep the picker highlight on an existing row
+fn clamp_device_selection(model: &mut Model) {
+    model.device_selected = model
+        .device_selected
+        .min(model.devices.len().saturating_sub(1));
+}
+
+/// Keep the live device table in step with the tracker, announcing changes
+fn apply_device_event(model: &mut Model, event: DeviceEvent) {
+    if !matches!(event, DeviceEvent::Lost(_)) {
+        model.tracking = TrackingState::Live;
+    }
+    match event {
+        DeviceEvent::Listed(devices) => {
+            model.devices = devices;
+        }
+        DeviceEvent::Connected(device) => {
+            let text = format!("🔌 {} connected", device.display_name());
+            model.toasts.push(text, ToastLevel::Info);
+            model.devices.push(device);
+        }
+        DeviceEvent::Disconnected(serial) => {
+            let text = format!("🔌 {} disconnected", model.device_name(&serial));
+            model.toasts.push(text, ToastLevel::Warning);
+            model.devices.retain(|device| device.serial != serial);
+            // A device plugged back in starts with a clean slate
+            model.watchdog.record_response(&serial);
+        }
+        DeviceEvent::StateChanged { device, previous } => {
+            let level = if device.is_online() {
+                ToastLevel::Info
+            } else {
+                ToastLevel::Warning
+            };
+            let text = format!(
+                "📱 {}: {} → {}",
+                device.display_name(),
+                previous,
+                device.state
+            );
+            model.toasts.push(text, level);
+            match model.devices.iter_mut().find(|d| d.serial == device.serial) {
+                Some(known) => *known = device,
+                None => model.devices.push(device),
+            }
+        }
+        DeviceEvent::Lost(error) => {
+            let text = format!("⚠ Lost the ADB server: {}", error);
+            model.toasts.push(text, ToastLevel::Warning);
+            model.tracking = TrackingState::Lost(error);
+        }
+    }
+}
+
+/// Run a connect / pair / tcpip prompt from the device picker
+fn submit_wireless_prompt(model: &mut Model, prompt: WirelessPrompt, text: String) {
+    let result = match prompt {
+        WirelessPrompt::Connect => normalize_address(&text, DEFAULT_TCP_PORT).and_then(|address| {
+            let reply = model
+                .adb_manager
+                .connect_device(&address)
+                .map_err(|e| e.to_string())?;
+            if let Err(e) = model.wireless.recent.remember(&address) {
+                return Ok(format!(
+                    "{} (could not save recent addresses: {})",
+                    reply, e
+                ));
+            }
+            Ok(reply)
+        }),
+        WirelessPrompt::Pair => parse_pair_input(&text).and_then(|(address, code)| {
+            model
+                .adb_manager
+                .pair_device(&address, &code)
+                .map(|reply| format!("{}. Press c to connect.", reply.trim_end_matches('.')))
+                .map_err(|e| e.to_string())
+        }),
+        WirelessPrompt::TcpIp => match (text.trim().parse::<u16>(), model.highlighted_device()) {
+            (Err(_), _) => Err(format!("Invalid port: {}", text.trim())),
+            (Ok(_), None) => Err("No device highlighted".to_string()),
+            (Ok(port), Some(device)) => {
+                let serial = device.serial.clone();
+                model
+                    .adb_manager
+                    .tcpip_device(&serial, port)
+                    .map(|reply| reply.replace('\n', " "))
+                    .map_err(|e| e.to_string())
+            }
+        },
+    };
+
+    match result {
+        Ok(status) => model.wireless.status = Some(status),
+        Err(e) => {
+            model.wireless.status = Some(e);
+            // Keep the input so typos can be fixed
+            model.wireless.prompt = Some((prompt, text));
+        }
+    }
+    refresh_devices(model);
+}
+
+/// Reload the device list shown in the picker in the background
+fn refresh_devices(model: &mut Model) {
+    let Some(sender) = model.event_sender.clone() else {
+        // Without an event loop (tests) the listing runs inline
+        let result = model.adb_manager.devices().map_err(PollError::from);
+        devices_listed(model, result);
+        return;
+    };
+    let poll = spawn_poll(
+        &model.adb_manager,
+        sender,
+        Some(POLL_TIMEOUT),
+        |manager| manager.devices(),
+        |id, result| Event::DeviceList(DeviceListEvent { id, result }),
+    );
+    model.device_poll = Some(poll);
+}
+
+/// Show a fetched device list, keeping the highlight on the same device
+fn devices_listed(model: &mut Model, result: Result<Vec<DeviceInfo>, PollError>) {
+    model.device_poll = None;
+    match result {
+        Ok(devices) => {
+            let highlighted = model
+                .highlighted_device()
+                .map(|device| device.serial.clone())
+                .or_else(|| model.active_device().map(str::to_string));
+            model.devices = devices;
+            if let Some(index) =
+                highlighted.and_then(|serial| model.devices.iter().position(|d| d.serial == serial))
+            {
+                model.device_selected = index;
+            }
+            clamp_device_selection(model);
+        }
+        // The picker can't show anything without the list
+        Err(e) if model.state == AppState::DevicePicker => {
+            model.devices.clear();
+            model.set_error(describe_error(e));
+            model.state = AppState::ShowResult;
+        }
+        Err(_) => {}
+    }
+}
+
+/// Show the disconnect reply and reload the device list
+fn disconnect_done(model: &mut Model, result: Result<String, PollError>) {
+    model.disconnect_job = None;
+    model.wireless.status = Some(match result {
+        Ok(reply) => reply,
+        Err(e) => e.to_string(),
+    });
+    refresh_devices(model);
+}
+
+/// Count a background read towards the target device's watchdog
+///
+/// Warns once when the device stops answering; the header keeps showing it.
+fn record_poll<T>(model: &mut Model, result: &Result<T, PollError>) {
+    let Some(serial) = model.target_device().map(str::to_string) else {
+        return;
+    };
+    match result {
+        Err(e) if e.timed_out => {
+            if model.watchdog.record_timeout(&serial) {
+                let text = format!("⚠ {} is not responding", model.device_name(&serial));
+                model.toasts.push(text, ToastLevel::Warning);
+            }
+        }
+        _ => model.watchdog.record_response(&serial),
+    }
+}
+
+/// Advice for a device that stopped answering
+fn reconnect_hint(serial: &str) -> String {
+    let steps = match Transport::from_serial(serial) {
+        Transport::Tcp => format!(
+            "• Reconnect it (adb disconnect {serial} && adb connect {serial})\n• Check the device is still on the network"
+        ),
+        Transport::Emulator => "• Restart the emulator\n• Run adb reconnect".to_string(),
+        Transport::Usb => "• Unplug the USB cable and plug it back in\n• Run adb reconnect".to_string(),
+    };
+    format!("{} is not responding:\n{}", serial, steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adb::{AdbOutput, DeviceInfo};
+    use crate::record::RecordingSummary;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_menu_navigation() {
+        let mut model = Model::new();
+        model.state = AppState::Menu;
+
+        update(&mut model, Message::MenuDown).await;
+        assert_eq!(model.menu.selected, 1);
+
+        update(&mut model, Message::MenuUp).await;
+        assert_eq!(model.menu.selected, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_background_command() {
+        use crate::adb::MockBackend;
+
+        let (sender, _receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut model = Model::new();
+        model.adb_manager = AdbManager::with_backend(Box::new(MockBackend::demo()));
+        model.event_sender = Some(sender);
+        model.adb_manager.select_device("emulator-5554".to_string());
+
+        update(
+            &mut model,
+            Message::ExecuteCommand(AdbCommand::Shell {
+                command: "sleep 10".to_string(),
+            }),
+        )
+        .await;
+        assert_eq!(model.state, AppState::Loading);
+        assert!(model.command_job.is_some());
+
+        update(&mut model, Message::CancelCommand).await;
+        assert_eq!(model.state, AppState::Menu);
+        assert!(model.command_job.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_templated_command_prompts() {
+        let mut model = Model::new();
+        model.state = AppState::Menu;
+
+        update(
+            &mut model,
+            Message::ExecuteCommand(AdbCommand::Shell {
+                command: "pm path {package}".to_string(),
+            }),
+        )
+        .await;
+        assert_eq!(model.state, AppState::Menu);
+        assert_eq!(
+            model.command_prompt.as_ref().and_then(|p| p.current()),
+            Some("package")
+        );
+
+        for c in "com.example".chars() {
+            update(&mut model, Message::PromptInput(c)).await;
+        }
+        update(&mut model, Message::PromptCancel).await;
+        assert!(model.command_prompt.is_none());
+        assert_eq!(model.state, AppState::Menu);
+    }
+
+    #[tokio::test]
+    async fn test_quit() {
+        let mut model = Model::new();
+        assert!(model.running);
+
+        update(&mut model, Message::Quit).await;
+        assert!(!model.running);
+    }
+
+    #[tokio::test]
+    async fn test_scroll_boundaries() {
+        let mut model = Model::new();
+        model.wrapped_lines = vec!["Line 1".to_string(), "Line 2".to_string()];
+        model.scroll_position = 0;
+
+        // Can't scroll up from position 0
+        update(&mut model, Message::ScrollUp).await;
+        assert_eq!(model.scroll_position, 0);
+
+        // Can scroll down
+        update(&mut model, Message::ScrollDown).await;
+        assert_eq!(model.scroll_position, 1);
+
+        // Can't scroll past end
+        update(&mut model, Message::ScrollDown).await;
+        assert_eq!(model.scroll_position, 1);
+    }
+
+    #[tokio::test]
+    async fn test_enter_exit_child_mode() {
+        let mut model = Model::new();
+        model.state = AppState::Menu;
+
+        update(&mut model, Message::EnterChild).await;
+        assert!(model.menu.is_in_child_mode());
+
+        update(&mut model, Message::ExitChild).await;
+        assert!(!model.menu.is_in_child_mode());
+    }
+
+    fn test_device(serial: &str) -> DeviceInfo {
+        DeviceInfo {
+            serial: serial.to_string(),
+            state: "device".to_string(),
+            model: None,
+            device: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_device_picker_selection() {
+        let mut model = Model::new();
+        model.state = AppState::DevicePicker;
+        model.devices = vec![test_device("phone-1"), test_device("emulator-5554")];
+
+        update(&mut model, Message::DeviceUp).await;
+        assert_eq!(model.device_selected, 0);
+
+        update(&mut model, Message::DeviceDown).await;
+        update(&mut model, Message::DeviceDown).await;
+        assert_eq!(model.device_selected, 1);
+
+        update(&mut model, Message::SelectDevice).await;
+        assert_eq!(model.active_device(), Some("emulator-5554"));
+        assert_eq!(model.state, AppState::Menu);
+    }
+
+    #[tokio::test]
+    async fn test_wireless_prompt_keeps_invalid_input() {
+        let mut model = Model::new();
+        model.state = AppState::DevicePicker;
+
+        update(
+            &mut model,
+            Message::OpenWirelessPrompt(WirelessPrompt::Pair),
+        )
+        .await;
+        for c in "10.0.0.2 123456".chars() {
+            update(&mut model, Message::WirelessInput(c)).await;
+        }
+        update(&mut model, Message::WirelessSubmit).await;
+        assert!(model.wireless.status.is_some());
+        assert_eq!(
+            model.wireless.prompt,
+            Some((WirelessPrompt::Pair, "10.0.0.2 123456".to_string()))
+        );
+
+        update(&mut model, Message::WirelessCancel).await;
+        assert!(model.wireless.prompt.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_logcat_filter_editing() {
+        let mut model = Model::new();
+        model.state = AppState::Logcat;
+
+        update(&mut model, Message::LogcatEditFilter(LogcatField::Tag)).await;
+        for c in "Activity".chars() {
+            update(&mut model, Message::LogcatInput(c)).await;
+        }
+        update(&mut model, Message::LogcatSubmitFilter).await;
+        assert_eq!(model.logcat.filter().tag.as_deref(), Some("Activity"));
+        assert!(model.logcat.input.is_none());
+
+        update(&mut model, Message::LogcatEditFilter(LogcatField::Regex)).await;
+        update(&mut model, Message::LogcatInput('(')).await;
+        update(&mut model, Message::LogcatSubmitFilter).await;
+        assert!(model.logcat.filter().regex.is_none());
+        assert!(model.logcat.input.is_some());
+        assert!(model.logcat.status.is_some());
+
+        update(&mut model, Message::LogcatCancelInput).await;
+        update(&mut model, Message::LogcatClearFilters).await;
+        assert!(model.logcat.filter().tag.is_none());
+
+        update(&mut model, Message::StopLogcat).await;
+        assert_eq!(model.state, AppState::Menu);
+    }
+
+    #[tokio::test]
+    async fn test_file_browser_local_pane() {
+        let dir = std::env::temp_dir().join(format!("droidtui-files-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("a.txt"), "hello").unwrap();
+
+        let mut model = Model::new();
+        model.state = AppState::FileBrowser;
+        model.files.focus = Pane::Local;
+        model.files.local_dir = dir.clone();
+        update(&mut model, Message::FileRefresh).await;
+        assert_eq!(model.files.local.entries.len(), 2);
+        assert!(model.files.local.entries[0].is_dir);
+
+        update(&mut model, Message::FileOpen).await;
+        assert_eq!(model.files.local_dir, dir.join("sub"));
+        update(&mut model, Message::FileParent).await;
+        assert_eq!(model.files.local_dir, dir);
+
+        update(&mut model, Message::FileDown).await;
+        update(&mut model, Message::FileRename).await;
+        for _ in 0.."a.txt".len() {
+            update(&mut model, Message::FileBackspace).await;
+        }
+        for c in "b.txt".chars() {
+            update(&mut model, Message::FileInput(c)).await;
+        }
+        update(&mut model, Message::FileSubmitInput).await;
+        assert!(dir.join("b.txt").exists());
+
+        update(&mut model, Message::FileDelete).await;
+        update(&mut model, Message::FileSubmitInput).await;
+        assert!(!dir.join("b.txt").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_file_browser_symlinks() {
+        use crate::adb::MockBackend;
+        use crate::files::FileEntry;
+
+        let mock = MockBackend::demo().respond("[ -d '/sdcard/dir-link' ]", "dir\n");
+        let mut model = Model::new();
+        model.adb_manager = AdbManager::with_backend(Box::new(mock));
+        model.state = AppState::FileBrowser;
+        model.files.focus = Pane::Remote;
+        model.files.remote_dir = "/sdcard".to_string();
+        let link = |name: &str| FileEntry {
+            name: name.to_string(),
+            is_dir: false,
+            is_symlink: true,
+            size: 0,
+        };
+        model
+            .files
+            .remote
+            .set_entries(vec![link("dir-link"), link("file-link")]);
+
+        // A link to a file stays put
+        update(&mut model, Message::FileDown).await;
+        update(&mut model, Message::FileOpen).await;
+        assert_eq!(model.files.remote_dir, "/sdcard");
+        assert!(model
+            .files
+            .status
+            .as_ref()
+            .unwrap()
+            .contains("links to a file"));
+
+        update(&mut model, Message::FileUp).await;
+        update(&mut model, Message::FileOpen).await;
+        assert_eq!(model.files.remote_dir, "/sdcard/dir-link");
+    }
+
+    #[tokio::test]
+    async fn test_package_search_and_actions() {
+        let mut model = Model::new();
+        model.state = AppState::Packages;
+        model
+            .packages
+            .set_entries(crate::adb::packages::build_package_entries(
+                "package:com.android.settings\npackage:com.example.app\n",
+                "package:com.example.app\n",
+                "",
+            ));
+
+        update(&mut model, Message::PackageSearch).await;
+        for c in "exa".chars() {
+            update(&mut model, Message::PackageInput(c)).await;
+        }
+        update(&mut model, Message::PackageSearchDone).await;
+        assert!(!model.packages.searching);
+        assert_eq!(
+            model.packages.selected_package().map(|p| p.name.as_str()),
+            Some("com.example.app")
+        );
+
+        update(&mut model, Message::PackageOpenActions).await;
+        update(&mut model, Message::PackageDown).await;
+        assert_eq!(model.packages.action, Some(1));
+        update(&mut model, Message::PackageCloseActions).await;
+        assert!(model.packages.action.is_none());
+    }
+
+    #[tokio::test]
     async fn test_clear_results() {
         let mut model = Model::new();
         model.set_result("Test output".to_string());
@@ -319,4 +2073,534 @@ mod tests {
         assert!(model.result_lines.is_empty());
         assert_eq!(model.state, AppState::Menu);
     }
+
+    #[tokio::test]
+    async fn test_parsed_output_panel() {
+        let raw = "Current Battery Service state:\n  level: 42\n  scale: 100\n".to_string();
+        let status = crate::adb::BatteryStatus::parse(&raw).unwrap();
+        let mut model = Model::new();
+        model.state = AppState::Loading;
+
+        update(
+            &mut model,
+            Message::CommandCompleted(CommandResult::Success(AdbOutput::Battery { status, raw })),
+        )
+        .await;
+        assert_eq!(model.state, AppState::ShowResult);
+        assert!(matches!(
+            model.result_panel,
+            Some(AdbOutput::Battery { .. })
+        ));
+        assert_eq!(model.result_lines[1], "  level: 42");
+
+        update(&mut model, Message::ReturnToMenu).await;
+        assert!(model.result_panel.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dashboard() {
+        let mut model = Model::new();
+        model.state = AppState::Menu;
+        update(&mut model, Message::ExecuteCommand(AdbCommand::Dashboard)).await;
+        assert_eq!(model.state, AppState::Dashboard);
+
+        let sample = crate::adb::DeviceSample::parse(
+            "0.50 0.40 0.30 1/100 42\n--droidtui--\n--droidtui--\nMemTotal: 1000 kB\nMemAvailable: 250 kB\n",
+            std::time::Instant::now(),
+        );
+        update(&mut model, Message::DashboardSample(Ok(sample))).await;
+        assert_eq!(model.dashboard.memory.latest(), Some(75));
+
+        update(
+            &mut model,
+            Message::DashboardSample(Err(PollError {
+                message: "offline".to_string(),
+                timed_out: false,
+            })),
+        )
+        .await;
+        assert_eq!(model.dashboard.error.as_deref(), Some("offline"));
+
+        // Polls that time out count towards the watchdog
+        model.adb_manager.select_device("emulator-5554".to_string());
+        let timeout = || PollError::from(AdbError::Timeout(POLL_TIMEOUT));
+        update(&mut model, Message::DashboardSample(Err(timeout()))).await;
+        update(&mut model, Message::DashboardSample(Err(timeout()))).await;
+        assert!(model.watchdog.is_unresponsive("emulator-5554"));
+        assert!(!model.toasts.is_empty());
+
+        let interval = model.dashboard.interval;
+        update(&mut model, Message::DashboardSlower).await;
+        assert_eq!(model.dashboard.interval, interval * 2);
+
+        update(&mut model, Message::ReturnToMenu).await;
+        assert_eq!(model.state, AppState::Menu);
+    }
+
+    #[tokio::test]
+    async fn test_process_manager() {
+        let mut model = Model::new();
+        model.state = AppState::Menu;
+        update(
+            &mut model,
+            Message::ExecuteCommand(AdbCommand::ManageProcesses),
+        )
+        .await;
+        assert_eq!(model.state, AppState::Processes);
+
+        let sample = crate::adb::ProcessSample::parse(
+            "PID PPID USER RSS VSZ S NAME\n\
+             1 0 root 100 200 S init\n\
+             900 1 u0_a12 5000 9000 S com.example.app\n",
+        );
+        update(&mut model, Message::ProcessSample(Ok(sample))).await;
+        assert_eq!(model.processes.entries.len(), 2);
+
+        update(&mut model, Message::ProcessCycleSort).await;
+        assert_eq!(model.processes.selected_process().map(|p| p.pid), Some(900));
+
+        update(&mut model, Message::ProcessSearch).await;
+        for c in "root".chars() {
+            update(&mut model, Message::ProcessInput(c)).await;
+        }
+        update(&mut model, Message::ProcessSearchDone).await;
+        assert_eq!(model.processes.visible().len(), 1);
+
+        update(&mut model, Message::ProcessOpenActions).await;
+        assert_eq!(model.processes.selected_pid, Some(1));
+        assert_eq!(model.processes.action, Some(0));
+        update(&mut model, Message::ProcessCloseActions).await;
+        assert!(model.processes.action.is_none());
+
+        update(&mut model, Message::ProcessOpenActions).await;
+        update(&mut model, Message::ProcessRunAction).await;
+        assert_eq!(model.state, AppState::Confirm);
+        update(&mut model, Message::ConfirmDecline).await;
+        assert_eq!(model.state, AppState::Processes);
+
+        update(&mut model, Message::ReturnToMenu).await;
+        assert_eq!(model.state, AppState::Menu);
+    }
+
+    #[tokio::test]
+    async fn test_process_action_runs_in_background() {
+        use crate::adb::{MockBackend, Signal};
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut model = Model::new();
+        let mock = MockBackend::demo();
+        model.adb_manager = AdbManager::with_backend(Box::new(mock.clone()));
+        model.adb_manager.select_device("emulator-5554".to_string());
+        model.event_sender = Some(sender);
+
+        let process =
+            crate::adb::ps::parse_ps("900 1 u0_a12 5000 9000 S com.example.app\n").remove(0);
+        perform_process_action(&mut model, ProcessAction::Signal(Signal::Cont), process);
+        let id = model.processes.running.as_ref().unwrap().0.id;
+        assert!(model.processes.status.as_deref().unwrap().ends_with('…'));
+
+        let result = loop {
+            match receiver.recv().await {
+                Some(Event::ProcessAction(ActionEvent { id: done, result })) if done == id => {
+                    break result
+                }
+                Some(_) => {}
+                None => panic!("worker dropped the sender"),
+            }
+        };
+        update(&mut model, Message::ProcessActionDone(result)).await;
+        assert!(model.processes.running.is_none());
+        assert!(model
+            .processes
+            .status
+            .as_deref()
+            .unwrap()
+            .starts_with("com.example.app (900)"));
+        assert!(mock
+            .calls()
+            .iter()
+            .any(|call| call.contains("kill -CONT 900")));
+    }
+
+    #[tokio::test]
+    async fn test_remote_rename_runs_in_background() {
+        use crate::adb::MockBackend;
+        use crate::files::FileEntry;
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut model = Model::new();
+        let mock = MockBackend::demo().respond("mv ", "");
+        model.adb_manager = AdbManager::with_backend(Box::new(mock.clone()));
+        model.adb_manager.select_device("emulator-5554".to_string());
+        model.event_sender = Some(sender);
+        model.state = AppState::FileBrowser;
+        model.files.focus = Pane::Remote;
+        model.files.remote_dir = "/sdcard".to_string();
+        model.files.remote.set_entries(vec![FileEntry {
+            name: "a.txt".to_string(),
+            is_dir: false,
+            is_symlink: false,
+            size: 5,
+        }]);
+
+        rename_selected(&mut model, "b.txt");
+        let id = model.files.running.as_ref().unwrap().0.id;
+        let result = loop {
+            match receiver.recv().await {
+                Some(Event::FileAction(ActionEvent { id: done, result })) if done == id => {
+                    break result
+                }
+                Some(_) => {}
+                None => panic!("worker dropped the sender"),
+            }
+        };
+        update(&mut model, Message::FileActionDone(result)).await;
+        assert!(model.files.running.is_none());
+        assert_eq!(model.files.status.as_deref(), Some("Renamed a.txt → b.txt"));
+        assert!(mock
+            .calls()
+            .iter()
+            .any(|call| call.contains("mv '/sdcard/a.txt' '/sdcard/b.txt'")));
+
+        // The pane reloads through its own poll
+        let id = model.files.listing.as_ref().unwrap().id;
+        let result = loop {
+            match receiver.recv().await {
+                Some(Event::RemoteListing(ListingEvent { id: done, result })) if done == id => {
+                    break result
+                }
+                Some(_) => {}
+                None => panic!("worker dropped the sender"),
+            }
+        };
+        update(&mut model, Message::RemoteListed(result)).await;
+        assert!(model.files.listing.is_none());
+        assert!(model.files.remote.entries.iter().all(|e| e.name != "a.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_confirm_destructive_command() {
+        let mut model = Model::new();
+        model.state = AppState::Menu;
+        model.adb_manager.select_device("emulator-5554".to_string());
+        let reboot = AdbCommand::Shell {
+            command: "reboot".to_string(),
+        };
+
+        update(&mut model, Message::ExecuteCommand(reboot.clone())).await;
+        assert_eq!(model.state, AppState::Confirm);
+        let confirmation = model.confirmation.as_ref().unwrap();
+        assert_eq!(confirmation.device.as_deref(), Some("emulator-5554"));
+        assert_eq!(confirmation.detail(), "reboot");
+        update(&mut model, Message::ConfirmDecline).await;
+        assert_eq!(model.state, AppState::Menu);
+        assert!(model.confirmation.is_none());
+
+        // Read-only mode refuses without asking
+        model.adb_manager.set_read_only(true);
+        update(&mut model, Message::ExecuteCommand(reboot)).await;
+        assert_eq!(model.state, AppState::ShowResult);
+        assert!(model
+            .command_error
+            .as_deref()
+            .unwrap()
+            .contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn test_reboot_progress() {
+        let mut model = Model::new();
+        model.state = AppState::Rebooting;
+        model.reboot = Some(RebootWatch::new(RebootTarget::Recovery, None));
+
+        update(
+            &mut model,
+            Message::RebootProgress(Ok("emulator-5554".to_string())),
+        )
+        .await;
+        let watch = model.reboot.as_ref().unwrap();
+        assert_eq!(watch.phase, RebootPhase::Waiting);
+        assert_eq!(watch.serial.as_deref(), Some("emulator-5554"));
+
+        update(
+            &mut model,
+            Message::RebootProgress(Ok("emulator-5554 is back in recovery mode".to_string())),
+        )
+        .await;
+        assert!(model.reboot.is_none());
+        assert_eq!(model.state, AppState::ShowResult);
+        assert!(model
+            .command_result
+            .as_deref()
+            .unwrap()
+            .starts_with("emulator-5554 is back"));
+    }
+
+    #[tokio::test]
+    async fn test_recording_finished() {
+        let mut model = Model::new();
+        model.state = AppState::Recording;
+
+        let summary = RecordingSummary {
+            path: "/tmp/recording-20240131-142502.mp4".into(),
+            bytes: 2048,
+            duration: Duration::from_secs(12),
+            mp4: true,
+        };
+        update(&mut model, Message::RecordingFinished(Ok(summary))).await;
+        assert_eq!(model.state, AppState::ShowResult);
+        assert!(model
+            .command_result
+            .as_deref()
+            .unwrap()
+            .starts_with("Recording saved to /tmp/recording-20240131-142502.mp4"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_reports_errors() {
+        // Without an event loop there is nothing to report the stream back to
+        let mut model = Model::new();
+        update(
+            &mut model,
+            Message::ExecuteCommand(AdbCommand::Shell {
+                command: "STREAM".to_string(),
+            }),
+        )
+        .await;
+        assert_eq!(model.state, AppState::ShowResult);
+        assert!(model.stream_state.is_none());
+        assert!(model.command_error.is_some());
+
+        model.state = AppState::Streaming;
+        update(
+            &mut model,
+            Message::StreamEnded(Err("Failed to create window".to_string())),
+        )
+        .await;
+        assert_eq!(model.state, AppState::ShowResult);
+        assert_eq!(
+            model.command_error.as_deref(),
+            Some("Screen stream failed: Failed to create window")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_pipeline() {
+        use crate::adb::MockBackend;
+        use crate::worker::CommandEvent;
+        use ratatui::{buffer::Buffer, layout::Rect};
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mock = MockBackend::demo();
+        let mut model = Model::new();
+        model.adb_manager = AdbManager::with_backend(Box::new(mock.clone()));
+        model.event_sender = Some(sender);
+
+        update(
+            &mut model,
+            Message::ExecuteCommand(AdbCommand::GetBatteryInfo),
+        )
+        .await;
+        assert_eq!(model.state, AppState::Loading);
+        let id = model.command_job.as_ref().unwrap().id;
+        let result = loop {
+            match receiver.recv().await {
+                Some(Event::Command(CommandEvent::Finished { id: done, result })) if done == id => {
+                    break result
+                }
+                Some(_) => {}
+                None => panic!("worker dropped the sender"),
+            }
+        };
+        update(&mut model, Message::CommandCompleted(result)).await;
+        assert_eq!(model.state, AppState::ShowResult);
+        assert!(matches!(
+            model.result_panel,
+            Some(AdbOutput::Battery { .. })
+        ));
+        assert_eq!(
+            mock.calls(),
+            ["devices", "shell emulator-5554 dumpsys battery"]
+        );
+
+        let area = Rect::new(0, 0, 120, 40);
+        let mut buffer = Buffer::empty(area);
+        crate::view::render(&mut model, area, &mut buffer);
+        let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("87%"));
+        assert!(screen.contains("Current Battery Service state:"));
+    }
+
+    #[tokio::test]
+    async fn test_shell_exit_status_result() {
+        use crate::adb::{MockBackend, ShellOutput};
+        use ratatui::{buffer::Buffer, layout::Rect};
+
+        let mock = MockBackend::demo().respond_with(
+            "ls /missing",
+            ShellOutput {
+                stdout: String::new(),
+                stderr: "ls: /missing: No such file or directory\n".to_string(),
+                exit_code: Some(1),
+            },
+        );
+        let mut model = Model::new();
+        model.adb_manager = AdbManager::with_backend(Box::new(mock));
+        let output = model
+            .adb_manager
+            .execute_output(AdbCommand::Shell {
+                command: "ls /missing".to_string(),
+            })
+            .unwrap();
+        update(
+            &mut model,
+            Message::CommandCompleted(CommandResult::Success(output)),
+        )
+        .await;
+        assert_eq!(model.exit_code, Some(1));
+
+        let area = Rect::new(0, 0, 120, 40);
+        let mut buffer = Buffer::empty(area);
+        crate::view::render(&mut model, area, &mut buffer);
+        let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("Exited with status 1"));
+        assert!(!screen.contains("Command Result"));
+
+        // Built-in commands report a failing exit status as an error
+        let error = model
+            .adb_manager
+            .execute(AdbCommand::UninstallPackage {
+                package_name: "com.android.settings".to_string(),
+            })
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Exited with status 1: Failure [DELETE_FAILED_INTERNAL_ERROR]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_device() {
+        use crate::adb::MockBackend;
+        use crate::worker::CommandEvent;
+        use ratatui::{buffer::Buffer, layout::Rect};
+
+        let render = |model: &mut Model| {
+            let area = Rect::new(0, 0, 120, 40);
+            let mut buffer = Buffer::empty(area);
+            crate::view::render(model, area, &mut buffer);
+            buffer
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut model = Model::new();
+        model.adb_manager = AdbManager::with_backend(Box::new(MockBackend::demo()));
+        model.adb_manager.select_device("emulator-5554".to_string());
+        model.event_sender = Some(sender);
+        model.state = AppState::Menu;
+        model.watchdog.record_timeout("emulator-5554");
+        model.watchdog.record_timeout("emulator-5554");
+        assert!(render(&mut model).contains("Not responding"));
+
+        // Any answer from the device clears the warning
+        update(
+            &mut model,
+            Message::ExecuteCommand(AdbCommand::GetBatteryInfo),
+        )
+        .await;
+        let id = model.command_job.as_ref().unwrap().id;
+        let result = loop {
+            match receiver.recv().await {
+                Some(Event::Command(CommandEvent::Finished { id: done, result })) if done == id => {
+                    break result
+                }
+                Some(_) => {}
+                None => panic!("worker dropped the sender"),
+            }
+        };
+        update(&mut model, Message::CommandCompleted(result)).await;
+        assert!(!model.watchdog.is_unresponsive("emulator-5554"));
+        assert!(!render(&mut model).contains("Not responding"));
+
+        assert!(reconnect_hint("192.168.1.20:5555").contains("adb disconnect 192.168.1.20:5555"));
+        assert!(reconnect_hint("0123456789ABCDEF").contains("USB cable"));
+    }
+
+    #[tokio::test]
+    async fn test_device_events() {
+        use crate::hotplug::DeviceEvent;
+        use ratatui::{buffer::Buffer, layout::Rect};
+
+        let device = |serial: &str, state: &str| DeviceInfo {
+            serial: serial.to_string(),
+            state: state.to_string(),
+            model: Some("Pixel_8".to_string()),
+            device: None,
+        };
+        let render = |model: &mut Model| {
+            let area = Rect::new(0, 0, 140, 40);
+            let mut buffer = Buffer::empty(area);
+            crate::view::render(model, area, &mut buffer);
+            buffer
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+
+        let mut model = Model::new();
+        model.state = AppState::Menu;
+        update(
+            &mut model,
+            Message::DevicesChanged(DeviceEvent::Listed(vec![device("emulator-5554", "device")])),
+        )
+        .await;
+        assert_eq!(model.tracking, TrackingState::Live);
+        assert!(model.toasts.is_empty());
+        // The only device is used without selecting it
+        assert_eq!(model.target_device(), Some("emulator-5554"));
+        assert_eq!(model.active_device(), None);
+        assert!(render(&mut model).contains("● Connected"));
+
+        update(
+            &mut model,
+            Message::DevicesChanged(DeviceEvent::StateChanged {
+                device: device("emulator-5554", "offline"),
+                previous: "device".to_string(),
+            }),
+        )
+        .await;
+        assert_eq!(model.devices[0].state, "offline");
+        let screen = render(&mut model);
+        assert!(screen.contains("● offline"));
+        assert!(screen.contains("device → offline"));
+
+        model.adb_manager.select_device("emulator-5554".to_string());
+        update(
+            &mut model,
+            Message::DevicesChanged(DeviceEvent::Connected(device("0123456789ABCDEF", "device"))),
+        )
+        .await;
+        update(
+            &mut model,
+            Message::DevicesChanged(DeviceEvent::Disconnected("emulator-5554".to_string())),
+        )
+        .await;
+        assert_eq!(model.devices.len(), 1);
+        let screen = render(&mut model);
+        assert!(screen.contains("Pixel 8 (0123456789ABCDEF) connected"));
+        assert!(screen.contains("○ Disconnected"));
+
+        update(
+            &mut model,
+            Message::DevicesChanged(DeviceEvent::Lost("Connection refused".to_string())),
+        )
+        .await;
+        assert!(render(&mut model).contains("ADB server unreachable"));
+        assert_eq!(model.devices.len(), 1);
+    }
 }
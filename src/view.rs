@@ -1,27 +1,328 @@
+use crate::adb::format_size;
+use crate::adb::{AdbOutput, Transport};
+use crate::dashboard::History;
 use crate::effects::{
     get_dots_orbit, get_loading_dots, get_loading_spinner, get_orbital_spinner,
-    get_particle_effect, get_progress_bar, get_wave_animation, RevealWidget,
+    get_particle_effect, get_progress_bar, get_transfer_progress_bar, get_wave_animation,
+    RevealWidget,
 };
+use crate::files::{FileInput, Pane, PaneState};
+use crate::hotplug::TrackingState;
 use crate::model::{AppState, Model};
+use crate::packages::PackageAction;
+use crate::preview::{self, GraphicsProtocol};
+use crate::processes::{ProcessAction, ProcessSort};
+use crate::reboot::RebootPhase;
+use crate::template::CommandPrompt;
+use crate::toast::ToastLevel;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    widgets::{Block, BorderType, Paragraph, Widget},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Clear, Gauge, List, ListItem, Paragraph, Row, Sparkline, Table, Widget,
+        Wrap,
+    },
 };
+use std::rc::Rc;
 
 /// Main view function - renders the entire UI based on the model
 /// This is pure function that takes model and produces UI
 pub fn render(model: &mut Model, area: Rect, buf: &mut Buffer) {
-    match model.state {
+    render_state(model, model.state, area, buf);
+    if model.state != AppState::Startup {
+        render_toasts(model, area, buf);
+    }
+}
+
+fn render_state(model: &mut Model, state: AppState, area: Rect, buf: &mut Buffer) {
+    match state {
         AppState::Startup => render_startup(model, area, buf),
         AppState::Menu => render_menu(model, area, buf),
         AppState::Loading => render_loading(model, area, buf),
         AppState::Executing => render_executing(model, area, buf),
         AppState::ShowResult => render_result(model, area, buf),
+        AppState::DevicePicker => render_device_picker(model, area, buf),
+        AppState::Logcat => render_logcat(model, area, buf),
+        AppState::FileBrowser => render_file_browser(model, area, buf),
+        AppState::Packages => render_packages(model, area, buf),
+        AppState::Screenshot => render_screenshot(model, area, buf),
+        AppState::Mirror => render_mirror(model, area, buf),
+        AppState::Dashboard => render_dashboard(model, area, buf),
+        AppState::Processes => render_processes(model, area, buf),
+        AppState::Rebooting => render_rebooting(model, area, buf),
+        AppState::Recording => render_recording(model, area, buf),
+        AppState::Streaming => render_streaming(model, area, buf),
+        AppState::Confirm => {
+            // The modal sits on top of the screen the command came from
+            if let Some(previous) = model.confirmation.as_ref().map(|c| c.previous) {
+                render_state(model, previous, area, buf);
+            }
+            render_confirmation(model, area, buf);
+        }
+    }
+}
+
+/// Header indicator for the target device and the server, and whether all is well
+fn connection_status(model: &Model) -> (Option<String>, bool) {
+    match &model.tracking {
+        TrackingState::Starting => return (None, true),
+        TrackingState::Lost(_) => return (Some("⚠ ADB server unreachable".to_string()), false),
+        TrackingState::Live => {}
+    }
+    let Some(serial) = model.target_device() else {
+        return match model.devices.len() {
+            0 => (Some("○ No devices attached".to_string()), false),
+            count => (Some(format!("🔌 {} devices attached", count)), true),
+        };
+    };
+    match model.devices.iter().find(|d| d.serial == serial) {
+        Some(device) if device.is_online() => (Some("● Connected".to_string()), true),
+        Some(device) => (Some(format!("● {}", device.state)), false),
+        None => (Some("○ Disconnected".to_string()), false),
     }
 }
 
+/// Render notifications in the top right corner below the header, newest at the bottom
+fn render_toasts(model: &Model, area: Rect, buf: &mut Buffer) {
+    let mut y = area.y + 3;
+    for toast in model.toasts.iter() {
+        let width = (toast.text.chars().count() as u16 + 4).min(area.width);
+        if y + 3 > area.bottom() {
+            break;
+        }
+        let toast_area = Rect {
+            x: area.right().saturating_sub(width + 1).max(area.x),
+            y,
+            width,
+            height: 3,
+        };
+        let color = match toast.level {
+            ToastLevel::Info => Color::LightGreen,
+            ToastLevel::Warning => Color::Yellow,
+        };
+        Clear.render(toast_area, buf);
+        Paragraph::new(toast.text.clone())
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .style(Style::default().fg(color)),
+            )
+            .style(Style::default().fg(Color::White))
+            .render(toast_area, buf);
+        y += 3;
+    }
+}
+
+/// Render the confirmation modal for a destructive command
+fn render_confirmation(model: &Model, area: Rect, buf: &mut Buffer) {
+    let Some(confirmation) = &model.confirmation else {
+        return;
+    };
+    let popup_area = centered_rect(60, 30, area);
+    Clear.render(popup_area, buf);
+
+    let block = Block::bordered()
+        .title("⚠️ Confirm  y:Run  any other key:Cancel")
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::LightRed));
+
+    let device = confirmation.device.as_deref().unwrap_or("default device");
+    let lines = vec![
+        Line::from(Span::styled(
+            confirmation.title.clone(),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            confirmation.detail(),
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("on ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                device.to_string(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+    ];
+
+    Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .render(popup_area, buf);
+}
+
+/// Render the reboot status while the device is away
+fn render_rebooting(model: &Model, area: Rect, buf: &mut Buffer) {
+    let Some(watch) = &model.reboot else {
+        return;
+    };
+    let popup_area = centered_rect(60, 40, area);
+    Clear.render(popup_area, buf);
+
+    let block = Block::bordered()
+        .title("🔄 Reboot  Esc:Stop waiting")
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Yellow));
+
+    let step = match watch.phase {
+        RebootPhase::Rebooting => "1/2",
+        RebootPhase::Waiting => "2/2",
+    };
+    let elapsed = watch.elapsed(std::time::Instant::now());
+    let lines = vec![
+        Line::from(Span::styled(
+            format!(
+                "{} {}",
+                get_loading_spinner(model.loading_counter),
+                watch.status()
+            ),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("Step {}  ⏱ {}s", step, elapsed.as_secs()),
+            Style::default().fg(Color::Gray),
+        )),
+    ];
+
+    Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .render(popup_area, buf);
+}
+
+fn render_streaming(model: &Model, area: Rect, buf: &mut Buffer) {
+    let Some(stream) = &model.stream_state else {
+        return;
+    };
+    let popup_area = centered_rect(60, 40, area);
+    Clear.render(popup_area, buf);
+
+    let block = Block::bordered()
+        .title("📺 Screen Stream  s/Esc:Stop")
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Green));
+
+    let device = stream.serial.as_deref().unwrap_or("device");
+    let status = if stream.started {
+        format!("● Streaming {} in a separate window", device)
+    } else {
+        format!(
+            "{} Starting stream of {}…",
+            get_loading_spinner(model.loading_counter),
+            device
+        )
+    };
+    let config = &stream.config;
+    let lines = vec![
+        Line::from(Span::styled(
+            status,
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{}x{} @ {}", config.width, config.height, config.bitrate),
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(Span::styled(
+            "Click, drag and type in the window to control the device",
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(Span::styled(
+            "Close the window or press Esc in it to stop",
+            Style::default().fg(Color::Gray),
+        )),
+    ];
+
+    Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .render(popup_area, buf);
+}
+
+fn render_recording(model: &Model, area: Rect, buf: &mut Buffer) {
+    let Some(recording) = &model.recording else {
+        return;
+    };
+    let popup_area = centered_rect(60, 40, area);
+    Clear.render(popup_area, buf);
+
+    let block = Block::bordered()
+        .title("⏺️ Recording  s/Esc:Stop")
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::LightRed));
+
+    let status = if recording.stopping {
+        format!(
+            "{} Finishing file…",
+            get_loading_spinner(model.loading_counter)
+        )
+    } else {
+        "● REC".to_string()
+    };
+    let config = &recording.options.config;
+    let lines = vec![
+        Line::from(Span::styled(
+            status,
+            Style::default()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "⏱ {}s / {}s  💾 {}",
+                recording.started.elapsed().as_secs(),
+                recording.options.max_duration.as_secs(),
+                format_size(recording.written())
+            ),
+            Style::default().fg(Color::White),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "{}x{} @ {}{}",
+                config.width,
+                config.height,
+                config.bitrate,
+                if recording.options.show_window {
+                    "  📺 window"
+                } else {
+                    ""
+                }
+            ),
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(Span::styled(
+            format!("Saving to {}", recording.dir.display()),
+            Style::default().fg(Color::Gray),
+        )),
+    ];
+
+    Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .render(popup_area, buf);
+}
+
 /// Render startup screen with animations
 fn render_startup(model: &mut Model, area: Rect, buf: &mut Buffer) {
     let reveal_widget = RevealWidget::new(
@@ -162,9 +463,38 @@ fn render_menu(model: &mut Model, area: Rect, buf: &mut Buffer) {
         .border_type(BorderType::Rounded)
         .style(Style::default().fg(Color::Green));
 
-    let header = Paragraph::new("ADB Command Interface")
+    let device_text = match (model.active_device(), model.target_device()) {
+        (Some(serial), _) => format!("📱 Device: {}", model.device_name(serial)),
+        (None, Some(serial)) => format!("📱 Device: {} (only one)", model.device_name(serial)),
+        (None, None) => "📱 No device selected (press d to choose)".to_string(),
+    };
+    let mut device_text = format!(
+        "{}   🖥 Server: {}",
+        device_text,
+        model.adb_manager.server_address()
+    );
+    if model.adb_manager.is_read_only() {
+        device_text.push_str("   🔒 Read-only");
+    }
+    let (connection, connected) = connection_status(model);
+    if let Some(connection) = connection {
+        device_text.push_str("   ");
+        device_text.push_str(&connection);
+    }
+    let unresponsive = model
+        .target_device()
+        .is_some_and(|serial| model.watchdog.is_unresponsive(serial));
+    if unresponsive {
+        device_text.push_str("   ⚠ Not responding");
+    }
+
+    let header = Paragraph::new(device_text)
         .block(header_block)
-        .style(Style::default().fg(Color::LightGreen))
+        .style(Style::default().fg(if unresponsive || !connected {
+            Color::Yellow
+        } else {
+            Color::LightGreen
+        }))
         .alignment(Alignment::Center);
 
     if header_area.width > 0 {
@@ -206,9 +536,9 @@ fn render_menu(model: &mut Model, area: Rect, buf: &mut Buffer) {
         .style(Style::default().fg(Color::Yellow));
 
     let footer_text = if model.menu.is_in_child_mode() {
-        "↑/↓ or j/k: Navigate | Enter: Execute | ← Left/Backspace: Back | q/Esc: Quit"
+        "↑/↓ or j/k: Navigate | Enter: Execute | ← Left/Backspace: Back | d: Device | q/Esc: Quit"
     } else {
-        "↑/↓ or j/k: Navigate | Enter/→: Options | q/Esc: Quit"
+        "↑/↓ or j/k: Navigate | Enter/→: Options | d: Device | q/Esc: Quit"
     };
 
     // Apply fade-in effect if entering child mode
@@ -265,16 +595,1025 @@ fn render_menu(model: &mut Model, area: Rect, buf: &mut Buffer) {
     if footer_area.width > 0 {
         footer.render(footer_area, buf);
     }
+
+    if let Some(prompt) = &model.command_prompt {
+        render_command_prompt(prompt, area, buf);
+    }
+}
+
+/// Render the placeholder prompt of a custom command
+fn render_command_prompt(prompt: &CommandPrompt, area: Rect, buf: &mut Buffer) {
+    let popup_area = centered_rect(60, 30, area);
+    Clear.render(popup_area, buf);
+
+    let block = Block::bordered()
+        .title(format!(
+            "✏️ {} ({}/{})  Enter:Next Esc:Cancel",
+            prompt.current().unwrap_or_default(),
+            prompt.values.len() + 1,
+            prompt.names.len()
+        ))
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Green));
+
+    let lines = vec![
+        Line::from(Span::styled(
+            prompt.template.clone(),
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("{}: ", prompt.current().unwrap_or_default()),
+                Style::default().fg(Color::LightGreen),
+            ),
+            Span::styled(
+                format!("{}▏", prompt.input),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+    ];
+
+    Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .render(popup_area, buf);
+}
+
+/// Render the device picker popup
+fn render_device_picker(model: &Model, area: Rect, buf: &mut Buffer) {
+    let popup_area = centered_rect(70, 60, area);
+    Clear.render(popup_area, buf);
+
+    let picker_block = Block::bordered()
+        .title("📱 Select Device  ↑/↓:Navigate Enter:Select r:Refresh Esc:Back")
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Green));
+
+    let inner = picker_block.inner(popup_area);
+    picker_block.render(popup_area, buf);
+
+    let recent = &model.wireless.recent.addresses;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),                                 // Devices
+            Constraint::Length(recent.len().min(4) as u16 + 1), // Recent addresses
+            Constraint::Length(3),                              // Prompt / status
+        ])
+        .split(inner);
+
+    if model.devices.is_empty() {
+        Paragraph::new(
+            "No devices found.\n\nMake sure:\n- Device is connected via USB or paired over Wi-Fi\n- USB debugging is enabled\n- Device is authorized\n\nPress r to refresh or c to connect over the network",
+        )
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .render(chunks[0], buf);
+    } else {
+        let active = model.active_device();
+        let items: Vec<ListItem> = model
+            .devices
+            .iter()
+            .enumerate()
+            .map(|(idx, device)| {
+                let marker = if Some(device.serial.as_str()) == active {
+                    "● "
+                } else {
+                    "  "
+                };
+                let state_color = if device.is_online() {
+                    Color::LightGreen
+                } else {
+                    Color::Yellow
+                };
+                let transport = device.transport();
+                let transport_color = match transport {
+                    Transport::Usb => Color::LightBlue,
+                    Transport::Tcp => Color::Magenta,
+                    Transport::Emulator => Color::Gray,
+                };
+                let name_style = if idx == model.device_selected {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Rgb(61, 220, 132))
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+
+                ListItem::new(Line::from(vec![
+                    Span::styled(marker, Style::default().fg(Color::LightGreen)),
+                    Span::styled(
+                        format!("{:<4}", transport.label()),
+                        Style::default().fg(transport_color),
+                    ),
+                    Span::styled(device.display_name(), name_style),
+                    Span::raw("  "),
+                    Span::styled(
+                        format!("[{}]", device.state),
+                        Style::default().fg(state_color),
+                    ),
+                ]))
+            })
+            .collect();
+
+        List::new(items).render(chunks[0], buf);
+    }
+
+    let mut recent_lines = vec![Line::from(Span::styled(
+        "Recent addresses",
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    recent_lines.extend(recent.iter().take(4).map(|address| {
+        Line::from(Span::styled(
+            format!("  {}", address),
+            Style::default().fg(Color::Gray),
+        ))
+    }));
+    if recent.is_empty() {
+        recent_lines.clear();
+    }
+    Paragraph::new(recent_lines).render(chunks[1], buf);
+
+    let (footer_title, footer_text, footer_color) = match &model.wireless.prompt {
+        Some((prompt, text)) => (
+            format!("{} (Enter: Run | Esc: Cancel)", prompt.title()),
+            format!("{}█", text),
+            Color::Cyan,
+        ),
+        None => (
+            "Wireless".to_string(),
+            model.wireless.status.clone().unwrap_or_else(|| {
+                "c: Connect | p: Pair | t: TCP/IP mode | x: Disconnect".to_string()
+            }),
+            Color::Yellow,
+        ),
+    };
+    Paragraph::new(footer_text)
+        .block(
+            Block::bordered()
+                .title(footer_title)
+                .title_alignment(Alignment::Center)
+                .border_type(BorderType::Rounded)
+                .style(Style::default().fg(footer_color)),
+        )
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .render(chunks[2], buf);
+}
+
+/// Render the live logcat viewer
+fn render_logcat(model: &Model, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Filters
+            Constraint::Min(0),    // Log lines
+            Constraint::Length(3), // Help / filter input
+        ])
+        .split(area);
+
+    let logcat = &model.logcat;
+
+    let state_label = if !logcat.streaming {
+        "⏹ Stopped"
+    } else if logcat.is_following() {
+        "● Live"
+    } else {
+        "⏸ Scrolled"
+    };
+    let header_block = Block::bordered()
+        .title(format!(
+            "📜 Logcat {} [{}/{}]",
+            state_label,
+            logcat.visible_count(),
+            logcat.record_count()
+        ))
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Green));
+    Paragraph::new(logcat.filter().summary())
+        .block(header_block)
+        .style(Style::default().fg(Color::LightGreen))
+        .alignment(Alignment::Center)
+        .render(chunks[0], buf);
+
+    let body_block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Green));
+    let body = body_block.inner(chunks[1]);
+    body_block.render(chunks[1], buf);
+
+    let height = body.height as usize;
+
+    let lines: Vec<Line> = logcat
+        .window(height)
+        .map(|record| {
+            let color = record.level.color();
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", record.time),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:>5} {:>5} ", record.pid, record.tid),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(
+                    format!("{} ", record.level.as_char()),
+                    Style::default().fg(Color::Black).bg(color),
+                ),
+                Span::styled(
+                    format!(" {}: ", record.tag),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(record.message.clone(), Style::default().fg(color)),
+            ])
+        })
+        .collect();
+    Paragraph::new(lines).render(body, buf);
+
+    let (footer_title, footer_text, footer_color) = match &logcat.input {
+        Some((field, text)) => (
+            format!("✏️ {} filter (Enter: Apply | Esc: Cancel | empty clears)", field.label()),
+            format!("{}█", text),
+            Color::Cyan,
+        ),
+        None => (
+            "Help".to_string(),
+            logcat.status.clone().unwrap_or_else(|| {
+                "↑/↓ j/k: Scroll | End/f: Follow | l: Level | t: Tag | p: Package/PID | /: Regex | c: Clear | q/Esc: Back".to_string()
+            }),
+            Color::Yellow,
+        ),
+    };
+    let footer_block = Block::bordered()
+        .title(footer_title)
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(footer_color));
+    Paragraph::new(footer_text)
+        .block(footer_block)
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .render(chunks[2], buf);
+}
+
+/// Render the two-pane file browser
+fn render_file_browser(model: &Model, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Panes
+            Constraint::Length(3), // Transfer / status / prompt
+        ])
+        .split(area);
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[0]);
+
+    let files = &model.files;
+    render_file_pane(
+        &format!("💻 {}", files.local_dir.display()),
+        &files.local,
+        files.focus == Pane::Local,
+        panes[0],
+        buf,
+    );
+    render_file_pane(
+        &format!("📱 {}", files.remote_dir),
+        &files.remote,
+        files.focus == Pane::Remote,
+        panes[1],
+        buf,
+    );
+
+    let (footer_title, footer_text, footer_color) = match (&files.input, &files.transfer) {
+        (Some((FileInput::Rename, text)), _) => (
+            "✏️ Rename (Enter: Apply | Esc: Cancel)".to_string(),
+            format!("{}█", text),
+            Color::Cyan,
+        ),
+        (Some((FileInput::ConfirmDelete, _)), _) => (
+            "⚠️ Delete".to_string(),
+            format!(
+                "Delete {}? (y: Yes | any other key: No)",
+files.selected_name().unwrap_or_default()
+            ),
+            Color::Red,
+        ),
+        (None, Some(transfer)) => {
+            let width = (chunks[1].width as usize).saturating_sub(40).clamp(10, 50);
+            let size = match transfer.total {
+                Some(total) => format!(
+                    "{} / {}",
+                    format_size(transfer.transferred),
+                    format_size(total)
+                ),
+                None => format_size(transfer.transferred),
+            };
+            (
+                transfer.label.clone(),
+                format!(
+                    "{} {}",
+                    get_transfer_progress_bar(transfer.fraction(), model.loading_counter, width),
+                    size
+                ),
+                Color::Cyan,
+            )
+        }
+        (None, None) => (
+            "Help".to_string(),
+            files.status.clone().unwrap_or_else(|| {
+                "↑/↓ j/k: Navigate | Enter: Open | Backspace: Up | Tab: Switch pane | c: Copy | r: Rename | D: Delete | R: Refresh | q/Esc: Back".to_string()
+            }),
+            Color::Yellow,
+        ),
+    };
+    let footer_block = Block::bordered()
+        .title(footer_title)
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(footer_color));
+    Paragraph::new(footer_text)
+        .block(footer_block)
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .render(chunks[1], buf);
+}
+
+/// Render one side of the file browser, keeping the selection in view
+fn render_file_pane(title: &str, pane: &PaneState, focused: bool, area: Rect, buf: &mut Buffer) {
+    let border_color = if focused {
+        Color::Green
+    } else {
+        Color::DarkGray
+    };
+    let block = Block::bordered()
+        .title(title.to_string())
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(border_color));
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if pane.entries.is_empty() {
+        Paragraph::new("(empty)")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center)
+            .render(inner, buf);
+        return;
+    }
+
+    let height = inner.height as usize;
+    let start = (pane.selected + 1).saturating_sub(height);
+    let items: Vec<ListItem> = pane
+        .entries
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(height)
+        .map(|(idx, entry)| {
+            let (icon, size) = if entry.is_dir {
+                ("📁 ", String::new())
+            } else if entry.is_symlink {
+                ("🔗 ", String::new())
+            } else {
+                ("📄 ", format_size(entry.size))
+            };
+            let style = if focused && idx == pane.selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Rgb(61, 220, 132))
+                    .add_modifier(Modifier::BOLD)
+            } else if idx == pane.selected {
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD)
+            } else if entry.is_dir {
+                Style::default().fg(Color::LightBlue)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{}{}", icon, entry.name), style),
+                Span::raw("  "),
+                Span::styled(size, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    List::new(items).render(inner, buf);
+}
+
+/// Render the package manager list with its action panel
+fn render_packages(model: &Model, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Search
+            Constraint::Min(0),    // Packages
+            Constraint::Length(3), // Help / status
+        ])
+        .split(area);
+
+    let packages = &model.packages;
+    let filtered = packages.filtered();
+
+    let search_style = if packages.searching {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::Green)
+    };
+    let cursor = if packages.searching { "█" } else { "" };
+    Paragraph::new(format!("🔍 {}{}", packages.query, cursor))
+        .block(
+            Block::bordered()
+                .title(format!(
+                    "📦 Packages [{}/{}]",
+                    filtered.len(),
+                    packages.entries.len()
+                ))
+                .title_alignment(Alignment::Center)
+                .border_type(BorderType::Rounded)
+                .style(search_style),
+        )
+        .style(Style::default().fg(Color::White))
+        .render(chunks[0], buf);
+
+    let list_block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Green));
+    let inner = list_block.inner(chunks[1]);
+    list_block.render(chunks[1], buf);
+
+    let height = inner.height as usize;
+    let start = (packages.selected + 1).saturating_sub(height);
+    let items: Vec<ListItem> = filtered
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(height)
+        .map(|(idx, package)| {
+            let name_style = if idx == packages.selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Rgb(61, 220, 132))
+                    .add_modifier(Modifier::BOLD)
+            } else if package.enabled {
+                Style::default().fg(Color::White)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            let (origin, origin_color) = if package.system {
+                ("system", Color::Yellow)
+            } else {
+                ("user  ", Color::LightBlue)
+            };
+            let mut spans = vec![
+                Span::styled(format!("{} ", origin), Style::default().fg(origin_color)),
+                Span::styled(package.name.clone(), name_style),
+            ];
+            if !package.enabled {
+                spans.push(Span::styled(" (disabled)", Style::default().fg(Color::Red)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    List::new(items).render(inner, buf);
+
+    if let (Some(action), Some(package)) = (packages.action, packages.selected_package()) {
+        let popup_area = centered_rect(40, 50, area);
+        Clear.render(popup_area, buf);
+        let popup_block = Block::bordered()
+            .title(format!("⚙️ {}", package.name))
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded)
+            .style(Style::default().fg(Color::Green));
+        let popup_inner = popup_block.inner(popup_area);
+        popup_block.render(popup_area, buf);
+
+        let mut lines = Vec::new();
+        if let Some(path) = &package.apk_path {
+            lines.push(ListItem::new(Span::styled(
+                path.clone(),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.extend(PackageAction::ALL.iter().enumerate().map(|(idx, item)| {
+            let style = if idx == action {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Rgb(61, 220, 132))
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(Span::styled(item.label(package), style))
+        }));
+        List::new(lines).render(popup_inner, buf);
+    }
+
+    let help = if packages.searching {
+        "Type to search | ↑/↓: Navigate | Enter/Esc: Done".to_string()
+    } else if packages.action.is_some() {
+        "↑/↓ j/k: Choose action | Enter: Run | Esc: Close".to_string()
+    } else {
+        packages.status.clone().unwrap_or_else(|| {
+            "↑/↓ j/k: Navigate | /: Search | Enter: Actions | r: Refresh | q/Esc: Back".to_string()
+        })
+    };
+    Paragraph::new(help)
+        .block(
+            Block::bordered()
+                .title("Help")
+                .title_alignment(Alignment::Center)
+                .border_type(BorderType::Rounded)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .render(chunks[2], buf);
+}
+
+/// Render the process manager
+fn render_processes(model: &Model, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Filter
+            Constraint::Min(0),    // Processes
+            Constraint::Length(3), // Help / status
+        ])
+        .split(area);
+
+    let view = &model.processes;
+    let visible = view.visible();
+
+    let filter_style = if view.searching {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::Green)
+    };
+    let cursor = if view.searching { "█" } else { "" };
+    Paragraph::new(format!("🔍 {}{}", view.query, cursor))
+        .block(
+            Block::bordered()
+                .title(format!(
+                    "🎯 Processes [{}/{}] by {} {}",
+                    visible.len(),
+                    view.entries.len(),
+                    view.sort.label(),
+                    if view.descending { "↓" } else { "↑" }
+                ))
+                .title_alignment(Alignment::Center)
+                .border_type(BorderType::Rounded)
+                .style(filter_style),
+        )
+        .style(Style::default().fg(Color::White))
+        .render(chunks[0], buf);
+
+    let list_block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Green));
+    let inner = list_block.inner(chunks[1]);
+    list_block.render(chunks[1], buf);
+
+    // One row goes to the header
+    let height = inner.height.saturating_sub(1) as usize;
+    let selected = view.selected_index();
+    let start = (selected + 1).saturating_sub(height);
+    let header_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let sorted_style = header_style.add_modifier(Modifier::UNDERLINED);
+    let column = |label: &'static str, sort: ProcessSort| {
+        Span::styled(
+            label,
+            if view.sort == sort {
+                sorted_style
+            } else {
+                header_style
+            },
+        )
+    };
+    let header = Row::new(vec![
+        Line::from(column("PID", ProcessSort::Pid)),
+        Line::from(column("USER", ProcessSort::User)),
+        Line::from(column("CPU%", ProcessSort::Cpu)),
+        Line::from(column("RSS", ProcessSort::Memory)),
+        Line::from(Span::styled("S", header_style)),
+        Line::from(column("NAME", ProcessSort::Name)),
+    ]);
+
+    let rows = visible
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(height)
+        .map(|(idx, process)| {
+            let style = if idx == selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Rgb(61, 220, 132))
+                    .add_modifier(Modifier::BOLD)
+            } else if process.state == "R" {
+                Style::default().fg(Color::LightGreen)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            Row::new(vec![
+                process.pid.to_string(),
+                process.user.clone(),
+                process
+                    .cpu
+                    .map(|cpu| format!("{:.1}", cpu))
+                    .unwrap_or_else(|| "-".to_string()),
+                format_size(process.rss_kb * 1024),
+                process.state.clone(),
+                process.name.clone(),
+            ])
+            .style(style)
+        });
+    Table::new(
+        rows,
+        [
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Length(2),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .render(inner, buf);
+
+    if let (Some(action), Some(process)) = (view.action, view.selected_process()) {
+        let popup_area = centered_rect(40, 40, area);
+        Clear.render(popup_area, buf);
+        let popup_block = Block::bordered()
+            .title(format!("⚙️ {} ({})", process.name, process.pid))
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded)
+            .style(Style::default().fg(Color::Green));
+        let popup_inner = popup_block.inner(popup_area);
+        popup_block.render(popup_area, buf);
+
+        let items: Vec<ListItem> = ProcessAction::available(process)
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let style = if idx == action {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Rgb(61, 220, 132))
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                ListItem::new(Span::styled(item.label(process), style))
+            })
+            .collect();
+        List::new(items).render(popup_inner, buf);
+    }
+
+    let help = if view.searching {
+        "Type to filter by name or user | Enter/Esc: Done".to_string()
+    } else if view.action.is_some() {
+        "↑/↓ j/k: Choose action | Enter: Send | Esc: Close".to_string()
+    } else {
+        view.status.clone().unwrap_or_else(|| {
+            "↑/↓ j/k: Navigate | /: Filter | s: Sort | o: Reverse | Enter: Actions | q/Esc: Back"
+                .to_string()
+        })
+    };
+    Paragraph::new(help)
+        .block(
+            Block::bordered()
+                .title("Help")
+                .title_alignment(Alignment::Center)
+                .border_type(BorderType::Rounded)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .render(chunks[2], buf);
+}
+
+/// Render the screenshot preview
+fn render_screenshot(model: &mut Model, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Preview
+            Constraint::Length(3), // Path / help
+        ])
+        .split(area);
+
+    let Some(screenshot) = &model.screenshot else {
+        model.screenshot_area = None;
+        return;
+    };
+    let (width, height) = screenshot.image.dimensions();
+
+    let preview_block = Block::bordered()
+        .title(format!(
+            "📷 Screenshot {}x{} ({})",
+            width,
+            height,
+            model.graphics.label()
+        ))
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Green));
+    let inner = preview_block.inner(chunks[0]);
+    preview_block.render(chunks[0], buf);
+
+    model.screenshot_area = match model.graphics {
+        GraphicsProtocol::HalfBlocks => {
+            preview::render_half_blocks(&screenshot.image, inner, buf);
+            None
+        }
+        // The image itself is written by the app after the frame is drawn
+        _ => Some(preview::fit_area(
+            (width, height),
+            preview::cell_size(),
+            inner,
+        )),
+    };
+
+    Paragraph::new(format!("Saved to {}", screenshot.path.display()))
+        .block(
+            Block::bordered()
+                .title("s: New Screenshot | q/Esc: Back")
+                .title_alignment(Alignment::Center)
+                .border_type(BorderType::Rounded)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .render(chunks[1], buf);
+}
+
+/// Render the terminal mirror
+fn render_mirror(model: &mut Model, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Screen
+            Constraint::Length(3), // Stats / help
+        ])
+        .split(area);
+
+    let Some(mirror) = &model.mirror else {
+        model.mirror_area = None;
+        return;
+    };
+
+    let title = match &mirror.frame {
+        Some(frame) => format!(
+            "📱 Mirror {}x{} ({})",
+            frame.width(),
+            frame.height(),
+            model.graphics.label()
+        ),
+        None => "📱 Mirror".to_string(),
+    };
+    let screen_block = Block::bordered()
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Green));
+    let inner = screen_block.inner(chunks[0]);
+    screen_block.render(chunks[0], buf);
+
+    model.mirror_area = match (&mirror.frame, model.graphics) {
+        (None, _) => {
+            Paragraph::new(format!(
+                "{} Waiting for the first frame…",
+                get_loading_spinner(model.loading_counter)
+            ))
+            .style(Style::default().fg(Color::Gray))
+            .alignment(Alignment::Center)
+            .render(inner, buf);
+            None
+        }
+        (Some(frame), GraphicsProtocol::HalfBlocks) => {
+            preview::render_half_blocks(frame, inner, buf);
+            None
+        }
+        // The frame itself is written by the app after the terminal is drawn
+        (Some(frame), _) => Some(preview::fit_area(
+            frame.dimensions(),
+            preview::cell_size(),
+            inner,
+        )),
+    };
+
+    let decoded = mirror.decoded();
+    Paragraph::new(format!(
+        "{:.1} fps | {} shown, {} dropped",
+        mirror.fps(std::time::Instant::now()),
+        mirror.shown,
+        decoded.saturating_sub(mirror.shown)
+    ))
+    .block(
+        Block::bordered()
+            .title("q/Esc: Back")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded)
+            .style(Style::default().fg(Color::Yellow)),
+    )
+    .style(Style::default().fg(Color::White))
+    .alignment(Alignment::Center)
+    .render(chunks[1], buf);
+}
+
+/// Render the live dashboard: one panel per metric with its recent history
+fn render_dashboard(model: &Model, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Panels
+            Constraint::Length(3), // Help / status
+        ])
+        .split(area);
+
+    let dashboard = &model.dashboard;
+    let sample = dashboard.latest.as_ref();
+
+    Paragraph::new(format!(
+        "{} | every {:.2}s",
+        model.active_device().unwrap_or("default device"),
+        dashboard.interval.as_secs_f32()
+    ))
+    .block(
+        Block::bordered()
+            .title("📈 Live Dashboard")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Rounded)
+            .style(Style::default().fg(Color::Green)),
+    )
+    .style(Style::default().fg(Color::White))
+    .alignment(Alignment::Center)
+    .render(chunks[0], buf);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[0]);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+
+    // CPU
+    let mut title = match dashboard.cpu.latest() {
+        Some(cpu) => format!("⚙️ CPU {}%", cpu),
+        None => "⚙️ CPU".to_string(),
+    };
+    if let Some([one, five, fifteen]) = sample.and_then(|s| s.load) {
+        title.push_str(&format!(" | load {:.2} {:.2} {:.2}", one, five, fifteen));
+    }
+    let inner = render_dashboard_block(title, Color::Cyan, top[0], buf);
+    render_history(&dashboard.cpu, Some(100), Color::Cyan, inner, buf);
+
+    // Memory
+    let memory = sample.and_then(|s| s.memory);
+    let title = match memory {
+        Some(memory) => format!(
+            "🧠 Memory {} / {}",
+            format_size(memory.used_kb() * 1024),
+            format_size(memory.total_kb * 1024)
+        ),
+        None => "🧠 Memory".to_string(),
+    };
+    let inner = render_dashboard_block(title, Color::Magenta, top[1], buf);
+    let parts = split_gauge(inner);
+    if let Some(memory) = memory {
+        Gauge::default()
+            .gauge_style(Style::default().fg(Color::Magenta).bg(Color::Black))
+            .percent(memory.used_percent().min(100) as u16)
+            .render(parts[0], buf);
+    }
+    render_history(&dashboard.memory, Some(100), Color::Magenta, parts[1], buf);
+
+    // Battery
+    let battery = sample.and_then(|s| s.battery.as_ref());
+    let title = match battery {
+        Some(battery) => {
+            let mut title = format!("🔋 Battery {}", battery.status.label());
+            if let Some(temperature) = battery.temperature_c {
+                title.push_str(&format!(" | {:.1} °C", temperature));
+            }
+            title
+        }
+        None => "🔋 Battery".to_string(),
+    };
+    let inner = render_dashboard_block(title, Color::Yellow, bottom[0], buf);
+    let parts = split_gauge(inner);
+    if let Some(battery) = battery {
+        let percent = battery.percent();
+        Gauge::default()
+            .gauge_style(Style::default().fg(Color::Yellow).bg(Color::Black))
+            .percent(percent)
+            .label(format!("{}%", percent))
+            .render(parts[0], buf);
+    }
+    render_history(&dashboard.temperature, None, Color::Yellow, parts[1], buf);
+
+    // Network
+    let title = match (dashboard.rx.latest(), dashboard.tx.latest()) {
+        (Some(rx), Some(tx)) => {
+            format!("🌐 Network ↓ {}/s ↑ {}/s", format_size(rx), format_size(tx))
+        }
+        _ => "🌐 Network".to_string(),
+    };
+    let inner = render_dashboard_block(title, Color::LightBlue, bottom[1], buf);
+    let halves = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+    render_history(&dashboard.rx, None, Color::LightBlue, halves[0], buf);
+    render_history(&dashboard.tx, None, Color::LightGreen, halves[1], buf);
+
+    let (help, color) = match &dashboard.error {
+        Some(error) => (error.clone(), Color::Red),
+        None => (
+            "+/-: Faster / Slower | q/Esc: Back".to_string(),
+            Color::Yellow,
+        ),
+    };
+    Paragraph::new(help)
+        .block(
+            Block::bordered()
+                .title("Help")
+                .title_alignment(Alignment::Center)
+                .border_type(BorderType::Rounded)
+                .style(Style::default().fg(color)),
+        )
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .render(chunks[2], buf);
+}
+
+/// Bordered dashboard panel, returning the area inside it
+fn render_dashboard_block(title: String, color: Color, area: Rect, buf: &mut Buffer) -> Rect {
+    let block = Block::bordered()
+        .title(title)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(color));
+    let inner = block.inner(area);
+    block.render(area, buf);
+    inner
+}
+
+/// One row for a gauge, the rest for a sparkline
+fn split_gauge(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area)
+}
+
+/// Sparkline of the newest values that fit, scaled to `max` or the largest value shown
+fn render_history(history: &History, max: Option<u64>, color: Color, area: Rect, buf: &mut Buffer) {
+    if history.is_empty() {
+        Paragraph::new("Waiting for samples…")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center)
+            .render(area, buf);
+        return;
+    }
+    let data = history.tail(area.width as usize);
+    let max = max.unwrap_or_else(|| data.iter().copied().max().unwrap_or(0).max(1));
+    Sparkline::default()
+        .data(&data)
+        .max(max)
+        .style(Style::default().fg(color))
+        .render(area, buf);
 }
 
 /// Render command result with scrolling support
 fn render_result(model: &mut Model, area: Rect, buf: &mut Buffer) {
-    let (title, color) = if model.command_result.is_some() {
-        ("✅ Command Result", Color::Green)
-    } else if model.command_error.is_some() {
-        ("❌ Command Error", Color::Red)
-    } else {
-        ("📋 No Output", Color::Yellow)
+    // A shell command that ran but exited non-zero is a failure too
+    let (title, color) = match (&model.command_result, model.exit_code) {
+        (Some(_), Some(code)) if code != 0 => {
+            (format!("❌ Exited with status {}", code), Color::Red)
+        }
+        (Some(_), _) => ("✅ Command Result".to_string(), Color::Green),
+        (None, _) if model.command_error.is_some() => ("❌ Command Error".to_string(), Color::Red),
+        (None, _) => ("📋 No Output".to_string(), Color::Yellow),
     };
 
     // Get slide animation progress for result popup
@@ -302,6 +1641,22 @@ fn render_result(model: &mut Model, area: Rect, buf: &mut Buffer) {
         return;
     }
 
+    // Parsed output gets a panel above the raw text when there is room for both
+    let popup_area = match &model.result_panel {
+        Some(output) if popup_area.height >= result_panel_height(output) + 5 => {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(result_panel_height(output)),
+                    Constraint::Min(0),
+                ])
+                .split(popup_area);
+            render_result_panel(output, rows[0], buf);
+            rows[1]
+        }
+        _ => popup_area,
+    };
+
     // Split area for content and scroll bar
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -393,6 +1748,195 @@ fn render_result(model: &mut Model, area: Rect, buf: &mut Buffer) {
     }
 }
 
+/// Rows taken by the panel for parsed output, including borders
+fn result_panel_height(output: &AdbOutput) -> u16 {
+    match output {
+        AdbOutput::Text(_) | AdbOutput::Shell { .. } => 0,
+        AdbOutput::Battery { .. } => 4,
+        AdbOutput::Memory { info, .. } => 5 + info.processes.len().min(5) as u16,
+        AdbOutput::Connectivity { state, .. } => 3 + state.networks.len().clamp(1, 4) as u16,
+    }
+}
+
+/// Render gauges and tables for parsed command output
+fn render_result_panel(output: &AdbOutput, area: Rect, buf: &mut Buffer) {
+    let title = match output {
+        AdbOutput::Text(_) | AdbOutput::Shell { .. } => return,
+        AdbOutput::Battery { .. } => "🔋 Battery",
+        AdbOutput::Memory { .. } => "🧠 Memory",
+        AdbOutput::Connectivity { .. } => "🌐 Networks",
+    };
+    let block = Block::bordered()
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let header_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    match output {
+        AdbOutput::Text(_) | AdbOutput::Shell { .. } => {}
+        AdbOutput::Battery { status, .. } => {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(inner);
+
+            let percent = status.percent();
+            let color = match percent {
+                0..=15 => Color::Red,
+                16..=40 => Color::Yellow,
+                _ => Color::Green,
+            };
+            Gauge::default()
+                .gauge_style(Style::default().fg(color).bg(Color::Black))
+                .percent(percent)
+                .label(format!("{}%", percent))
+                .render(rows[0], buf);
+
+            let mut details = vec![status.status.label().to_string()];
+            if !status.plugged.is_empty() {
+                details[0].push_str(&format!(" via {}", status.plugged.join(", ")));
+            }
+            details.push(format!("Health: {}", status.health.label()));
+            if let Some(temperature) = status.temperature_c {
+                details.push(format!("{:.1} °C", temperature));
+            }
+            if let Some(voltage) = status.voltage_mv {
+                details.push(format!("{:.2} V", voltage as f32 / 1000.0));
+            }
+            if let Some(technology) = &status.technology {
+                details.push(technology.clone());
+            }
+            Paragraph::new(details.join(" | "))
+                .style(Style::default().fg(Color::White))
+                .alignment(Alignment::Center)
+                .render(rows[1], buf);
+        }
+        AdbOutput::Memory { info, .. } => {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Min(0),
+                ])
+                .split(inner);
+
+            let ratio = info.used_ratio();
+            let color = if ratio > 0.9 {
+                Color::Red
+            } else if ratio > 0.75 {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            Gauge::default()
+                .gauge_style(Style::default().fg(color).bg(Color::Black))
+                .ratio(ratio)
+                .label(format!(
+                    "{} / {} used",
+                    format_size(info.used_kb * 1024),
+                    format_size(info.total_kb * 1024)
+                ))
+                .render(rows[0], buf);
+
+            let mut summary = format!("Free {}", format_size(info.free_kb * 1024));
+            if let Some(lost) = info.lost_kb {
+                summary.push_str(&format!(" | Lost {}", format_size(lost * 1024)));
+            }
+            Paragraph::new(summary)
+                .style(Style::default().fg(Color::White))
+                .alignment(Alignment::Center)
+                .render(rows[1], buf);
+
+            let processes = info.processes.iter().take(5).map(|process| {
+                Row::new(vec![
+                    process.name.clone(),
+                    process.pid.map(|pid| pid.to_string()).unwrap_or_default(),
+                    format_size(process.kb * 1024),
+                ])
+            });
+            Table::new(
+                processes,
+                [
+                    Constraint::Min(20),
+                    Constraint::Length(7),
+                    Constraint::Length(10),
+                ],
+            )
+            .header(Row::new(vec!["Process", "PID", "PSS"]).style(header_style))
+            .style(Style::default().fg(Color::White))
+            .render(rows[2], buf);
+        }
+        AdbOutput::Connectivity { state, .. } => {
+            if state.networks.is_empty() {
+                Paragraph::new("No active networks")
+                    .style(Style::default().fg(Color::Red))
+                    .alignment(Alignment::Center)
+                    .render(inner, buf);
+                return;
+            }
+
+            let networks = state.networks.iter().map(|network| {
+                let default = Some(network.id) == state.default_network;
+                let mut name = network.transports.join("+");
+                if let Some(ssid) = &network.ssid {
+                    name.push_str(&format!(" \"{}\"", ssid));
+                }
+                let internet = match (network.validated, network.metered) {
+                    (true, true) => "✔ metered",
+                    (true, false) => "✔",
+                    (false, _) => "✘",
+                };
+                let style = if default {
+                    Style::default()
+                        .fg(Color::Rgb(61, 220, 132))
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                Row::new(vec![
+                    if default { "★" } else { "" }.to_string(),
+                    name,
+                    network.state.clone().unwrap_or_default(),
+                    network.interface.clone().unwrap_or_default(),
+                    network.addresses.join(", "),
+                    internet.to_string(),
+                ])
+                .style(style)
+            });
+            Table::new(
+                networks,
+                [
+                    Constraint::Length(1),
+                    Constraint::Length(18),
+                    Constraint::Length(10),
+                    Constraint::Length(12),
+                    Constraint::Min(16),
+                    Constraint::Length(9),
+                ],
+            )
+            .header(
+                Row::new(vec![
+                    "",
+                    "Network",
+                    "State",
+                    "Interface",
+                    "Addresses",
+                    "Internet",
+                ])
+                .style(header_style),
+            )
+            .render(inner, buf);
+        }
+    }
+}
+
 /// Render a scrollbar indicator
 fn render_scrollbar(
     area: Rect,
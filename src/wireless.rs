@@ -0,0 +1,245 @@
+//! Wireless debugging
+//!
+//! Address parsing for `adb connect` / `adb pair`, and the list of recently used
+//! addresses, persisted under the XDG state directory.
+
+use crate::adb::address::split_host_port;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Port `adb tcpip` and `adb connect` use when none is given
+pub const DEFAULT_TCP_PORT: u16 = 5555;
+
+/// How many recent addresses are kept
+const MAX_RECENT: usize = 8;
+
+/// Normalise user input into `host:port`, adding `default_port` when missing
+///
+/// IPv6 addresses are bracketed: `fe80::1` becomes `[fe80::1]:5555`.
+pub fn normalize_address(input: &str, default_port: u16) -> Result<String, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Enter an address like 192.168.1.20:5555".to_string());
+    }
+    if input.contains(char::is_whitespace) {
+        return Err(format!("Invalid address: {}", input));
+    }
+
+    if let Some((host, port)) = split_host_port(input) {
+        return Ok(if host.contains(':') {
+            format!("[{}]:{}", host, port)
+        } else {
+            format!("{}:{}", host, port)
+        });
+    }
+
+    let host = input.trim_start_matches('[').trim_end_matches(']');
+    if host.matches(':').count() >= 2 {
+        Ok(format!("[{}]:{}", host, default_port))
+    } else if input.contains(':') {
+        Err(format!("Invalid port in {}", input))
+    } else {
+        Ok(format!("{}:{}", host, default_port))
+    }
+}
+
+/// Parse `host:port code` as typed into the pairing prompt
+pub fn parse_pair_input(input: &str) -> Result<(String, String), String> {
+    let mut parts = input.split_whitespace();
+    let (Some(address), Some(code), None) = (parts.next(), parts.next(), parts.next()) else {
+        return Err("Enter the pairing address and code, e.g. 192.168.1.20:37099 123456".into());
+    };
+    // The pairing port is random, so it must be given explicitly
+    if split_host_port(address).is_none() {
+        return Err(format!("Pairing needs host:port, got {}", address));
+    }
+    Ok((
+        normalize_address(address, DEFAULT_TCP_PORT)?,
+        code.to_string(),
+    ))
+}
+
+/// Recently connected addresses, most recent first
+#[derive(Debug, Clone, Default)]
+pub struct RecentAddresses {
+    pub addresses: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl RecentAddresses {
+    /// Load from `$XDG_STATE_HOME/droidtui/recent_addresses` (or `~/.local/state`)
+    pub fn load() -> Self {
+        let path = state_dir().map(|dir| dir.join("recent_addresses"));
+        let addresses = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| {
+                content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .take(MAX_RECENT)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { addresses, path }
+    }
+
+    /// Move `address` to the front and persist the list
+    pub fn remember(&mut self, address: &str) -> io::Result<()> {
+        self.addresses.retain(|a| a != address);
+        self.addresses.insert(0, address.to_string());
+        self.addresses.truncate(MAX_RECENT);
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.addresses.join("\n") + "\n")
+    }
+}
+
+/// Per-user state directory for droidtui
+fn state_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|home| !home.is_empty())
+                .map(|home| PathBuf::from(home).join(".local").join("state"))
+        })?;
+    Some(base.join("droidtui"))
+}
+
+/// Prompt shown in the device picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WirelessPrompt {
+    /// `adb connect host[:port]`
+    Connect,
+    /// `adb pair host:port code`
+    Pair,
+    /// `adb tcpip <port>` on the highlighted device
+    TcpIp,
+}
+
+impl WirelessPrompt {
+    pub fn title(&self) -> &'static str {
+        match self {
+            WirelessPrompt::Connect => "🔗 Connect to host[:port]",
+            WirelessPrompt::Pair => "🔐 Pair with host:port code",
+            WirelessPrompt::TcpIp => "📶 Restart adbd in TCP mode on port",
+        }
+    }
+}
+
+/// Wireless debugging state of the device picker
+#[derive(Debug, Clone, Default)]
+pub struct WirelessState {
+    pub recent: RecentAddresses,
+    pub prompt: Option<(WirelessPrompt, String)>,
+    /// Position while cycling through recent addresses in the connect prompt
+    pub recent_index: Option<usize>,
+    pub status: Option<String>,
+}
+
+impl WirelessState {
+    pub fn new() -> Self {
+        Self {
+            recent: RecentAddresses::load(),
+            ..Default::default()
+        }
+    }
+
+    /// Open a prompt with a sensible default value
+    pub fn open_prompt(&mut self, prompt: WirelessPrompt) {
+        let initial = match prompt {
+            WirelessPrompt::Connect => self.recent.addresses.first().cloned(),
+            WirelessPrompt::Pair => None,
+            WirelessPrompt::TcpIp => Some(DEFAULT_TCP_PORT.to_string()),
+        };
+        self.recent_index = initial
+            .as_ref()
+            .filter(|_| prompt == WirelessPrompt::Connect)
+            .map(|_| 0);
+        self.prompt = Some((prompt, initial.unwrap_or_default()));
+    }
+
+    /// Step through recent addresses in the connect prompt
+    pub fn cycle_recent(&mut self, delta: isize) {
+        let Some((WirelessPrompt::Connect, text)) = self.prompt.as_mut() else {
+            return;
+        };
+        let count = self.recent.addresses.len();
+        if count == 0 {
+            return;
+        }
+        let next = match self.recent_index {
+            Some(index) => (index as isize + delta).rem_euclid(count as isize) as usize,
+            None => 0,
+        };
+        self.recent_index = Some(next);
+        *text = self.recent.addresses[next].clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_address() {
+        assert_eq!(
+            normalize_address("192.168.1.20", 5555).unwrap(),
+            "192.168.1.20:5555"
+        );
+        assert_eq!(
+            normalize_address(" phone.local:4444 ", 5555).unwrap(),
+            "phone.local:4444"
+        );
+        assert_eq!(
+            normalize_address("fe80::1", 5555).unwrap(),
+            "[fe80::1]:5555"
+        );
+        assert_eq!(
+            normalize_address("[fe80::1]:37000", 5555).unwrap(),
+            "[fe80::1]:37000"
+        );
+        assert!(normalize_address("", 5555).is_err());
+        assert!(normalize_address("host:port", 5555).is_err());
+    }
+
+    #[test]
+    fn test_parse_pair_input() {
+        assert_eq!(
+            parse_pair_input("192.168.1.20:37099 123456").unwrap(),
+            ("192.168.1.20:37099".to_string(), "123456".to_string())
+        );
+        assert!(parse_pair_input("192.168.1.20 123456").is_err());
+        assert!(parse_pair_input("192.168.1.20:37099").is_err());
+    }
+
+    #[test]
+    fn test_recent_addresses() {
+        let mut state = WirelessState::default();
+        state.recent.remember("10.0.0.2:5555").unwrap();
+        state.recent.remember("10.0.0.3:5555").unwrap();
+        state.recent.remember("10.0.0.2:5555").unwrap();
+        assert_eq!(state.recent.addresses, ["10.0.0.2:5555", "10.0.0.3:5555"]);
+
+        state.open_prompt(WirelessPrompt::Connect);
+        assert_eq!(
+            state.prompt.as_ref().map(|(_, text)| text.as_str()),
+            Some("10.0.0.2:5555")
+        );
+        state.cycle_recent(1);
+        assert_eq!(
+            state.prompt.as_ref().map(|(_, text)| text.as_str()),
+            Some("10.0.0.3:5555")
+        );
+    }
+}
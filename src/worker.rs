@@ -0,0 +1,180 @@
+//! Background command execution
+//!
+//! Menu commands run on their own thread with a copy of the ADB manager so the UI
+//! keeps drawing while they wait on the device. Events are tagged with the job id;
+//! anything from a cancelled or superseded job is dropped by the event loop.
+//! Views that refresh periodically use [`spawn_poll`] the same way.
+
+use crate::adb::{AdbCommand, AdbError, AdbManager, AdbResult, CancelToken};
+use crate::event::Event;
+use crate::message::CommandResult;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Id for a new background job; events carry it so stale ones can be dropped
+pub fn next_job_id() -> u64 {
+    NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// How long a periodic read may take before it reports a timeout
+pub const POLL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Why a background job failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollError {
+    pub message: String,
+    /// The job ran past its timeout, a sign the device stopped answering
+    pub timed_out: bool,
+}
+
+impl From<AdbError> for PollError {
+    fn from(e: AdbError) -> Self {
+        Self {
+            timed_out: matches!(e, AdbError::Timeout(_)),
+            message: e.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for PollError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Progress of a background command
+#[derive(Debug, Clone)]
+pub enum CommandEvent {
+    Started(u64),
+    Finished { id: u64, result: CommandResult },
+}
+
+/// Outcome of a one-off action started with [`spawn_poll`]
+#[derive(Debug, Clone)]
+pub struct ActionEvent {
+    pub id: u64,
+    pub result: Result<String, PollError>,
+}
+
+/// Handle to a running command; dropping it cancels the command
+#[derive(Debug)]
+pub struct CommandJob {
+    pub id: u64,
+    cancel: CancelToken,
+    /// Whether the command ran past its timeout
+    timed_out: Arc<AtomicBool>,
+}
+
+impl CommandJob {
+    /// Close the command's connection to the server
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Whether the command failed with [`AdbError::Timeout`]
+    pub fn timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for CommandJob {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Handle to a periodic background read; dropping it closes its connection
+#[derive(Debug)]
+pub struct PollJob {
+    pub id: u64,
+    cancel: CancelToken,
+}
+
+impl PollJob {
+    /// Close the poll's connection; its result still reports back
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+}
+
+impl Drop for PollJob {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Run `poll` on a worker thread within `timeout`, sending its result wrapped by `event`
+pub fn spawn_poll<T: Send + 'static>(
+    manager: &AdbManager,
+    sender: UnboundedSender<Event>,
+    timeout: Option<Duration>,
+    poll: impl FnOnce(&mut AdbManager) -> AdbResult<T> + Send + 'static,
+    event: fn(u64, Result<T, PollError>) -> Event,
+) -> PollJob {
+    let id = next_job_id();
+    let cancel = CancelToken::default();
+    let mut manager = manager.for_worker(cancel.clone());
+
+    std::thread::spawn(move || {
+        let result = manager.with_timeout(timeout, poll).map_err(PollError::from);
+        let _ = sender.send(event(id, result));
+    });
+
+    PollJob { id, cancel }
+}
+
+/// Format an ADB error with troubleshooting hints for common failures
+pub fn describe_error(e: impl std::fmt::Display) -> String {
+    let error_msg = format!("{}", e);
+
+    // Add helpful context for common errors
+    if error_msg.contains("Connection") || error_msg.contains("connection") {
+        format!(
+            "{}\n\nTroubleshooting:\n• Make sure ADB server is running (adb start-server)\n• Check that device is connected (adb devices)\n• Verify USB debugging is enabled on device",
+            error_msg
+        )
+    } else if error_msg.contains("No device selected") {
+        format!(
+            "{}\n\nPlease:\n• Connect an Android device via USB\n• Enable USB debugging on the device\n• Press 'd' to pick a device when several are attached",
+            error_msg
+        )
+    } else {
+        error_msg
+    }
+}
+
+/// Run `command` on a worker thread, reporting through `sender`
+pub fn spawn_command(
+    manager: &AdbManager,
+    command: AdbCommand,
+    sender: UnboundedSender<Event>,
+) -> CommandJob {
+    let id = next_job_id();
+    let cancel = CancelToken::default();
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let mut manager = manager.for_worker(cancel.clone());
+
+    let job = CommandJob {
+        id,
+        cancel,
+        timed_out: timed_out.clone(),
+    };
+
+    std::thread::spawn(move || {
+        let _ = sender.send(Event::Command(CommandEvent::Started(id)));
+        let result = match manager.execute_output(command) {
+            Ok(output) => CommandResult::Success(output),
+            Err(e) => {
+                timed_out.store(matches!(e, AdbError::Timeout(_)), Ordering::SeqCst);
+                CommandResult::Error(describe_error(e))
+            }
+        };
+        let _ = sender.send(Event::Command(CommandEvent::Finished { id, result }));
+    });
+
+    job
+}
@@ -1,9 +1,22 @@
 //! Integration tests for ADB functionality
 //!
 //! These tests verify the ADB client integration and command execution.
-//! Note: Some tests require an actual ADB server running and a connected device.
-
-use droidtui::adb::{AdbCommand, AdbError, AdbManager, PackageFilter};
+//! Most talk to the fake server in `support`; the ignored ones need an actual ADB
+//! server running and a connected device.
+
+mod support;
+
+use droidtui::adb::Signal;
+use droidtui::adb::{
+    AdbCommand, AdbError, AdbManager, AdbOutput, CancelToken, CommandTimeouts, InstallOptions,
+    PackageFilter, RebootTarget, RecordOptions,
+};
+use droidtui::record;
+use std::net::Ipv6Addr;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::time::Duration;
+use support::{FakeAdbServer, Reply};
 
 #[test]
 fn test_adb_manager_creation() {
@@ -100,6 +113,673 @@ fn test_list_packages_command() {
     }
 }
 
+// Tests below run against the fake ADB server in `support`
+
+/// A fake server with one emulator selected on a fresh manager
+fn fake_device() -> FakeAdbServer {
+    FakeAdbServer::start().with_device("emulator-5554", "sdk_gphone64_x86_64")
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("droidtui-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_fake_server_device_commands() {
+    let server = fake_device();
+    let mut manager = server.manager();
+
+    let version = manager.execute(AdbCommand::GetAdbVersion).unwrap();
+    assert_eq!(
+        version,
+        format!(
+            "ADB server version: 1.0.{} ({})",
+            support::SERVER_VERSION,
+            server.address()
+        )
+    );
+
+    let devices = manager.devices().unwrap();
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].serial, "emulator-5554");
+    assert_eq!(devices[0].model.as_deref(), Some("sdk_gphone64_x86_64"));
+
+    let listing = manager.execute(AdbCommand::ListDevices).unwrap();
+    assert!(listing.contains("emulator-5554\tdevice"));
+    // Listing leaves the selection alone; a sole device is still used implicitly
+    assert_eq!(manager.selected_device(), None);
+    assert_eq!(
+        manager.execute(AdbCommand::GetDeviceState).unwrap(),
+        "Device state: device"
+    );
+    assert_eq!(
+        manager.execute(AdbCommand::GetSerialNumber).unwrap(),
+        "Serial number: emulator-5554"
+    );
+    assert!(server.received("host:devices-l"));
+}
+
+#[test]
+fn test_fake_server_shell_commands() {
+    let server = fake_device()
+        .respond(
+            "dumpsys battery",
+            include_str!("fixtures/dumpsys_battery.txt"),
+        )
+        .respond(
+            "dumpsys meminfo",
+            include_str!("fixtures/dumpsys_meminfo.txt"),
+        )
+        .respond(
+            "dumpsys connectivity",
+            include_str!("fixtures/dumpsys_connectivity.txt"),
+        )
+        .respond("cat /proc/cpuinfo", "processor\t: 0\n")
+        .respond("getprop", "[ro.product.model]: [sdk_gphone64_x86_64]\n")
+        .respond("logcat -d -t 5", "--------- beginning of main\n")
+        .respond("logcat -d -v threadtime", "I ActivityManager: Start proc\n")
+        .respond(
+            "ip addr show wlan0",
+            "inet 10.0.2.16/24 scope global wlan0\n",
+        )
+        .respond("wm size", "Physical size: 1080x2400\n")
+        .respond("wm density", "Physical density: 420\n")
+        .respond("ps", "USER PID PPID NAME\nroot 1 0 init\n")
+        .respond("echo hello", "hello\n");
+    let mut manager = server.manager();
+
+    let cases = [
+        (AdbCommand::GetBatteryInfo, "Current Battery Service state:"),
+        (AdbCommand::GetCpuInfo, "processor"),
+        (AdbCommand::GetDeviceProperties, "ro.product.model"),
+        (AdbCommand::GetSystemLog { lines: 5 }, "beginning of main"),
+        (AdbCommand::StreamLogcat, "ActivityManager"),
+        (AdbCommand::GetWifiStatus, "10.0.2.16"),
+        (
+            AdbCommand::GetScreenResolution,
+            "1080x2400\n\nPhysical density: 420",
+        ),
+        (AdbCommand::ListProcesses, "init"),
+        (
+            AdbCommand::Shell {
+                command: "echo hello".to_string(),
+            },
+            "hello",
+        ),
+    ];
+    for (command, expected) in cases {
+        let output = manager.execute(command.clone()).unwrap();
+        assert!(output.contains(expected), "{:?}: {}", command, output);
+    }
+
+    assert!(matches!(
+        manager.execute_output(AdbCommand::GetBatteryInfo).unwrap(),
+        AdbOutput::Battery { .. }
+    ));
+    assert!(matches!(
+        manager.execute_output(AdbCommand::GetMemoryInfo).unwrap(),
+        AdbOutput::Memory { .. }
+    ));
+    assert!(matches!(
+        manager.execute_output(AdbCommand::GetNetworkInfo).unwrap(),
+        AdbOutput::Connectivity { .. }
+    ));
+    assert_eq!(manager.screen_size().unwrap(), (1080, 2400));
+
+    // Unscripted commands succeed without output
+    assert_eq!(
+        manager
+            .execute(AdbCommand::Shell {
+                command: "true".to_string()
+            })
+            .unwrap(),
+        "Command executed successfully (no output)"
+    );
+    assert!(manager.execute(AdbCommand::Dashboard).is_ok());
+    assert!(manager.execute(AdbCommand::ManageProcesses).is_ok());
+    assert!(server.received("shell,v2,raw:echo hello"));
+}
+
+#[test]
+fn test_fake_server_package_commands() {
+    let server = fake_device()
+        .respond(
+            "pm list packages",
+            "package:com.android.settings\npackage:com.example.app\n",
+        )
+        .respond("pm list packages -3", "package:com.example.app\n")
+        .respond("pm list packages -d", "")
+        .respond(
+            "pm list packages -f",
+            "package:/data/app/base.apk=com.example.app\n\
+             package:/system/app/Settings.apk=com.android.settings\n",
+        )
+        .respond("dumpsys package com.example.app", "versionName=1.2.3\n")
+        .respond("pm uninstall", "Success\n")
+        .respond("pm clear", "Success\n")
+        .respond("pm enable", "Package com.example.app new state: enabled\n")
+        .respond(
+            "pm disable-user",
+            "Package com.example.app new state: disabled-user\n",
+        )
+        .respond("monkey -p com.example.app", "Events injected: 1\n")
+        .respond(
+            "monkey -p com.example.lib",
+            "** No activities found to run\n",
+        );
+    let mut manager = server.manager();
+
+    let list = manager
+        .execute(AdbCommand::ListPackages {
+            include_path: false,
+            filter: PackageFilter::All,
+        })
+        .unwrap();
+    assert!(list.contains("com.android.settings"));
+    let user = manager
+        .execute(AdbCommand::ListPackages {
+            include_path: false,
+            filter: PackageFilter::User,
+        })
+        .unwrap();
+    assert!(!user.contains("com.android.settings"));
+
+    let package = |name: &str| name.to_string();
+    let cases = [
+        (
+            AdbCommand::GetPackageInfo {
+                package_name: package("com.example.app"),
+            },
+            "versionName=1.2.3",
+        ),
+        (
+            AdbCommand::UninstallPackage {
+                package_name: package("com.example.app"),
+            },
+            "Success",
+        ),
+        (
+            AdbCommand::ClearPackageData {
+                package_name: package("com.example.app"),
+            },
+            "Success",
+        ),
+        (
+            AdbCommand::SetPackageEnabled {
+                package_name: package("com.example.app"),
+                enabled: true,
+            },
+            "enabled",
+        ),
+        (
+            AdbCommand::SetPackageEnabled {
+                package_name: package("com.example.app"),
+                enabled: false,
+            },
+            "disabled-user",
+        ),
+        (
+            AdbCommand::LaunchPackage {
+                package_name: package("com.example.app"),
+            },
+            "Launched com.example.app",
+        ),
+        (
+            AdbCommand::ForceStop {
+                package_name: package("com.example.app"),
+            },
+            "no output",
+        ),
+        (AdbCommand::ManagePackages, "/data/app/base.apk"),
+    ];
+    for (command, expected) in cases {
+        let output = manager.execute(command.clone()).unwrap();
+        assert!(output.contains(expected), "{:?}: {}", command, output);
+    }
+
+    let error = manager
+        .execute(AdbCommand::LaunchPackage {
+            package_name: package("com.example.lib"),
+        })
+        .unwrap_err();
+    assert!(error.to_string().contains("no launcher activity"));
+    assert!(server.received("shell,v2,raw:pm disable-user --user 0 com.example.app"));
+}
+
+#[test]
+fn test_fake_server_rejects_package_injection() {
+    let server = fake_device().respond("dumpsys package", "versionName=1.2.3\n");
+    let mut manager = server.manager();
+
+    let name = "com.example.app; reboot".to_string();
+    let commands = [
+        AdbCommand::GetPackageInfo {
+            package_name: name.clone(),
+        },
+        AdbCommand::ForceStop {
+            package_name: name.clone(),
+        },
+        AdbCommand::LaunchPackage { package_name: name },
+    ];
+    for command in commands {
+        let error = manager.execute(command.clone()).unwrap_err();
+        assert!(
+            error.to_string().contains("Invalid package name"),
+            "{:?}: {}",
+            command,
+            error
+        );
+    }
+    assert!(!server.requests().iter().any(|r| r.contains("reboot")));
+}
+
+#[test]
+fn test_fake_server_exit_status() {
+    let server = fake_device()
+        .respond("echo ok", "ok\n")
+        .respond_with(
+            "ls /missing",
+            Reply::failed("ls: /missing: No such file or directory\n", 1),
+        )
+        .respond_with(
+            "pm uninstall",
+            Reply {
+                stdout: b"Failure [DELETE_FAILED_INTERNAL_ERROR]\n".to_vec(),
+                exit_code: 1,
+                ..Reply::default()
+            },
+        );
+    let mut manager = server.manager();
+
+    let output = manager.shell_output("echo ok").unwrap();
+    assert_eq!(output.stdout, "ok\n");
+    assert_eq!(output.exit_code, Some(0));
+
+    // A failing custom command keeps its streams apart
+    let output = manager
+        .execute_output(AdbCommand::Shell {
+            command: "ls /missing".to_string(),
+        })
+        .unwrap();
+    let AdbOutput::Shell { output, raw } = output else {
+        panic!("expected shell output");
+    };
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.stderr, "ls: /missing: No such file or directory\n");
+    assert_eq!(output.exit_code, Some(1));
+    assert_eq!(raw, output.stderr);
+
+    // Built-in commands fail on a non-zero status
+    let error = manager
+        .execute(AdbCommand::UninstallPackage {
+            package_name: "com.example.app".to_string(),
+        })
+        .unwrap_err();
+    assert!(matches!(error, AdbError::ExitStatus { code: 1, .. }));
+    assert!(error.to_string().contains("DELETE_FAILED_INTERNAL_ERROR"));
+    assert!(server.received("host-serial:emulator-5554:features"));
+}
+
+#[test]
+fn test_fake_server_track_devices() {
+    let server = fake_device();
+    let mut manager = server.manager();
+    let cancel = CancelToken::default();
+    let mut worker = manager.for_worker(cancel.clone());
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let tracker = std::thread::spawn(move || {
+        worker.track_devices(&mut |devices| {
+            let _ = sender.send(devices);
+        })
+    });
+    let next = || receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    let devices = next();
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].model.as_deref(), Some("sdk_gphone64_x86_64"));
+
+    server.set_device_state("emulator-5554", "offline");
+    assert_eq!(next()[0].state, "offline");
+    manager.connect_device("192.168.1.20:5555").unwrap();
+    assert_eq!(next()[1].serial, "192.168.1.20:5555");
+    server.unplug("emulator-5554");
+    let devices = next();
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].serial, "192.168.1.20:5555");
+
+    cancel.cancel();
+    assert!(matches!(tracker.join().unwrap(), Err(AdbError::Cancelled)));
+    assert!(server.received("host:track-devices-l"));
+}
+
+#[test]
+fn test_fake_server_timeout() {
+    let server = fake_device()
+        .respond_with(
+            "dumpsys battery",
+            Reply::stdout("level: 80\n").delayed(Duration::from_secs(5)),
+        )
+        .respond("getprop", "[ro.product.model]: [Pixel 8]\n");
+    let mut manager = server.manager();
+    let mut timeouts = CommandTimeouts::default();
+    timeouts
+        .set("get_battery_info", Some(Duration::from_millis(200)))
+        .unwrap();
+    manager.set_timeouts(timeouts);
+
+    let error = manager.execute(AdbCommand::GetBatteryInfo).unwrap_err();
+    assert!(matches!(error, AdbError::Timeout(timeout) if timeout == Duration::from_millis(200)));
+    assert_eq!(error.exit_code(), 11);
+
+    // The manager keeps working after a timeout
+    let output = manager.execute(AdbCommand::GetDeviceProperties).unwrap();
+    assert!(output.contains("Pixel 8"));
+}
+
+#[test]
+fn test_fake_server_install() {
+    let server = fake_device()
+        .respond("pm install", "Success\n")
+        .respond(
+            "pm install-create",
+            "Success: created install session [42]\n",
+        )
+        .respond("pm install-write", "Success: streamed 4 bytes\n")
+        .respond("pm install-commit", "Success\n");
+    let mut manager = server.manager();
+
+    let dir = temp_dir("install");
+    let base = dir.join("base.apk");
+    let split = dir.join("split_config.en.apk");
+    std::fs::write(&base, b"PK\x03\x04base").unwrap();
+    std::fs::write(&split, b"PK\x03\x04").unwrap();
+
+    let output = manager
+        .execute(AdbCommand::InstallPackage {
+            apk_path: base.display().to_string(),
+            options: InstallOptions::default(),
+        })
+        .unwrap();
+    assert_eq!(output, format!("Installed {}", base.display()));
+    assert_eq!(
+        server.file("/data/local/tmp/droidtui-install.apk").unwrap(),
+        b"PK\x03\x04base"
+    );
+    assert!(server.received("shell,v2,raw:pm install \"/data/local/tmp/droidtui-install.apk\""));
+
+    let output = manager
+        .execute(AdbCommand::InstallSplitPackage {
+            apk_paths: vec![base.display().to_string(), split.display().to_string()],
+            options: InstallOptions::default(),
+        })
+        .unwrap();
+    assert_eq!(output, "Installed 2 APKs in session 42");
+    assert!(server.received("shell,v2,raw:pm install-create -S 12"));
+    assert!(server.received("shell,v2,raw:pm install-commit 42"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_fake_server_install_failure() {
+    let server = fake_device().respond_with(
+        "pm install",
+        Reply {
+            stdout: b"Failure [INSTALL_FAILED_OLDER_SDK: Requires newer sdk version]\n".to_vec(),
+            exit_code: 1,
+            ..Reply::default()
+        },
+    );
+    let mut manager = server.manager();
+
+    let dir = temp_dir("install-failure");
+    let apk = dir.join("app.apk");
+    std::fs::write(&apk, b"PK\x03\x04").unwrap();
+
+    let error = manager
+        .execute(AdbCommand::InstallPackage {
+            apk_path: apk.display().to_string(),
+            options: InstallOptions::default(),
+        })
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        AdbError::InstallFailed { ref code, .. } if code == "INSTALL_FAILED_OLDER_SDK"
+    ));
+    // The staged APK is removed either way
+    assert!(server.received("shell,v2,raw:rm -f \"/data/local/tmp/droidtui-install.apk\""));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_fake_server_files() {
+    let server = fake_device()
+        .with_file("/sdcard/DCIM/Camera/IMG_0001.jpg", vec![0xff; 100_000])
+        .with_file("/sdcard/notes.txt", "remember the milk\n");
+    let mut manager = server.manager();
+
+    let listing = manager
+        .execute(AdbCommand::ListDirectory {
+            path: "/sdcard".to_string(),
+        })
+        .unwrap();
+    assert!(listing.starts_with("/sdcard:\n"));
+    assert!(listing.contains("d       4096  DCIM"));
+    assert!(listing.contains("-         18  notes.txt"));
+
+    assert_eq!(manager.file_size("/sdcard/notes.txt").unwrap(), 18);
+
+    // Larger than one sync chunk
+    let mut photo = Vec::new();
+    manager
+        .pull("/sdcard/DCIM/Camera/IMG_0001.jpg", &mut photo)
+        .unwrap();
+    assert_eq!(photo, vec![0xff; 100_000]);
+
+    manager
+        .push(&mut &b"pushed"[..], "/sdcard/Download/pushed.txt")
+        .unwrap();
+    assert_eq!(
+        server.file("/sdcard/Download/pushed.txt").as_deref(),
+        Some(&b"pushed"[..])
+    );
+
+    assert!(manager
+        .pull("/sdcard/missing.txt", &mut Vec::new())
+        .is_err());
+    assert!(server.received("LIST /sdcard"));
+}
+
+#[test]
+fn test_fake_server_ipv6() {
+    // Skip where the sandbox has no IPv6 loopback
+    let Ok(server) = FakeAdbServer::start_on(Ipv6Addr::LOCALHOST.into()) else {
+        return;
+    };
+    let server = server
+        .with_device("emulator-5554", "sdk_gphone64_x86_64")
+        .with_file("/sdcard/notes.txt", "remember the milk\n");
+    let mut manager = server.manager();
+    manager.select_device("emulator-5554".to_string());
+
+    assert!(manager.execute(AdbCommand::GetAdbVersion).is_ok());
+    assert_eq!(manager.devices().unwrap().len(), 1);
+    assert_eq!(manager.file_size("/sdcard/notes.txt").unwrap(), 18);
+
+    let mut notes = Vec::new();
+    manager.pull("/sdcard/notes.txt", &mut notes).unwrap();
+    assert_eq!(notes, b"remember the milk\n");
+    manager
+        .push(&mut &b"pushed"[..], "/sdcard/pushed.txt")
+        .unwrap();
+    assert_eq!(
+        server.file("/sdcard/pushed.txt").as_deref(),
+        Some(&b"pushed"[..])
+    );
+    assert!(manager.execute(AdbCommand::TcpIp { port: 5555 }).is_ok());
+    assert!(server.received("tcpip:5555"));
+}
+
+#[test]
+fn test_fake_server_wireless_commands() {
+    let server = fake_device().respond(
+        "ip -f inet addr show wlan0",
+        "    inet 192.168.1.20/24 brd 192.168.1.255 scope global wlan0\n",
+    );
+    let mut manager = server.manager();
+    manager.select_device("emulator-5554".to_string());
+
+    let output = manager.execute(AdbCommand::TcpIp { port: 5555 }).unwrap();
+    assert!(output.contains("Connect with 192.168.1.20:5555"));
+    assert!(server.received("tcpip:5555"));
+
+    let connected = manager
+        .execute(AdbCommand::Connect {
+            address: "192.168.1.20:5555".to_string(),
+        })
+        .unwrap();
+    assert_eq!(connected, "connected to 192.168.1.20:5555");
+    assert_eq!(manager.devices().unwrap().len(), 2);
+
+    let paired = manager
+        .execute(AdbCommand::Pair {
+            address: "192.168.1.20:37000".to_string(),
+            code: "123456".to_string(),
+        })
+        .unwrap();
+    assert!(paired.starts_with("Successfully paired to 192.168.1.20:37000"));
+    assert!(server.received("host:pair:123456:192.168.1.20:37000"));
+
+    let disconnected = manager
+        .execute(AdbCommand::Disconnect {
+            address: Some("192.168.1.20:5555".to_string()),
+        })
+        .unwrap();
+    assert_eq!(disconnected, "disconnected 192.168.1.20:5555");
+    assert_eq!(
+        manager
+            .execute(AdbCommand::Disconnect { address: None })
+            .unwrap(),
+        "Disconnected everything"
+    );
+    assert!(manager
+        .execute(AdbCommand::Connect {
+            address: String::new()
+        })
+        .is_err());
+}
+
+#[test]
+fn test_fake_server_reboot() {
+    let server = fake_device();
+    let mut manager = server.manager();
+
+    let output = manager
+        .execute(AdbCommand::Reboot {
+            target: RebootTarget::Recovery,
+        })
+        .unwrap();
+    assert_eq!(output, "Rebooting emulator-5554 into recovery");
+    assert!(server.received("reboot:recovery"));
+
+    let output = manager
+        .wait_for_device("emulator-5554", RebootTarget::Recovery)
+        .unwrap();
+    assert_eq!(output, "emulator-5554 is back in recovery mode");
+    assert!(server.received("host-serial:emulator-5554:wait-for-any-recovery"));
+}
+
+#[test]
+fn test_fake_server_screen_commands() {
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png.extend_from_slice(b"IHDR fake image");
+    let server = fake_device()
+        .respond("screencap -p", &png)
+        .respond("screenrecord", [0u8, 0, 0, 1, 0x67, 0x42]);
+    let mut manager = server.manager();
+    let dir = temp_dir("fake-screen");
+    manager.set_screenshot_dir(dir.clone());
+
+    let output = manager.execute(AdbCommand::TakeScreenshot).unwrap();
+    let path = output.strip_prefix("Screenshot saved to ").unwrap();
+    assert_eq!(std::fs::read(path).unwrap(), png);
+    assert!(server.received("exec:screencap -p"));
+
+    // Without ffmpeg the raw stream is kept, so only check the request and the summary
+    let options = RecordOptions {
+        max_duration: Duration::from_secs(1),
+        ..RecordOptions::default()
+    };
+    let result = record::record(&mut manager, &options, &AtomicU64::new(0));
+    assert!(server.received("exec:screenrecord --output-format=h264"));
+    if let Ok(summary) = result {
+        let summary = summary.describe();
+        assert!(summary.starts_with("Recording saved"), "{}", summary);
+    }
+    assert!(manager
+        .execute(AdbCommand::RecordScreen {
+            options: RecordOptions::default()
+        })
+        .is_err());
+
+    assert!(manager.execute(AdbCommand::MirrorScreen).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_fake_server_errors() {
+    let server = fake_device()
+        .with_device("0123456789ABCDEF", "Pixel_7")
+        .respond_with(
+            "kill -KILL 1",
+            Reply::failed("/system/bin/sh: kill: 1: Operation not permitted\n", 1),
+        );
+    let mut manager = server.manager();
+
+    // Two devices and no selection
+    assert!(matches!(
+        manager.execute(AdbCommand::GetBatteryInfo),
+        Err(AdbError::NoDeviceSelected)
+    ));
+
+    manager.select_device("emulator-5554".to_string());
+    let error = manager
+        .execute(AdbCommand::KillProcess {
+            pid: 1,
+            signal: Signal::Kill,
+        })
+        .unwrap_err();
+    assert!(error.to_string().contains("Operation not permitted"));
+
+    manager.select_device("gone".to_string());
+    assert!(manager.execute(AdbCommand::GetBatteryInfo).is_err());
+    assert!(server.received("host:transport:gone"));
+
+    // Read-only mode refuses before reaching the server
+    let requests = server.requests().len();
+    manager.set_read_only(true);
+    assert!(matches!(
+        manager.execute(AdbCommand::Reboot {
+            target: RebootTarget::System
+        }),
+        Err(AdbError::ReadOnly)
+    ));
+    assert_eq!(server.requests().len(), requests);
+
+    let empty = FakeAdbServer::start();
+    assert!(matches!(
+        empty.manager().execute(AdbCommand::GetBatteryInfo),
+        Err(AdbError::DeviceNotFound)
+    ));
+    assert_eq!(
+        empty.manager().execute(AdbCommand::ListDevices).unwrap(),
+        "No devices found.\n\nMake sure:\n- Device is connected via USB\n- USB debugging is enabled\n- Device is authorized"
+    );
+}
+
 // Integration tests below require actual ADB server and device
 // Run with: cargo test --test adb_integration_tests -- --ignored
 
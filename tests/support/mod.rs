@@ -0,0 +1,572 @@
+//! Fake ADB server for integration tests
+//!
+//! Listens on a local port and speaks enough of the host protocol for
+//! [`AdbManager`]: `host:version`, `host:devices[-l]`,
+//! `host:transport:<serial>`, `shell:`, `shell,v2:`, `exec:`, `sync:` and the
+//! wireless requests. Shell commands are answered from scripted replies, files
+//! live in memory and every request is logged for assertions.
+
+use droidtui::adb::{AdbManager, ServerAddress};
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+/// Version reported for `host:version`
+pub const SERVER_VERSION: u32 = 41;
+
+/// Features every fake device reports
+const FEATURES: &str = "shell_v2,cmd,stat_v2,ls_v2";
+
+/// Largest `DATA` chunk of a sync transfer
+const SYNC_CHUNK: usize = 64 * 1024;
+
+/// How often `track-devices` looks for changes
+const TRACK_POLL: Duration = Duration::from_millis(20);
+
+/// Output of a scripted shell command
+#[derive(Debug, Clone, Default)]
+pub struct Reply {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub exit_code: u8,
+    /// How long the device hangs before answering
+    pub delay: Duration,
+}
+
+impl Reply {
+    pub fn stdout(output: impl AsRef<[u8]>) -> Self {
+        Self {
+            stdout: output.as_ref().to_vec(),
+            ..Self::default()
+        }
+    }
+
+    pub fn failed(stderr: &str, exit_code: u8) -> Self {
+        Self {
+            stderr: stderr.as_bytes().to_vec(),
+            exit_code,
+            ..Self::default()
+        }
+    }
+
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FakeDevice {
+    serial: String,
+    state: String,
+    model: String,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    devices: Vec<FakeDevice>,
+    /// Command prefix and reply; the longest matching prefix wins
+    replies: Vec<(String, Reply)>,
+    files: BTreeMap<String, Vec<u8>>,
+    requests: Vec<String>,
+}
+
+/// A fake ADB server running on its own threads until dropped
+#[derive(Debug)]
+pub struct FakeAdbServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<FakeState>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl FakeAdbServer {
+    /// Listen on a free local port
+    pub fn start() -> Self {
+        Self::start_on(Ipv4Addr::LOCALHOST.into()).expect("bind fake ADB server")
+    }
+
+    /// Listen on a free port of `ip`, e.g. `::1`
+    pub fn start_on(ip: IpAddr) -> io::Result<Self> {
+        let listener = TcpListener::bind((ip, 0))?;
+        let addr = listener.local_addr().expect("fake ADB server address");
+        let state = Arc::new(Mutex::new(FakeState::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        {
+            let state = state.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(stream) = stream else {
+                        continue;
+                    };
+                    let state = state.clone();
+                    thread::spawn(move || {
+                        let _ = serve(stream, &state);
+                    });
+                }
+            });
+        }
+
+        Ok(Self { addr, state, stop })
+    }
+
+    /// Attach an online device
+    pub fn with_device(self, serial: &str, model: &str) -> Self {
+        self.lock().devices.push(FakeDevice {
+            serial: serial.to_string(),
+            state: "device".to_string(),
+            model: model.to_string(),
+        });
+        self
+    }
+
+    /// Move an attached device to `state`, e.g. `offline` or `unauthorized`
+    pub fn set_device_state(&self, serial: &str, state: &str) {
+        for device in self.lock().devices.iter_mut() {
+            if device.serial == serial {
+                device.state = state.to_string();
+            }
+        }
+    }
+
+    /// Detach a device
+    pub fn unplug(&self, serial: &str) {
+        self.lock().devices.retain(|device| device.serial != serial);
+    }
+
+    /// Answer shell and exec commands starting with `prefix` with `stdout`
+    pub fn respond(self, prefix: &str, stdout: impl AsRef<[u8]>) -> Self {
+        self.respond_with(prefix, Reply::stdout(stdout))
+    }
+
+    /// Answer commands starting with `prefix` with `reply`
+    pub fn respond_with(self, prefix: &str, reply: Reply) -> Self {
+        self.lock().replies.push((prefix.to_string(), reply));
+        self
+    }
+
+    /// Put a file on every device
+    pub fn with_file(self, path: &str, contents: impl AsRef<[u8]>) -> Self {
+        self.lock()
+            .files
+            .insert(path.to_string(), contents.as_ref().to_vec());
+        self
+    }
+
+    pub fn address(&self) -> ServerAddress {
+        ServerAddress {
+            host: self.addr.ip().to_string(),
+            port: self.addr.port(),
+        }
+    }
+
+    /// A manager talking to this server
+    pub fn manager(&self) -> AdbManager {
+        AdbManager::with_server(self.address()).expect("manager for fake ADB server")
+    }
+
+    /// Requests received so far, e.g. `host:devices-l` or `shell:getprop`
+    pub fn requests(&self) -> Vec<String> {
+        self.lock().requests.clone()
+    }
+
+    /// Whether a request starting with `prefix` was received
+    pub fn received(&self, prefix: &str) -> bool {
+        self.lock()
+            .requests
+            .iter()
+            .any(|request| request.starts_with(prefix))
+    }
+
+    /// Contents of a device file
+    pub fn file(&self, path: &str) -> Option<Vec<u8>> {
+        self.lock().files.get(path).cloned()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FakeState> {
+        lock(&self.state)
+    }
+}
+
+impl Drop for FakeAdbServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+fn lock(state: &Mutex<FakeState>) -> MutexGuard<'_, FakeState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Serve one client connection until it closes or a device service ends
+fn serve(mut stream: TcpStream, state: &Mutex<FakeState>) -> io::Result<()> {
+    let mut transport: Option<String> = None;
+    loop {
+        let request = read_request(&mut stream)?;
+        lock(state).requests.push(request.clone());
+
+        if let Some(serial) = &transport {
+            return serve_device(&mut stream, state, serial, &request);
+        }
+
+        if let Some(serial) = request.strip_prefix("host:transport:") {
+            if has_device(state, serial) {
+                transport = Some(serial.to_string());
+                stream.write_all(b"OKAY")?;
+            } else {
+                fail(&mut stream, &format!("device '{}' not found", serial))?;
+            }
+            continue;
+        }
+        if request == "host:transport-any" {
+            match lock(state).devices.first() {
+                Some(device) => transport = Some(device.serial.clone()),
+                None => return fail(&mut stream, "no devices/emulators found"),
+            }
+            stream.write_all(b"OKAY")?;
+            continue;
+        }
+        return serve_host(&mut stream, state, &request);
+    }
+}
+
+/// Answer a one-shot `host:` request
+fn serve_host(stream: &mut TcpStream, state: &Mutex<FakeState>, request: &str) -> io::Result<()> {
+    if request == "host:version" {
+        return okay_reply(stream, &format!("{:04x}", SERVER_VERSION));
+    }
+    if request == "host:devices" || request == "host:devices-l" {
+        let listing = device_listing(state, request.ends_with("-l"));
+        return okay_reply(stream, &listing);
+    }
+    if request == "host:track-devices" || request == "host:track-devices-l" {
+        return track_devices(stream, state, request.ends_with("-l"));
+    }
+    if request == "host:features" {
+        return okay_reply(stream, FEATURES);
+    }
+    if let Some(address) = request.strip_prefix("host:connect:") {
+        let mut state = lock(state);
+        let reply = if state.devices.iter().any(|device| device.serial == address) {
+            format!("already connected to {}", address)
+        } else {
+            state.devices.push(FakeDevice {
+                serial: address.to_string(),
+                state: "device".to_string(),
+                model: "network".to_string(),
+            });
+            format!("connected to {}", address)
+        };
+        drop(state);
+        return okay_reply(stream, &reply);
+    }
+    if let Some(address) = request.strip_prefix("host:disconnect:") {
+        let mut state = lock(state);
+        let before = state.devices.len();
+        state.devices.retain(|device| match address {
+            "" => !device.serial.contains(':'),
+            address => device.serial != address,
+        });
+        let reply = match address {
+            "" => String::new(),
+            address if state.devices.len() < before => format!("disconnected {}", address),
+            address => format!("no such device '{}'", address),
+        };
+        drop(state);
+        return okay_reply(stream, &reply);
+    }
+    if let Some(rest) = request.strip_prefix("host:pair:") {
+        let address = rest.split_once(':').map_or(rest, |(_, address)| address);
+        return okay_reply(
+            stream,
+            &format!("Successfully paired to {} [guid=adb-fake]", address),
+        );
+    }
+    if let Some(rest) = request.strip_prefix("host-serial:") {
+        if let Some(serial) = rest.strip_suffix(":features") {
+            if has_device(state, serial) {
+                return okay_reply(stream, FEATURES);
+            }
+        }
+        // host-serial:<serial>:wait-for-any-<state>; the serial may contain ':'
+        if let Some((serial, _)) = rest.rsplit_once(":wait-for-") {
+            stream.write_all(b"OKAY")?;
+            if has_device(state, serial) {
+                stream.write_all(b"OKAY")?;
+            }
+            return Ok(());
+        }
+    }
+    fail(stream, &format!("unknown host service '{}'", request))
+}
+
+/// Run a device service on the transport selected for `serial`
+fn serve_device(
+    stream: &mut TcpStream,
+    state: &Mutex<FakeState>,
+    serial: &str,
+    request: &str,
+) -> io::Result<()> {
+    let Some((service, command)) = request.split_once(':') else {
+        return fail(stream, &format!("unknown device service '{}'", request));
+    };
+    match service {
+        "sync" => {
+            stream.write_all(b"OKAY")?;
+            serve_sync(stream, state)
+        }
+        "exec" => {
+            let reply = reply_for(state, command);
+            stream.write_all(b"OKAY")?;
+            thread::sleep(reply.delay);
+            stream.write_all(&reply.stdout)
+        }
+        "reboot" => stream.write_all(b"OKAY"),
+        "tcpip" => {
+            stream.write_all(b"OKAY")?;
+            writeln!(stream, "restarting in TCP mode port: {}", command)
+        }
+        service if service.starts_with("shell") => {
+            let reply = reply_for(state, command);
+            stream.write_all(b"OKAY")?;
+            thread::sleep(reply.delay);
+            if service.split(',').any(|option| option == "v2") {
+                write_shell_v2(stream, &reply)
+            } else {
+                stream.write_all(&reply.stdout)?;
+                stream.write_all(&reply.stderr)
+            }
+        }
+        _ => fail(
+            stream,
+            &format!("unknown device service '{}' on {}", request, serial),
+        ),
+    }
+}
+
+/// Write stdout, stderr and the exit code as shell protocol v2 packets
+fn write_shell_v2(stream: &mut TcpStream, reply: &Reply) -> io::Result<()> {
+    for (id, data) in [(1u8, &reply.stdout), (2u8, &reply.stderr)] {
+        if !data.is_empty() {
+            stream.write_all(&[id])?;
+            stream.write_all(&(data.len() as u32).to_le_bytes())?;
+            stream.write_all(data)?;
+        }
+    }
+    stream.write_all(&[3])?;
+    stream.write_all(&1u32.to_le_bytes())?;
+    stream.write_all(&[reply.exit_code])
+}
+
+/// Serve sync requests until `QUIT` or the client closes
+fn serve_sync(stream: &mut TcpStream, state: &Mutex<FakeState>) -> io::Result<()> {
+    loop {
+        let mut id = [0u8; 4];
+        if stream.read_exact(&mut id).is_err() {
+            return Ok(());
+        }
+        if &id == b"QUIT" {
+            return Ok(());
+        }
+        let len = read_u32(stream)? as usize;
+        let mut payload = vec![0u8; len];
+        stream.read_exact(&mut payload)?;
+        let path = String::from_utf8_lossy(&payload).to_string();
+        lock(state)
+            .requests
+            .push(format!("{} {}", String::from_utf8_lossy(&id), path));
+
+        match &id {
+            b"LIST" => {
+                for (name, mode, size) in list_dir(&lock(state).files, &path) {
+                    stream.write_all(b"DENT")?;
+                    for value in [mode, size, 0, name.len() as u32] {
+                        stream.write_all(&value.to_le_bytes())?;
+                    }
+                    stream.write_all(name.as_bytes())?;
+                }
+                stream.write_all(b"DONE")?;
+                stream.write_all(&[0u8; 16])?;
+            }
+            b"STAT" => {
+                let (mode, size) = match lock(state).files.get(&path) {
+                    Some(contents) => (0o100644, contents.len() as u32),
+                    None => (0, 0),
+                };
+                stream.write_all(b"STAT")?;
+                for value in [mode, size, 0] {
+                    stream.write_all(&value.to_le_bytes())?;
+                }
+            }
+            b"RECV" => {
+                let contents = lock(state).files.get(&path).cloned();
+                let Some(contents) = contents else {
+                    let message = "No such file or directory";
+                    stream.write_all(b"FAIL")?;
+                    stream.write_all(&(message.len() as u32).to_le_bytes())?;
+                    return stream.write_all(message.as_bytes());
+                };
+                for chunk in contents.chunks(SYNC_CHUNK) {
+                    stream.write_all(b"DATA")?;
+                    stream.write_all(&(chunk.len() as u32).to_le_bytes())?;
+                    stream.write_all(chunk)?;
+                }
+                stream.write_all(b"DONE")?;
+                stream.write_all(&0u32.to_le_bytes())?;
+            }
+            b"SEND" => {
+                // The path carries the mode after a comma
+                let path = path
+                    .rsplit_once(',')
+                    .map_or(path.as_str(), |(path, _)| path);
+                let contents = receive_file(stream)?;
+                lock(state).files.insert(path.to_string(), contents);
+                stream.write_all(b"OKAY")?;
+                stream.write_all(&0u32.to_le_bytes())?;
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Read `DATA` chunks up to `DONE <mtime>`
+fn receive_file(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    loop {
+        let mut id = [0u8; 4];
+        stream.read_exact(&mut id)?;
+        let len = read_u32(stream)? as usize;
+        match &id {
+            b"DATA" => {
+                let mut chunk = vec![0u8; len];
+                stream.read_exact(&mut chunk)?;
+                contents.extend_from_slice(&chunk);
+            }
+            b"DONE" => return Ok(contents),
+            other => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected {:?} in SEND", String::from_utf8_lossy(other)),
+                ))
+            }
+        }
+    }
+}
+
+/// Entries directly below `path`: name, mode and size; directories are implied by files
+fn list_dir(files: &BTreeMap<String, Vec<u8>>, path: &str) -> Vec<(String, u32, u32)> {
+    let prefix = format!("{}/", path.trim_end_matches('/'));
+    let mut entries: Vec<(String, u32, u32)> = Vec::new();
+    for (file, contents) in files {
+        let Some(rest) = file.strip_prefix(&prefix) else {
+            continue;
+        };
+        let entry = match rest.split_once('/') {
+            Some((dir, _)) => (dir.to_string(), 0o040771, 4096),
+            None => (rest.to_string(), 0o100660, contents.len() as u32),
+        };
+        if !entries.iter().any(|(name, _, _)| *name == entry.0) {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+fn reply_for(state: &Mutex<FakeState>, command: &str) -> Reply {
+    lock(state)
+        .replies
+        .iter()
+        .filter(|(prefix, _)| command.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, reply)| reply.clone())
+        .unwrap_or_default()
+}
+
+fn has_device(state: &Mutex<FakeState>, serial: &str) -> bool {
+    lock(state)
+        .devices
+        .iter()
+        .any(|device| device.serial == serial)
+}
+
+fn read_request(stream: &mut TcpStream) -> io::Result<String> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let len = usize::from_str_radix(&String::from_utf8_lossy(&len), 16)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body)?;
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+fn read_u32(stream: &mut TcpStream) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    stream.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+/// `OKAY` followed by a length-prefixed reply
+fn okay_reply(stream: &mut TcpStream, reply: &str) -> io::Result<()> {
+    write!(stream, "OKAY{:04x}{}", reply.len(), reply)
+}
+
+/// `host:devices` output, with model details when `long`
+fn device_listing(state: &Mutex<FakeState>, long: bool) -> String {
+    lock(state)
+        .devices
+        .iter()
+        .enumerate()
+        .map(|(index, device)| match long {
+            true => format!(
+                "{:<22} {} usb:1-{} product:{} model:{} device:{} transport_id:{}\n",
+                device.serial,
+                device.state,
+                index + 1,
+                device.model,
+                device.model,
+                device.model,
+                index + 1
+            ),
+            false => format!("{}\t{}\n", device.serial, device.state),
+        })
+        .collect()
+}
+
+/// Send the device list, then again whenever it changes, until the client hangs up
+fn track_devices(stream: &mut TcpStream, state: &Mutex<FakeState>, long: bool) -> io::Result<()> {
+    stream.write_all(b"OKAY")?;
+    stream.set_read_timeout(Some(TRACK_POLL))?;
+    let mut sent = None;
+    loop {
+        let listing = device_listing(state, long);
+        if sent.as_ref() != Some(&listing) {
+            write!(stream, "{:04x}{}", listing.len(), listing)?;
+            sent = Some(listing);
+        }
+        // Waits for the next check, noticing when the client closes the connection
+        match stream.read(&mut [0u8; 1]) {
+            Ok(0) => return Ok(()),
+            Ok(_) => {}
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn fail(stream: &mut TcpStream, message: &str) -> io::Result<()> {
+    write!(stream, "FAIL{:04x}{}", message.len(), message)
+}